@@ -0,0 +1,245 @@
+//! Full-account JSON backup and restore.
+//!
+//! `Backup::fetch` downloads every project and the Inbox, with their columns, tasks and subtasks. The archive is plain JSON tagged with
+//! `SCHEMA_VERSION`, so it can be stored anywhere and restored into the same or another account.
+//! ```ignore
+//! let backup = Backup::fetch(&ticktick).await?;
+//! std::fs::write("ticktick-backup.json", backup.to_json())?;
+//!
+//! let backup = Backup::from_json(&std::fs::read_to_string("ticktick-backup.json")?)?;
+//! let report = backup.restore(&ticktick).await;
+//! for failure in &report.failures {
+//!     eprintln!("{}: {}", failure.id, failure.error);
+//! }
+//! ```
+//! The Inbox is archived as a project with the ID `inbox`, and its tasks are restored into the Inbox of the account.
+//! The Open API only returns open tasks, so completed tasks aren't included.
+//! Columns are archived, but can't be recreated through the Open API when restoring.
+use std::collections::HashMap;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    builders::TaskBuilder,
+    projects::{Column, Project, ProjectID},
+    tasks::{Task, TaskID},
+    ticktick_datetime_format, TickTick, TickTickError,
+};
+
+/// Version of the archive format written by `Backup::to_json`.
+pub const SCHEMA_VERSION: u32 = 1;
+/// ID the Open API accepts for the Inbox, which isn't listed with the other projects.
+const INBOX_ID: &str = "inbox";
+
+/// Errors that can occur while reading a backup archive.
+#[derive(Debug)]
+pub enum BackupError {
+    ParseError(serde_json::Error),
+    /// The archive was written by a newer version of this crate.
+    UnsupportedVersion(u32),
+}
+
+impl From<serde_json::Error> for BackupError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ParseError(value)
+    }
+}
+
+impl std::fmt::Display for BackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ParseError(err) => write!(f, "Invalid backup archive: {}", err),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Backup schema version {} is not supported, expected {} or lower",
+                version, SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+/// Backup of every project in an account.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub schema_version: u32,
+    #[serde(with = "ticktick_datetime_format")]
+    pub created_time: DateTime<Utc>,
+    pub projects: Vec<ProjectBackup>,
+}
+
+/// A project with its columns and tasks. Subtasks are kept on their tasks.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBackup {
+    pub project: Project,
+    pub columns: Vec<Column>,
+    pub tasks: Vec<Task>,
+}
+
+/// Project or task that failed to be restored.
+#[derive(Debug)]
+pub struct RestoreFailure {
+    /// ID of the project or task in the backup.
+    pub id: String,
+    pub error: TickTickError,
+}
+
+/// Result of `Backup::restore`.
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// Project IDs in the backup, mapped to the IDs of the restored projects.
+    pub project_ids: HashMap<ProjectID, ProjectID>,
+    /// Task IDs in the backup, mapped to the IDs of the restored tasks.
+    pub task_ids: HashMap<TaskID, TaskID>,
+    pub failures: Vec<RestoreFailure>,
+}
+
+impl Backup {
+    /// Download every project and the Inbox, with their columns and open tasks.
+    pub async fn fetch(ticktick: &TickTick) -> Result<Self, TickTickError> {
+        let mut projects = Vec::new();
+        for project in ticktick.projects().get_all().await? {
+            let data = ticktick.projects().get_data(project.get_id()).await?;
+            projects.push(ProjectBackup {
+                project,
+                columns: data.columns,
+                tasks: data.tasks,
+            });
+        }
+        let inbox = ProjectID(INBOX_ID.into());
+        match ticktick.projects().get_data(&inbox).await {
+            Ok(data) => projects.push(ProjectBackup {
+                project: Project {
+                    id: inbox,
+                    name: "Inbox".into(),
+                    ..Default::default()
+                },
+                columns: data.columns,
+                tasks: data.tasks,
+            }),
+            // Some servers don't expose the Inbox as a project.
+            Err(TickTickError::StatusError { status: 404, .. }) => {}
+            Err(error) => return Err(error),
+        }
+        Ok(Self {
+            schema_version: SCHEMA_VERSION,
+            // TickTick's datetime format keeps milliseconds.
+            created_time: Utc::now().trunc_subsecs(3),
+            projects,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Backup is always serializable")
+    }
+
+    /// Read an archive written by `to_json`, rejecting ones from newer schema versions.
+    pub fn from_json(json: &str) -> Result<Self, BackupError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Version {
+            schema_version: u32,
+        }
+        let version: Version = serde_json::from_str(json)?;
+        if version.schema_version > SCHEMA_VERSION {
+            return Err(BackupError::UnsupportedVersion(version.schema_version));
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Restore every project and task in the backup, continuing past failures.
+    ///
+    /// Projects that still exist with the same ID are reused, and their tasks that still exist are skipped, so
+    /// restoring into the account a backup was taken from only recreates what's missing. Everything else is created
+    /// with a new ID, see `RestoreReport` for the mapping. Tasks of a project that failed to be created are skipped.
+    /// Inbox tasks are restored into the Inbox.
+    pub async fn restore(&self, ticktick: &TickTick) -> RestoreReport {
+        let mut report = RestoreReport::default();
+        let existing_projects = match ticktick.projects().get_all().await {
+            Ok(projects) => projects,
+            Err(error) => {
+                report.failures.push(RestoreFailure {
+                    id: String::new(),
+                    error,
+                });
+                return report;
+            }
+        };
+        for backup in &self.projects {
+            let old_id = backup.project.get_id();
+            let is_inbox = old_id.0 == INBOX_ID;
+            let mut existing_tasks = Vec::new();
+            let new_id = if is_inbox
+                || existing_projects
+                    .iter()
+                    .any(|project| project.get_id() == old_id)
+            {
+                match ticktick.projects().get_data(old_id).await {
+                    Ok(data) => existing_tasks = data.tasks,
+                    Err(TickTickError::StatusError { status: 404, .. }) if is_inbox => {}
+                    Err(error) => {
+                        report.failures.push(RestoreFailure {
+                            id: old_id.0.clone(),
+                            error,
+                        });
+                        continue;
+                    }
+                }
+                old_id.clone()
+            } else {
+                let project = &backup.project;
+                let mut builder = ticktick
+                    .projects()
+                    .builder(&project.name)
+                    .sort_order(project.sort_order)
+                    .view_mode(project.view_mode)
+                    .kind(project.kind);
+                if !project.color.is_empty() {
+                    builder = builder.color(&project.color);
+                }
+                match builder.build_and_publish().await {
+                    Ok(created) => created.get_id().clone(),
+                    Err(error) => {
+                        report.failures.push(RestoreFailure {
+                            id: old_id.0.clone(),
+                            error,
+                        });
+                        continue;
+                    }
+                }
+            };
+            report.project_ids.insert(old_id.clone(), new_id.clone());
+            for task in &backup.tasks {
+                if existing_tasks
+                    .iter()
+                    .any(|existing| existing.get_id() == task.get_id())
+                {
+                    report
+                        .task_ids
+                        .insert(task.get_id().clone(), task.get_id().clone());
+                    continue;
+                }
+                let result = TaskBuilder::from_task(ticktick, task)
+                    .project_id(new_id.clone())
+                    .build_and_publish()
+                    .await;
+                match result {
+                    Ok(created) => {
+                        report
+                            .task_ids
+                            .insert(task.get_id().clone(), created.get_id().clone());
+                    }
+                    Err(error) => report.failures.push(RestoreFailure {
+                        id: task.get_id().0.clone(),
+                        error,
+                    }),
+                }
+            }
+        }
+        report
+    }
+}
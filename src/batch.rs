@@ -0,0 +1,348 @@
+//! Bulk task operations with per-operation results.
+//!
+//! `TickTick::batch` collects operations and runs them with bounded concurrency, retrying transient failures, so one
+//! failing operation doesn't stop the rest:
+//! ```ignore
+//! let report = ticktick
+//!     .batch()
+//!     .complete(done_task)
+//!     .move_task(task, archive_id)
+//!     .operations(stale_tasks.into_iter().map(BatchOperation::Delete))
+//!     .concurrency(8)
+//!     .execute()
+//!     .await;
+//! for result in report.failures() {
+//!     eprintln!("Operation {} failed: {}", result.index, result.outcome.as_ref().unwrap_err());
+//! }
+//! ```
+//! Network errors, rate limiting (`429`) and server errors (`5xx`) are retried with exponential backoff, other errors
+//! fail the operation straight away. Creates are only retried when rate limited, as the task may have been created
+//! even though the request failed. Likewise a delete may have gone through, so a `404` on a retried delete counts as
+//! success.
+use std::time::Duration;
+
+use futures_util::{stream, StreamExt};
+
+use crate::{
+    projects::ProjectID,
+    tasks::{Task, TaskID},
+    TickTick, TickTickError,
+};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+const DEFAULT_RETRIES: u32 = 2;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// An operation in a batch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchOperation {
+    /// Create a copy of the task, see `Tasks::create`.
+    Create(Task),
+    Update(Task),
+    Complete(Task),
+    Delete(Task),
+    /// Move the task to another project. Sent as an update of the task with `project_id` changed, so any other
+    /// changes made to `task` are saved too.
+    Move {
+        task: Task,
+        project_id: ProjectID,
+    },
+}
+
+impl BatchOperation {
+    /// The task the operation applies to.
+    pub fn task(&self) -> &Task {
+        match self {
+            Self::Create(task)
+            | Self::Update(task)
+            | Self::Complete(task)
+            | Self::Delete(task)
+            | Self::Move { task, .. } => task,
+        }
+    }
+}
+
+/// Result of one operation in a batch.
+#[derive(Debug)]
+pub struct OperationResult {
+    /// Position of the operation in the batch.
+    pub index: usize,
+    /// ID of the task the operation was given, empty for new tasks.
+    pub task_id: TaskID,
+    /// The task as stored by TickTick for creates, updates and moves, `None` for completes and deletes.
+    pub outcome: Result<Option<Task>, TickTickError>,
+    /// Number of times the operation was sent.
+    pub attempts: u32,
+}
+
+/// Results of `Batch::execute`, in the order operations were added.
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub results: Vec<OperationResult>,
+}
+
+impl BatchReport {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|result| result.outcome.is_ok())
+    }
+    pub fn succeeded(&self) -> impl Iterator<Item = &OperationResult> {
+        self.results.iter().filter(|result| result.outcome.is_ok())
+    }
+    pub fn failures(&self) -> impl Iterator<Item = &OperationResult> {
+        self.results.iter().filter(|result| result.outcome.is_err())
+    }
+}
+
+/// Builder for a batch of task operations. Call `execute` to run it.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    ticktick: TickTick,
+    operations: Vec<BatchOperation>,
+    concurrency: usize,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+impl TickTick {
+    /// Start a batch of task operations.
+    pub fn batch(&self) -> Batch {
+        Batch::new(self)
+    }
+}
+
+impl Batch {
+    pub fn new(ticktick: &TickTick) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            operations: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+            retries: DEFAULT_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        }
+    }
+    pub fn operation(mut self, operation: BatchOperation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+    pub fn operations(mut self, operations: impl IntoIterator<Item = BatchOperation>) -> Self {
+        self.operations.extend(operations);
+        self
+    }
+    pub fn create(self, task: Task) -> Self {
+        self.operation(BatchOperation::Create(task))
+    }
+    pub fn update(self, task: Task) -> Self {
+        self.operation(BatchOperation::Update(task))
+    }
+    pub fn complete(self, task: Task) -> Self {
+        self.operation(BatchOperation::Complete(task))
+    }
+    pub fn delete(self, task: Task) -> Self {
+        self.operation(BatchOperation::Delete(task))
+    }
+    pub fn move_task(self, task: Task, project_id: ProjectID) -> Self {
+        self.operation(BatchOperation::Move { task, project_id })
+    }
+    /// Maximum number of operations in flight at once. Defaults to 4.
+    pub fn concurrency(mut self, value: usize) -> Self {
+        self.concurrency = value.max(1);
+        self
+    }
+    /// Number of times a transient failure is retried. Defaults to 2.
+    pub fn retries(mut self, value: u32) -> Self {
+        self.retries = value;
+        self
+    }
+    /// Delay before the first retry, doubled for each retry after it. Defaults to 500ms.
+    pub fn retry_delay(mut self, value: Duration) -> Self {
+        self.retry_delay = value;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Run every operation, continuing past failures.
+    pub async fn execute(self) -> BatchReport {
+        let this = &self;
+        let mut results: Vec<OperationResult> = stream::iter(self.operations.iter().enumerate())
+            .map(|(index, operation)| this.run(index, operation))
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        results.sort_by_key(|result| result.index);
+        BatchReport { results }
+    }
+
+    async fn run(&self, index: usize, operation: &BatchOperation) -> OperationResult {
+        let mut attempts = 0;
+        let mut delay = self.retry_delay;
+        loop {
+            attempts += 1;
+            let outcome = match self.send(operation).await {
+                // An earlier attempt deleted the task, but its response was lost.
+                Err(TickTickError::StatusError { status: 404, .. })
+                    if attempts > 1 && matches!(operation, BatchOperation::Delete(_)) =>
+                {
+                    Ok(None)
+                }
+                outcome => outcome,
+            };
+            match outcome {
+                Err(error) if attempts <= self.retries && is_retryable(operation, &error) => {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                outcome => {
+                    return OperationResult {
+                        index,
+                        task_id: operation.task().get_id().clone(),
+                        outcome,
+                        attempts,
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send(&self, operation: &BatchOperation) -> Result<Option<Task>, TickTickError> {
+        let tasks = self.ticktick.tasks();
+        match operation {
+            BatchOperation::Create(task) => tasks.create(task).await.map(Some),
+            BatchOperation::Update(task) => tasks.update(task).await.map(Some),
+            BatchOperation::Complete(task) => tasks.complete(task).await.map(|_| None),
+            BatchOperation::Delete(task) => tasks.delete(task).await.map(|_| None),
+            BatchOperation::Move { task, project_id } => {
+                let mut task = task.clone();
+                task.project_id = project_id.clone();
+                tasks.update(&task).await.map(Some)
+            }
+        }
+    }
+}
+
+/// Whether `operation` can safely be sent again after failing with `error`.
+fn is_retryable(operation: &BatchOperation, error: &TickTickError) -> bool {
+    if let BatchOperation::Create(_) = operation {
+        return matches!(error, TickTickError::StatusError { status: 429, .. });
+    }
+    match error {
+        TickTickError::ClientError(_) | TickTickError::TransportError(_) => true,
+        TickTickError::StatusError { status, .. } => *status == 429 || *status >= 500,
+        TickTickError::ResponseParseError(_) => false,
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        testing::ScriptedTransport,
+        transport::{HttpMethod, HttpResponse},
+        AccessToken,
+    };
+
+    fn batch(transport: &Arc<ScriptedTransport>) -> Batch {
+        TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        )
+        .batch()
+        .concurrency(1)
+        .retry_delay(Duration::ZERO)
+    }
+
+    fn task(id: &str) -> Task {
+        serde_json::from_value(serde_json::json!({ "id": id, "projectId": "p1", "title": id }))
+            .unwrap()
+    }
+
+    fn error(status: u16) -> HttpResponse {
+        HttpResponse::new(status, "{}")
+    }
+
+    #[tokio::test]
+    async fn retried_delete_of_missing_task_succeeds() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .expect(HttpMethod::Delete, "/project/p1/task/t1", error(503))
+            .expect(HttpMethod::Delete, "/project/p1/task/t1", error(404))
+            .expect(HttpMethod::Delete, "/project/p1/task/t2", error(404));
+        let report = batch(&transport)
+            .delete(task("t1"))
+            .delete(task("t2"))
+            .execute()
+            .await;
+        let [deleted, missing] = &report.results[..] else {
+            panic!("expected two results");
+        };
+        assert!(matches!(deleted.outcome, Ok(None)));
+        assert_eq!(deleted.attempts, 2);
+        assert!(matches!(
+            missing.outcome,
+            Err(TickTickError::StatusError { status: 404, .. })
+        ));
+        assert_eq!(missing.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn move_updates_project_id() {
+        let transport = Arc::new(ScriptedTransport::new());
+        let mut moved = task("t1");
+        moved.project_id = ProjectID("p2".into());
+        transport.push_json(200, &moved);
+        let mut edited = task("t1");
+        edited.title = "Renamed".into();
+        let report = batch(&transport)
+            .move_task(edited, ProjectID("p2".into()))
+            .execute()
+            .await;
+        assert!(report.is_success());
+        let request = &transport.requests()[0];
+        assert_eq!(request.method, HttpMethod::Post);
+        assert!(request.url.ends_with("/task/t1"));
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["projectId"], "p2");
+        assert_eq!(body["title"], "Renamed");
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_only() {
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            // Update: retried on 500 until retries run out.
+            .push(error(500))
+            .push(error(500))
+            .push(error(500))
+            // Create: not retried on 500.
+            .push(error(500))
+            // Complete: not retried on 400.
+            .push(error(400));
+        let report = batch(&transport)
+            .update(task("t1"))
+            .create(task("t2"))
+            .complete(task("t3"))
+            .execute()
+            .await;
+        let attempts: Vec<u32> = report
+            .results
+            .iter()
+            .map(|result| result.attempts)
+            .collect();
+        assert_eq!(attempts, [3, 1, 1]);
+        assert_eq!(report.failures().count(), 3);
+        assert_eq!(transport.remaining(), 0);
+    }
+}
@@ -0,0 +1,50 @@
+use std::{fs, path::PathBuf};
+
+use ticks::AccessToken;
+
+/// Environment variable holding an access token, takes precedence over the saved token.
+pub const TOKEN_ENV_VAR: &str = "TICKS_ACCESS_TOKEN";
+
+/// Directory holding the saved access token, e.g. `~/.config/ticks`.
+pub fn config_dir() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join("ticks"))
+        .ok_or_else(|| "Could not determine config directory".to_string())
+}
+
+fn token_path() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join("token.json"))
+}
+
+pub fn load_token() -> Result<AccessToken, String> {
+    if let Ok(value) = std::env::var(TOKEN_ENV_VAR) {
+        return Ok(AccessToken {
+            value,
+            token_type: "bearer".into(),
+            expires_in: 0,
+            scope: "tasks:read tasks:write".into(),
+        });
+    }
+    let path = token_path()?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|_| format!("Not logged in. Run `ticks login` or set {}.", TOKEN_ENV_VAR))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Invalid token file {}: {}", path.display(), err))
+}
+
+pub fn save_token(token: &AccessToken) -> Result<PathBuf, String> {
+    let path = token_path()?;
+    fs::create_dir_all(config_dir()?).map_err(|err| err.to_string())?;
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(token).map_err(|err| err.to_string())?,
+    )
+    .map_err(|err| err.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,1085 @@
+//! `ticks` command-line client. Built with the `cli` feature, `ticks tui` requires the `tui` feature.
+mod config;
+mod output;
+#[cfg(feature = "tui")]
+mod tui;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use output::OutputFormat;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+use ticks::{
+    backup::Backup,
+    builders::TaskBuilder,
+    eisenhower::EisenhowerMatrix,
+    filter::Filter,
+    markdown::{MarkdownExporter, MarkdownImporter},
+    projects::{Project, ProjectID},
+    quick_add,
+    tasks::{Task, TaskID, TaskPriority, TaskStatus},
+    templates::Template,
+    todotxt, Authorization, TickTick,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "ticks",
+    version,
+    about = "Manage TickTick tasks from the command line"
+)]
+struct Cli {
+    /// Output format
+    #[arg(long, short, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+    /// Shorthand for `--output json`
+    #[arg(long, global = true)]
+    json: bool,
+    /// Send API requests somewhere other than https://ticktick.com
+    #[arg(long, global = true, env = "TICKS_BASE_URL", hide = true)]
+    base_url: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Authorize with TickTick and save the access token
+    Login {
+        #[arg(long, env = "TICKTICK_CLIENT_ID")]
+        client_id: String,
+        #[arg(long, env = "TICKTICK_CLIENT_SECRET", hide_env_values = true)]
+        client_secret: String,
+        /// Must match the redirect URI registered in the TickTick Developer Center
+        #[arg(
+            long,
+            env = "TICKTICK_REDIRECT_URI",
+            default_value = "http://localhost:8080"
+        )]
+        redirect_uri: String,
+    },
+    /// List projects
+    Projects,
+    /// List open tasks, in every project and the Inbox, or a single project
+    Ls {
+        /// Project ID or name
+        project: Option<String>,
+        /// Only list tasks matching a filter, e.g. "priority>=medium and due<=today+3d", see the `filter` module docs
+        #[arg(long, short)]
+        filter: Option<Filter>,
+    },
+    /// Create a task
+    Add {
+        title: String,
+        /// Project ID or name, defaults to the Inbox
+        #[arg(long, short)]
+        project: Option<String>,
+        /// Due date, `YYYY-MM-DD` for all-day tasks or `YYYY-MM-DD HH:MM` (local time)
+        #[arg(long, short)]
+        due: Option<String>,
+        /// none, low, medium or high
+        #[arg(long)]
+        priority: Option<TaskPriority>,
+        /// Comma separated list of tags
+        #[arg(long, short, value_delimiter = ',')]
+        tags: Vec<String>,
+        #[arg(long)]
+        content: Option<String>,
+        /// Parse dates, repeats, `#tags`, `!priority` and `^project` out of the title, e.g. "Pay rent tomorrow 9am
+        /// #finance !!". Options given explicitly take precedence.
+        #[arg(long, short)]
+        quick: bool,
+    },
+    /// Mark a task as completed
+    Done {
+        id: String,
+        /// Project ID or name, searched across all projects and the Inbox if omitted
+        #[arg(long, short)]
+        project: Option<String>,
+    },
+    /// Delete a task
+    Rm {
+        id: String,
+        /// Project ID or name, searched across all projects and the Inbox if omitted
+        #[arg(long, short)]
+        project: Option<String>,
+    },
+    /// Save every project and the Inbox, with their columns and tasks, to a JSON archive
+    Backup {
+        /// Archive path, printed to stdout if omitted
+        file: Option<PathBuf>,
+    },
+    /// Restore projects and tasks from a JSON archive
+    Restore { file: PathBuf },
+    /// Mirror a project to a todo.txt file. Edits made to the file are sent to TickTick, then the file is rewritten
+    /// with the project's open tasks. Mark lines done with `x` instead of deleting them.
+    Mirror {
+        /// Project ID or name
+        project: String,
+        file: PathBuf,
+    },
+    /// Print projects as Markdown, e.g. for status reports
+    Markdown {
+        /// Project IDs or names, all projects if omitted
+        projects: Vec<String>,
+        /// Template of each task's line, see the `markdown` module docs for placeholders
+        #[arg(long)]
+        task_template: Option<String>,
+        /// Leave out task content and descriptions
+        #[arg(long)]
+        no_content: bool,
+    },
+    /// Create tasks from a Markdown checklist. Headings select or create projects, nested items become subtasks and
+    /// `#tag`, `!high` and `due:YYYY-MM-DD` tokens set tags, priority and due date.
+    ImportMarkdown {
+        file: PathBuf,
+        /// Project ID or name for items before the first heading, defaults to the Inbox
+        #[arg(long, short)]
+        project: Option<String>,
+        /// Print the tasks that would be created without creating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create tasks from a JSON or TOML template, see the `templates` module docs for the format
+    Template {
+        file: PathBuf,
+        /// Project ID or name, defaults to the Inbox
+        #[arg(long, short, conflicts_with = "create_project")]
+        project: Option<String>,
+        /// Create a project from the template's name for the tasks
+        #[arg(long)]
+        create_project: bool,
+        /// Template variable, as `name=value`
+        #[arg(long = "var", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+        /// Print the tasks that would be created without creating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Sort open tasks into an Eisenhower matrix by priority and due date
+    Matrix {
+        /// Project ID or name, all projects and the Inbox if omitted
+        project: Option<String>,
+        /// Lowest priority of important tasks
+        #[arg(long, default_value = "medium")]
+        important: TaskPriority,
+        /// Tasks due within this many days, or overdue, are urgent
+        #[arg(long, default_value_t = 1)]
+        urgent_days: i64,
+    },
+    /// List tags with the number of open tasks using them, or edit a tag on every task
+    Tags {
+        #[command(subcommand)]
+        command: Option<TagsCommand>,
+    },
+    /// List a smart view, grouped by day: today, tomorrow, next7days (or week) or overdue
+    #[cfg(feature = "views")]
+    View { view: ticks::views::SmartView },
+    /// Browse and edit tasks interactively
+    #[cfg(feature = "tui")]
+    Tui,
+}
+
+#[derive(Subcommand)]
+enum TagsCommand {
+    /// Rename a tag on every task
+    Rename {
+        from: String,
+        to: String,
+        /// Print the tasks that would change without updating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Replace one or more tags with another on every task
+    Merge {
+        #[arg(required = true)]
+        sources: Vec<String>,
+        /// Tag that replaces the others
+        #[arg(long)]
+        into: String,
+        /// Print the tasks that would change without updating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove a tag from every task
+    Rm {
+        tag: String,
+        /// Print the tasks that would change without updating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli).await {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let format = if cli.json {
+        OutputFormat::Json
+    } else {
+        cli.output
+    };
+    if let Command::Login {
+        client_id,
+        client_secret,
+        redirect_uri,
+    } = cli.command
+    {
+        return login(client_id, client_secret, redirect_uri, cli.base_url).await;
+    }
+    let mut ticktick = TickTick::new(config::load_token()?).map_err(|err| err.to_string())?;
+    if let Some(base_url) = &cli.base_url {
+        ticktick = ticktick.with_base_url(base_url);
+    }
+    match cli.command {
+        Command::Login { .. } => unreachable!(),
+        Command::Projects => {
+            let projects = ticktick
+                .projects()
+                .get_all()
+                .await
+                .map_err(|err| err.to_string())?;
+            output::print_projects(&projects, format)
+        }
+        Command::Ls { project, filter } => {
+            let mut tasks = match project {
+                Some(project) => {
+                    let project_id = resolve_project(&ticktick, &project).await?;
+                    ticktick
+                        .projects()
+                        .get_data(&project_id)
+                        .await
+                        .map_err(|err| err.to_string())?
+                        .tasks
+                }
+                None => ticktick
+                    .tasks()
+                    .get_all_with_inbox()
+                    .await
+                    .map_err(|err| err.to_string())?,
+            };
+            if let Some(filter) = filter {
+                tasks.retain(|task| filter.matches(task));
+            }
+            output::print_tasks(&tasks, format)
+        }
+        Command::Add {
+            title,
+            project,
+            due,
+            priority,
+            tags,
+            content,
+            quick,
+        } => {
+            let mut builder = if quick {
+                quick_add::parse(&title)
+                    .into_builder(&ticktick)
+                    .await
+                    .map_err(|err| err.to_string())?
+            } else {
+                ticktick.tasks().builder(&title)
+            };
+            if let Some(project) = project {
+                builder = builder.project_id(resolve_project(&ticktick, &project).await?);
+            }
+            if let Some(due) = due {
+                let (due_date, is_all_day) = parse_due(&due)?;
+                builder = builder.due_date(due_date).is_all_day(is_all_day);
+            }
+            if let Some(priority) = priority {
+                builder = builder.priority(priority);
+            }
+            if !tags.is_empty() {
+                builder = builder.tags(tags);
+            }
+            if let Some(content) = content {
+                builder = builder.content(&content);
+            }
+            let task = builder
+                .build_and_publish()
+                .await
+                .map_err(|err| err.to_string())?;
+            print_task_result(&task, "Created", format)
+        }
+        Command::Done { id, project } => {
+            let mut task = ticktick
+                .tasks()
+                .bind(find_task(&ticktick, &id, project).await?);
+            task.complete().await.map_err(|err| err.to_string())?;
+            print_task_result(&task, "Completed", format)
+        }
+        Command::Rm { id, project } => {
+            let task = find_task(&ticktick, &id, project).await?;
+            ticktick
+                .tasks()
+                .delete(&task)
+                .await
+                .map_err(|err| err.to_string())?;
+            print_task_result(&task, "Deleted", format)
+        }
+        Command::Backup { file } => {
+            let backup = Backup::fetch(&ticktick)
+                .await
+                .map_err(|err| err.to_string())?;
+            match file {
+                Some(file) => {
+                    std::fs::write(&file, backup.to_json()).map_err(|err| err.to_string())?;
+                    eprintln!(
+                        "Saved {} projects to {}",
+                        backup.projects.len(),
+                        file.display()
+                    );
+                }
+                None => println!("{}", backup.to_json()),
+            }
+            Ok(())
+        }
+        Command::Restore { file } => {
+            let json = std::fs::read_to_string(&file).map_err(|err| err.to_string())?;
+            let backup = Backup::from_json(&json).map_err(|err| err.to_string())?;
+            let report = backup.restore(&ticktick).await;
+            match format {
+                OutputFormat::Json => output::print_json(&serde_json::json!({
+                    "projectIds": report.project_ids,
+                    "taskIds": report.task_ids,
+                    "failures": report.failures.iter().map(|failure| serde_json::json!({
+                        "id": failure.id,
+                        "error": failure.error.to_string(),
+                    })).collect::<Vec<_>>(),
+                }))?,
+                OutputFormat::Table => {
+                    println!(
+                        "Restored {} projects and {} tasks",
+                        report.project_ids.len(),
+                        report.task_ids.len()
+                    );
+                    for failure in &report.failures {
+                        eprintln!("Failed to restore {}: {}", failure.id, failure.error);
+                    }
+                }
+            }
+            if report.failures.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("{} items failed to restore", report.failures.len()))
+            }
+        }
+        Command::Markdown {
+            projects,
+            task_template,
+            no_content,
+        } => {
+            let mut exporter = MarkdownExporter::new().include_content(!no_content);
+            if let Some(template) = task_template {
+                exporter = exporter.task_template(&template);
+            }
+            let all_projects = ticktick
+                .projects()
+                .get_all()
+                .await
+                .map_err(|err| err.to_string())?;
+            let mut selected = Vec::new();
+            if projects.is_empty() {
+                selected = all_projects;
+            } else {
+                for project in projects {
+                    let project_id = resolve_project(&ticktick, &project).await?;
+                    let project = all_projects
+                        .iter()
+                        .find(|candidate| *candidate.get_id() == project_id)
+                        .cloned()
+                        .unwrap_or_else(|| inbox_project(project_id));
+                    selected.push(project);
+                }
+            }
+            let mut data = Vec::new();
+            for project in &selected {
+                data.push(
+                    ticktick
+                        .projects()
+                        .get_data(project.get_id())
+                        .await
+                        .map_err(|err| err.to_string())?,
+                );
+            }
+            print!("{}", exporter.export_all(selected.iter().zip(&data)));
+            Ok(())
+        }
+        Command::ImportMarkdown {
+            file,
+            project,
+            dry_run,
+        } => {
+            let markdown = std::fs::read_to_string(&file).map_err(|err| err.to_string())?;
+            let mut importer = MarkdownImporter::new(&ticktick).dry_run(dry_run);
+            if let Some(project) = project {
+                importer = importer.project_id(resolve_project(&ticktick, &project).await?);
+            }
+            let report = importer
+                .import(&markdown)
+                .await
+                .map_err(|err| err.to_string())?;
+            match format {
+                OutputFormat::Json => output::print_json(&serde_json::json!({
+                    "projects": report.projects,
+                    "created": report.created,
+                    "failures": report.failures.iter().map(|failure| serde_json::json!({
+                        "title": failure.title,
+                        "error": failure.error.to_string(),
+                    })).collect::<Vec<_>>(),
+                }))?,
+                OutputFormat::Table => {
+                    let verb = if dry_run { "Would create" } else { "Created" };
+                    for project in &report.projects {
+                        println!("{} project {}", verb, project.name);
+                    }
+                    for task in &report.created {
+                        println!("{} task {}", verb, task.title);
+                    }
+                    for failure in &report.failures {
+                        eprintln!("Failed to create {}: {}", failure.title, failure.error);
+                    }
+                }
+            }
+            if report.failures.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("{} items failed to import", report.failures.len()))
+            }
+        }
+        Command::Template {
+            file,
+            project,
+            create_project,
+            variables,
+            dry_run,
+        } => {
+            let text = std::fs::read_to_string(&file).map_err(|err| err.to_string())?;
+            let template = if file
+                .extension()
+                .is_some_and(|extension| extension == "toml")
+            {
+                Template::from_toml(&text)
+            } else {
+                Template::from_json(&text)
+            }
+            .map_err(|err| err.to_string())?;
+            let mut instance = template
+                .instantiate(&ticktick)
+                .variables(variables)
+                .create_project(create_project);
+            if let Some(project) = project {
+                instance = instance.project_id(resolve_project(&ticktick, &project).await?);
+            }
+            if dry_run {
+                let rendered = instance.render().map_err(|err| err.to_string())?;
+                return match format {
+                    OutputFormat::Json => output::print_json(&rendered),
+                    OutputFormat::Table => {
+                        if create_project {
+                            println!("Would create project {}", rendered.name.unwrap_or_default());
+                        }
+                        for task in &rendered.tasks {
+                            println!("Would create task {}", task.title);
+                        }
+                        Ok(())
+                    }
+                };
+            }
+            let report = instance.publish().await.map_err(|err| err.to_string())?;
+            match format {
+                OutputFormat::Json => output::print_json(&serde_json::json!({
+                    "project": report.project,
+                    "created": report.created,
+                    "failures": report.failures.iter().map(|failure| serde_json::json!({
+                        "title": failure.title,
+                        "error": failure.error.to_string(),
+                    })).collect::<Vec<_>>(),
+                }))?,
+                OutputFormat::Table => {
+                    if let Some(project) = &report.project {
+                        println!("Created project {}", project.name);
+                    }
+                    for task in &report.created {
+                        println!("Created task {}", task.title);
+                    }
+                    for failure in &report.failures {
+                        eprintln!("Failed to create {}: {}", failure.title, failure.error);
+                    }
+                }
+            }
+            if report.failures.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("{} tasks failed to create", report.failures.len()))
+            }
+        }
+        Command::Mirror { project, file } => mirror(&ticktick, &project, &file).await,
+        Command::Matrix {
+            project,
+            important,
+            urgent_days,
+        } => {
+            let tasks = match project {
+                Some(project) => {
+                    let project_id = resolve_project(&ticktick, &project).await?;
+                    ticktick
+                        .projects()
+                        .get_data(&project_id)
+                        .await
+                        .map_err(|err| err.to_string())?
+                        .tasks
+                }
+                None => ticktick
+                    .tasks()
+                    .get_all_with_inbox()
+                    .await
+                    .map_err(|err| err.to_string())?,
+            };
+            let quadrants = EisenhowerMatrix::new()
+                .important_priority(important)
+                .urgent_days(urgent_days)
+                .group(&tasks);
+            output::print_quadrants(&quadrants, format)
+        }
+        Command::Tags { command: None } => {
+            let tags = ticktick
+                .tags()
+                .list()
+                .await
+                .map_err(|err| err.to_string())?;
+            match format {
+                OutputFormat::Json => output::print_json(
+                    &tags
+                        .iter()
+                        .map(|tag| serde_json::json!({ "name": tag.name, "count": tag.count }))
+                        .collect::<Vec<_>>(),
+                ),
+                OutputFormat::Table => {
+                    let mut table = output::Table::new(vec!["TAG", "TASKS"]);
+                    for tag in &tags {
+                        table.row(vec![tag.name.clone(), tag.count.to_string()]);
+                    }
+                    table.print();
+                    Ok(())
+                }
+            }
+        }
+        Command::Tags {
+            command: Some(command),
+        } => {
+            let (edit, dry_run) = match command {
+                TagsCommand::Rename { from, to, dry_run } => {
+                    (ticktick.tags().rename(&from, &to), dry_run)
+                }
+                TagsCommand::Merge {
+                    sources,
+                    into,
+                    dry_run,
+                } => (ticktick.tags().merge(sources, &into), dry_run),
+                TagsCommand::Rm { tag, dry_run } => (ticktick.tags().remove(&tag), dry_run),
+            };
+            let report = edit
+                .dry_run(dry_run)
+                .apply()
+                .await
+                .map_err(|err| err.to_string())?;
+            let failures: Vec<_> = report
+                .batch
+                .iter()
+                .flat_map(|batch| batch.failures())
+                .collect();
+            match format {
+                OutputFormat::Json => output::print_json(&serde_json::json!({
+                    "tasks": report.tasks,
+                    "failures": failures.iter().map(|failure| serde_json::json!({
+                        "taskId": failure.task_id,
+                        "error": failure.outcome.as_ref().unwrap_err().to_string(),
+                    })).collect::<Vec<_>>(),
+                }))?,
+                OutputFormat::Table => {
+                    let verb = if dry_run { "Would update" } else { "Updated" };
+                    for (index, task) in report.tasks.iter().enumerate() {
+                        if failures.iter().all(|failure| failure.index != index) {
+                            println!("{} {}: {}", verb, task.title, task.tags.join(", "));
+                        }
+                    }
+                    for failure in &failures {
+                        eprintln!(
+                            "Failed to update {}: {}",
+                            failure.task_id.0,
+                            failure.outcome.as_ref().unwrap_err()
+                        );
+                    }
+                }
+            }
+            if failures.is_empty() {
+                Ok(())
+            } else {
+                Err(format!("{} tasks failed to update", failures.len()))
+            }
+        }
+        #[cfg(feature = "views")]
+        Command::View { view } => {
+            let days = ticktick
+                .smart_view(view)
+                .await
+                .map_err(|err| err.to_string())?;
+            output::print_day_groups(&days, format)
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui => tui::run(ticktick).await,
+    }
+}
+
+fn parse_variable(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected name=value, got \"{}\"", value))
+}
+
+fn print_task_result(task: &Task, action: &str, format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => output::print_json(task),
+        OutputFormat::Table => {
+            println!("{} {} {}", action, task.get_id().0, task.title);
+            Ok(())
+        }
+    }
+}
+
+async fn login(
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    base_url: Option<String>,
+) -> Result<(), String> {
+    let mut auth = Authorization::begin_auth(client_id, redirect_uri.clone())
+        .map_err(|err| err.to_string())?;
+    if let Some(base_url) = &base_url {
+        auth = auth.with_base_url(base_url);
+    }
+    eprintln!(
+        "Open this URL in your browser to authorize ticks:\n{}",
+        auth.get_url()
+    );
+    let redirected = wait_for_redirect(&redirect_uri).await?;
+    let query = |key: &str| {
+        redirected
+            .query_pairs()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+            .ok_or_else(|| format!("Redirect is missing the `{}` parameter", key))
+    };
+    let token = auth
+        .finish_auth(client_secret, query("code")?, query("state")?)
+        .await
+        .map_err(|err| err.to_string())?;
+    let path = config::save_token(&token)?;
+    eprintln!("Logged in. Token saved to {}", path.display());
+    Ok(())
+}
+
+/// Get the URL TickTick redirected to, by listening on the redirect URI if it's local, or by asking the user to paste it.
+async fn wait_for_redirect(redirect_uri: &str) -> Result<Url, String> {
+    let redirect = Url::parse(redirect_uri).map_err(|err| err.to_string())?;
+    let is_local = matches!(redirect.host_str(), Some("localhost") | Some("127.0.0.1"));
+    if !is_local {
+        eprintln!("Paste the URL you were redirected to:");
+        let mut line = String::new();
+        BufReader::new(tokio::io::stdin())
+            .read_line(&mut line)
+            .await
+            .map_err(|err| err.to_string())?;
+        return Url::parse(line.trim()).map_err(|err| err.to_string());
+    }
+    let port = redirect.port_or_known_default().unwrap_or(80);
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|err| format!("Could not listen on port {}: {}", port, err))?;
+    let (mut stream, _) = listener.accept().await.map_err(|err| err.to_string())?;
+    let mut request_line = String::new();
+    BufReader::new(&mut stream)
+        .read_line(&mut request_line)
+        .await
+        .map_err(|err| err.to_string())?;
+    let _ = stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\nticks is authorized, you can close this window.",
+        )
+        .await;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    redirect.join(path).map_err(|err| err.to_string())
+}
+
+/// Parse a due date, returning whether it's an all-day date.
+fn parse_due(value: &str) -> Result<(DateTime<Utc>, bool), String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok((local_to_utc(date.and_hms_opt(0, 0, 0).unwrap())?, true));
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok((local_to_utc(datetime)?, false));
+        }
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|datetime| (datetime.with_timezone(&Utc), false))
+        .map_err(|_| {
+            format!(
+                "Invalid due date \"{}\", expected YYYY-MM-DD or YYYY-MM-DD HH:MM",
+                value
+            )
+        })
+}
+
+fn local_to_utc(datetime: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .ok_or_else(|| format!("{} does not exist in the local time zone", datetime))
+}
+
+/// Send changes made to a todo.txt file to `project`, then rewrite the file from the project's open tasks.
+async fn mirror(ticktick: &TickTick, project: &str, file: &Path) -> Result<(), String> {
+    let project_id = resolve_project(ticktick, project).await?;
+    let project_name = if project_id.0 == "inbox" {
+        "Inbox".to_string()
+    } else {
+        ticktick
+            .projects()
+            .get(&project_id)
+            .await
+            .map_err(|err| err.to_string())?
+            .name
+    };
+    let mut tasks = ticktick
+        .projects()
+        .get_data(&project_id)
+        .await
+        .map_err(|err| err.to_string())?
+        .tasks;
+    let contents = match std::fs::read_to_string(file) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.to_string()),
+    };
+    let (mut created, mut updated) = (0, 0);
+    for item in todotxt::parse(&contents) {
+        if item.task.get_id().is_empty() {
+            TaskBuilder::from_task(ticktick, &item.task)
+                .project_id(project_id.clone())
+                .build_and_publish()
+                .await
+                .map_err(|err| err.to_string())?;
+            created += 1;
+            continue;
+        }
+        // Tasks completed or deleted in TickTick since the file was written are left alone.
+        let Some(task) = tasks
+            .iter_mut()
+            .find(|task| task.get_id() == item.task.get_id())
+        else {
+            continue;
+        };
+        if !item.apply_to(task) {
+            continue;
+        }
+        ticktick
+            .tasks()
+            .update(task)
+            .await
+            .map_err(|err| err.to_string())?;
+        if task.status == TaskStatus::Completed {
+            ticktick
+                .tasks()
+                .complete(task)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        updated += 1;
+    }
+    let tasks = ticktick
+        .projects()
+        .get_data(&project_id)
+        .await
+        .map_err(|err| err.to_string())?
+        .tasks;
+    let lines: Vec<String> = tasks
+        .iter()
+        .map(|task| todotxt::format_line(task, Some(&project_name)))
+        .collect();
+    std::fs::write(file, lines.join("\n") + "\n").map_err(|err| err.to_string())?;
+    eprintln!(
+        "Created {}, updated {}, wrote {} tasks to {}",
+        created,
+        updated,
+        tasks.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// The Inbox isn't returned by the project list, stand in for it by name.
+fn inbox_project(project_id: ProjectID) -> Project {
+    let mut project: Project =
+        serde_json::from_value(serde_json::json!({ "id": project_id })).unwrap_or_default();
+    project.name = "Inbox".into();
+    project
+}
+
+/// Resolve a project by ID, or by case-insensitive name.
+async fn resolve_project(ticktick: &TickTick, project: &str) -> Result<ProjectID, String> {
+    if project.eq_ignore_ascii_case("inbox") {
+        return Ok(ProjectID("inbox".into()));
+    }
+    let projects: Vec<Project> = ticktick
+        .projects()
+        .get_all()
+        .await
+        .map_err(|err| err.to_string())?;
+    projects
+        .iter()
+        .find(|candidate| candidate.get_id().0 == project)
+        .or_else(|| {
+            projects
+                .iter()
+                .find(|candidate| candidate.name.eq_ignore_ascii_case(project))
+        })
+        .map(|found| found.get_id().clone())
+        .ok_or_else(|| format!("No project with ID or name \"{}\"", project))
+}
+
+async fn find_task(ticktick: &TickTick, id: &str, project: Option<String>) -> Result<Task, String> {
+    let task_id = TaskID(id.into());
+    if let Some(project) = project {
+        let project_id = resolve_project(ticktick, &project).await?;
+        return ticktick
+            .tasks()
+            .get(&project_id, &task_id)
+            .await
+            .map_err(|err| err.to_string());
+    }
+    ticktick
+        .tasks()
+        .get_all_with_inbox()
+        .await
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .find(|task| *task.get_id() == task_id)
+        .ok_or_else(|| format!("No open task with ID \"{}\", try passing --project", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from([&["ticks"], args].concat())
+            .unwrap()
+            .command
+    }
+
+    #[test]
+    fn parses_add() {
+        let Command::Add {
+            title,
+            project,
+            priority,
+            tags,
+            quick,
+            ..
+        } = parse(&[
+            "add",
+            "Pay rent",
+            "--project",
+            "Work",
+            "--priority",
+            "high",
+            "--tags",
+            "finance,home",
+        ])
+        else {
+            panic!("expected add");
+        };
+        assert_eq!(title, "Pay rent");
+        assert_eq!(project.as_deref(), Some("Work"));
+        assert_eq!(priority, Some(TaskPriority::High));
+        assert_eq!(tags, ["finance", "home"]);
+        assert!(!quick);
+    }
+
+    #[test]
+    fn parses_global_options_after_subcommand() {
+        let cli =
+            Cli::try_parse_from(["ticks", "ls", "Work", "--json", "--filter", "tag:work"]).unwrap();
+        assert!(cli.json);
+        let Command::Ls { project, filter } = cli.command else {
+            panic!("expected ls");
+        };
+        assert_eq!(project.as_deref(), Some("Work"));
+        assert_eq!(filter, Some("tag:work".parse().unwrap()));
+    }
+
+    #[test]
+    fn parses_template_variables() {
+        let Command::Template { variables, .. } = parse(&[
+            "template",
+            "release.toml",
+            "--var",
+            "version=2.1.0",
+            "--var",
+            "note=a=b",
+        ]) else {
+            panic!("expected template");
+        };
+        assert_eq!(
+            variables,
+            [
+                ("version".to_string(), "2.1.0".to_string()),
+                ("note".to_string(), "a=b".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        for args in [
+            &["ticks", "add", "Task", "--priority", "urgent"][..],
+            &["ticks", "ls", "--filter", "priority>=urgent"],
+            &["ticks", "template", "t.toml", "--var", "version"],
+            &[
+                "ticks",
+                "template",
+                "t.toml",
+                "--project",
+                "Work",
+                "--create-project",
+            ],
+            &["ticks", "tags", "merge", "--into", "next"],
+            &["ticks", "done"],
+        ] {
+            assert!(Cli::try_parse_from(args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn parses_due_dates() {
+        let (date, is_all_day) = parse_due("2026-10-20").unwrap();
+        assert!(is_all_day);
+        assert_eq!(
+            date,
+            local_to_utc(
+                NaiveDate::from_ymd_opt(2026, 10, 20)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        let (date, is_all_day) = parse_due("2026-10-20 17:30").unwrap();
+        assert!(!is_all_day);
+        assert_eq!(
+            date,
+            local_to_utc(
+                NaiveDate::from_ymd_opt(2026, 10, 20)
+                    .unwrap()
+                    .and_hms_opt(17, 30, 0)
+                    .unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            parse_due("2026-10-20T17:30:00+02:00").unwrap(),
+            ("2026-10-20T15:30:00Z".parse().unwrap(), false)
+        );
+        assert!(parse_due("next tuesday").is_err());
+    }
+
+    #[cfg(feature = "testing")]
+    mod lookups {
+        use ticks::testing::FakeTickTick;
+
+        use super::*;
+
+        fn project(id: &str, name: &str) -> Project {
+            serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+        }
+
+        fn task(id: &str, project_id: &str) -> Task {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "projectId": project_id,
+                "title": id,
+            }))
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn resolves_projects_by_id_or_name() {
+            let fake = FakeTickTick::start().await.unwrap();
+            fake.insert_project(project("p1", "Work"));
+            fake.insert_project(project("p2", "Home"));
+            let ticktick = fake.client();
+
+            for (query, expected) in [
+                ("p2", "p2"),
+                ("work", "p1"),
+                ("HOME", "p2"),
+                ("Inbox", "inbox"),
+            ] {
+                assert_eq!(
+                    resolve_project(&ticktick, query).await.unwrap(),
+                    ProjectID(expected.into()),
+                    "{}",
+                    query
+                );
+            }
+            let error = resolve_project(&ticktick, "Garden").await.unwrap_err();
+            assert_eq!(error, "No project with ID or name \"Garden\"");
+        }
+
+        #[tokio::test]
+        async fn finds_tasks_in_projects_and_inbox() {
+            let fake = FakeTickTick::start().await.unwrap();
+            fake.insert_project(project("p1", "Work"));
+            fake.insert_task(task("t1", "p1"));
+            fake.insert_task(task("t2", "inbox"));
+            let ticktick = fake.client();
+
+            assert_eq!(
+                find_task(&ticktick, "t1", None).await.unwrap().project_id.0,
+                "p1"
+            );
+            assert_eq!(
+                find_task(&ticktick, "t2", None).await.unwrap().project_id.0,
+                "inbox"
+            );
+            assert_eq!(
+                find_task(&ticktick, "t1", Some("Work".into()))
+                    .await
+                    .unwrap()
+                    .get_id()
+                    .0,
+                "t1"
+            );
+            assert!(find_task(&ticktick, "t2", Some("Work".into()))
+                .await
+                .is_err());
+            let error = find_task(&ticktick, "t3", None).await.unwrap_err();
+            assert_eq!(error, "No open task with ID \"t3\", try passing --project");
+        }
+    }
+}
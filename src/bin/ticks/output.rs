@@ -0,0 +1,161 @@
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use ticks::{
+    eisenhower::{Quadrant, Quadrants},
+    projects::Project,
+    tasks::Task,
+};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// Plain text table with left-aligned, space-padded columns.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+    pub fn print(&self) {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.len()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let format_row = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        println!("{}", format_row(self.headers.clone()));
+        for row in &self.rows {
+            println!("{}", format_row(row.iter().map(String::as_str).collect()));
+        }
+    }
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|err| err.to_string())?
+    );
+    Ok(())
+}
+
+/// Format an API datetime in local time, or an empty string if unset.
+pub fn format_date(date: &DateTime<Utc>, is_all_day: bool) -> String {
+    if date.timestamp() == 0 {
+        return String::new();
+    }
+    let local = date.with_timezone(&Local);
+    if is_all_day {
+        local.format("%Y-%m-%d").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+pub fn print_projects(projects: &[Project], format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => print_json(projects),
+        OutputFormat::Table => {
+            let mut table = Table::new(vec!["ID", "NAME", "VIEW", "KIND"]);
+            for project in projects {
+                table.row(vec![
+                    project.get_id().0.clone(),
+                    project.name.clone(),
+                    format!("{:?}", project.view_mode).to_lowercase(),
+                    format!("{:?}", project.kind).to_lowercase(),
+                ]);
+            }
+            table.print();
+            Ok(())
+        }
+    }
+}
+
+pub fn print_tasks(tasks: &[Task], format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => print_json(tasks),
+        OutputFormat::Table => {
+            let mut table = Table::new(vec!["ID", "PROJECT", "PRIORITY", "DUE", "TITLE", "TAGS"]);
+            for task in tasks {
+                table.row(vec![
+                    task.get_id().0.clone(),
+                    task.project_id.0.clone(),
+                    task.priority.to_string(),
+                    format_date(&task.due_date, task.is_all_day),
+                    task.title.clone(),
+                    task.tags.join(","),
+                ]);
+            }
+            table.print();
+            Ok(())
+        }
+    }
+}
+
+pub fn print_quadrants(quadrants: &Quadrants, format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => print_json(&serde_json::json!({
+            "urgentImportant": quadrants.urgent_important,
+            "notUrgentImportant": quadrants.not_urgent_important,
+            "urgentUnimportant": quadrants.urgent_unimportant,
+            "notUrgentUnimportant": quadrants.not_urgent_unimportant,
+        })),
+        OutputFormat::Table => {
+            for (index, quadrant) in Quadrant::ALL.into_iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                println!("{}", quadrant);
+                print_tasks(quadrants.get(quadrant), format)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "views")]
+pub fn print_day_groups(
+    days: &[ticks::views::DayGroup],
+    format: OutputFormat,
+) -> Result<(), String> {
+    match format {
+        OutputFormat::Json => print_json(
+            &days
+                .iter()
+                .map(|day| serde_json::json!({"date": day.date.to_string(), "tasks": day.tasks}))
+                .collect::<Vec<_>>(),
+        ),
+        OutputFormat::Table => {
+            for (index, day) in days.iter().enumerate() {
+                if index > 0 {
+                    println!();
+                }
+                println!("{}", day.date.format("%a %Y-%m-%d"));
+                print_tasks(&day.tasks, format)?;
+            }
+            Ok(())
+        }
+    }
+}
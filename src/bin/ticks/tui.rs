@@ -0,0 +1,386 @@
+//! Interactive terminal UI, started with `ticks tui`. Built with the `tui` feature.
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use ticks::{
+    projects::Project,
+    tasks::{SubtaskStatus, Task, TaskPriority},
+    TickTick,
+};
+
+use crate::output::format_date;
+
+const HELP: &str =
+    "q quit  tab switch pane  j/k move  space complete  e edit  0-3 priority  +/- reprioritize  m move  r refresh";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Projects,
+    Tasks,
+}
+
+enum Mode {
+    Normal,
+    /// Editing the selected task's title.
+    Edit(String),
+    /// Choosing the project to move the selected task to.
+    Move(ListState),
+}
+
+struct App {
+    ticktick: TickTick,
+    projects: Vec<Project>,
+    project_state: ListState,
+    tasks: Vec<Task>,
+    task_state: ListState,
+    focus: Focus,
+    mode: Mode,
+    status: String,
+    quit: bool,
+}
+
+pub async fn run(ticktick: TickTick) -> Result<(), String> {
+    let mut app = App {
+        ticktick,
+        projects: Vec::new(),
+        project_state: ListState::default(),
+        tasks: Vec::new(),
+        task_state: ListState::default(),
+        focus: Focus::Projects,
+        mode: Mode::Normal,
+        status: HELP.into(),
+        quit: false,
+    };
+    app.load_projects().await?;
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal).await;
+    ratatui::restore();
+    result
+}
+
+impl App {
+    async fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        while !self.quit {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .map_err(|err| err.to_string())?;
+            let event = tokio::task::block_in_place(event::read).map_err(|err| err.to_string())?;
+            if let Event::Key(key) = event {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn load_projects(&mut self) -> Result<(), String> {
+        self.projects = self
+            .ticktick
+            .projects()
+            .get_all()
+            .await
+            .map_err(|err| err.to_string())?;
+        self.project_state
+            .select((!self.projects.is_empty()).then_some(0));
+        self.load_tasks().await;
+        Ok(())
+    }
+
+    async fn load_tasks(&mut self) {
+        let Some(project) = self.selected_project() else {
+            self.tasks.clear();
+            return;
+        };
+        match self.ticktick.projects().get_data(project.get_id()).await {
+            Ok(data) => {
+                self.tasks = data.tasks;
+                self.tasks.sort_by_key(|task| task.sort_order);
+            }
+            Err(err) => {
+                self.tasks.clear();
+                self.status = format!("Failed to load tasks: {}", err);
+            }
+        }
+        self.task_state
+            .select((!self.tasks.is_empty()).then_some(0));
+    }
+
+    fn selected_project(&self) -> Option<&Project> {
+        self.projects.get(self.project_state.selected()?)
+    }
+
+    fn selected_task_mut(&mut self) -> Option<&mut Task> {
+        self.tasks.get_mut(self.task_state.selected()?)
+    }
+
+    async fn handle_key(&mut self, key: KeyEvent) {
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal_key(key).await,
+            Mode::Edit(mut title) => match key.code {
+                KeyCode::Enter => {
+                    if let Some(task) = self.selected_task_mut() {
+                        task.title = title;
+                    }
+                    self.publish_selected("Renamed").await;
+                }
+                KeyCode::Esc => self.status = HELP.into(),
+                KeyCode::Backspace => {
+                    title.pop();
+                    self.mode = Mode::Edit(title);
+                }
+                KeyCode::Char(c) => {
+                    title.push(c);
+                    self.mode = Mode::Edit(title);
+                }
+                _ => self.mode = Mode::Edit(title),
+            },
+            Mode::Move(mut state) => match key.code {
+                KeyCode::Enter => {
+                    let target = state
+                        .selected()
+                        .and_then(|index| self.projects.get(index))
+                        .map(|project| project.get_id().clone());
+                    if let (Some(target), Some(task)) = (target, self.selected_task_mut()) {
+                        task.project_id = target;
+                        self.publish_selected("Moved").await;
+                        self.load_tasks().await;
+                    }
+                }
+                KeyCode::Esc => self.status = HELP.into(),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    state.select_next();
+                    self.mode = Mode::Move(state);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    state.select_previous();
+                    self.mode = Mode::Move(state);
+                }
+                _ => self.mode = Mode::Move(state),
+            },
+        }
+    }
+
+    async fn handle_normal_key(&mut self, key: KeyEvent) {
+        match (self.focus, key.code) {
+            (_, KeyCode::Char('q')) | (_, KeyCode::Esc) => self.quit = true,
+            (_, KeyCode::Tab) | (_, KeyCode::BackTab) => {
+                self.focus = match self.focus {
+                    Focus::Projects => Focus::Tasks,
+                    Focus::Tasks => Focus::Projects,
+                }
+            }
+            (_, KeyCode::Char('r')) => {
+                if let Err(err) = self.load_projects().await {
+                    self.status = format!("Failed to refresh: {}", err);
+                }
+            }
+            (Focus::Projects, KeyCode::Down | KeyCode::Char('j')) => {
+                self.project_state.select_next();
+                self.load_tasks().await;
+            }
+            (Focus::Projects, KeyCode::Up | KeyCode::Char('k')) => {
+                self.project_state.select_previous();
+                self.load_tasks().await;
+            }
+            (Focus::Projects, KeyCode::Enter | KeyCode::Char('l')) => self.focus = Focus::Tasks,
+            (Focus::Tasks, KeyCode::Char('h')) => self.focus = Focus::Projects,
+            (Focus::Tasks, KeyCode::Down | KeyCode::Char('j')) => self.task_state.select_next(),
+            (Focus::Tasks, KeyCode::Up | KeyCode::Char('k')) => self.task_state.select_previous(),
+            (Focus::Tasks, KeyCode::Char(' ') | KeyCode::Char('c')) => {
+                self.complete_selected().await
+            }
+            (Focus::Tasks, KeyCode::Char('e')) => {
+                if let Some(task) = self.selected_task_mut() {
+                    let title = task.title.clone();
+                    self.mode = Mode::Edit(title);
+                    self.status = "Editing title, enter to save, esc to cancel".into();
+                }
+            }
+            (Focus::Tasks, KeyCode::Char('m')) if self.task_state.selected().is_some() => {
+                let mut state = ListState::default();
+                state.select(self.project_state.selected());
+                self.mode = Mode::Move(state);
+                self.status = "Choose project, enter to move, esc to cancel".into();
+            }
+            (Focus::Tasks, KeyCode::Char(c @ '0'..='3')) => {
+                let priority = [
+                    TaskPriority::None,
+                    TaskPriority::Low,
+                    TaskPriority::Medium,
+                    TaskPriority::High,
+                ][c as usize - '0' as usize];
+                self.set_priority(|_| priority).await;
+            }
+            (Focus::Tasks, KeyCode::Char('+')) => self.set_priority(raise_priority).await,
+            (Focus::Tasks, KeyCode::Char('-')) => self.set_priority(lower_priority).await,
+            _ => {}
+        }
+    }
+
+    async fn set_priority(&mut self, change: impl Fn(TaskPriority) -> TaskPriority) {
+        if let Some(task) = self.selected_task_mut() {
+            task.priority = change(task.priority);
+            self.publish_selected("Reprioritized").await;
+        }
+    }
+
+    async fn publish_selected(&mut self, action: &str) {
+        let Some(index) = self.task_state.selected() else {
+            return;
+        };
+        let Some(task) = self.tasks.get(index) else {
+            return;
+        };
+        self.status = match self.ticktick.tasks().update(task).await {
+            Ok(_) => format!("{} \"{}\"", action, task.title),
+            Err(err) => format!("Failed to publish \"{}\": {}", task.title, err),
+        };
+    }
+
+    async fn complete_selected(&mut self) {
+        let Some(index) = self.task_state.selected() else {
+            return;
+        };
+        let Some(task) = self.tasks.get_mut(index) else {
+            return;
+        };
+        match self.ticktick.tasks().complete(task).await {
+            Ok(()) => {
+                self.status = format!("Completed \"{}\"", task.title);
+                self.tasks.remove(index);
+                if index >= self.tasks.len() {
+                    self.task_state.select(self.tasks.len().checked_sub(1));
+                }
+            }
+            Err(err) => self.status = format!("Failed to complete \"{}\": {}", task.title, err),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [projects_area, tasks_area] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main);
+
+        let projects = List::new(
+            self.projects
+                .iter()
+                .map(|project| ListItem::new(project.name.clone())),
+        )
+        .block(pane_block("Projects", self.focus == Focus::Projects))
+        .highlight_style(highlight_style());
+        frame.render_stateful_widget(projects, projects_area, &mut self.project_state);
+
+        let tasks = List::new(self.tasks.iter().map(task_item))
+            .block(pane_block("Tasks", self.focus == Focus::Tasks))
+            .highlight_style(highlight_style());
+        frame.render_stateful_widget(tasks, tasks_area, &mut self.task_state);
+
+        let status_line = match &self.mode {
+            Mode::Edit(title) => format!("Title: {}_", title),
+            _ => self.status.clone(),
+        };
+        frame.render_widget(
+            Paragraph::new(status_line).style(Style::new().fg(Color::DarkGray)),
+            status,
+        );
+
+        if let Mode::Move(state) = &mut self.mode {
+            let area = centered(tasks_area, 40, 12);
+            let choices = List::new(
+                self.projects
+                    .iter()
+                    .map(|project| ListItem::new(project.name.clone())),
+            )
+            .block(pane_block("Move to", true))
+            .highlight_style(highlight_style());
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(choices, area, state);
+        }
+    }
+}
+
+fn pane_block(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Block::new()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn highlight_style() -> Style {
+    Style::new().add_modifier(Modifier::REVERSED)
+}
+
+fn task_item(task: &Task) -> ListItem<'_> {
+    let priority_color = match task.priority {
+        TaskPriority::None => Color::Reset,
+        TaskPriority::Low => Color::Blue,
+        TaskPriority::Medium => Color::Yellow,
+        TaskPriority::High => Color::Red,
+    };
+    let mut title = vec![
+        Span::raw("[ ] "),
+        Span::styled(task.title.clone(), Style::new().fg(priority_color)),
+    ];
+    let due = format_date(&task.due_date, task.is_all_day);
+    if !due.is_empty() {
+        title.push(Span::styled(
+            format!("  {}", due),
+            Style::new().fg(Color::DarkGray),
+        ));
+    }
+    if !task.tags.is_empty() {
+        title.push(Span::styled(
+            format!("  #{}", task.tags.join(" #")),
+            Style::new().fg(Color::Green),
+        ));
+    }
+    let mut lines = vec![Line::from(title)];
+    lines.extend(task.subtasks.iter().map(|subtask| {
+        let check = match subtask.status {
+            SubtaskStatus::Completed => "[x]",
+            SubtaskStatus::Normal => "[ ]",
+        };
+        Line::from(format!("    {} {}", check, subtask.title))
+    }));
+    ListItem::new(lines)
+}
+
+fn raise_priority(priority: TaskPriority) -> TaskPriority {
+    match priority {
+        TaskPriority::None => TaskPriority::Low,
+        TaskPriority::Low => TaskPriority::Medium,
+        TaskPriority::Medium | TaskPriority::High => TaskPriority::High,
+    }
+}
+
+fn lower_priority(priority: TaskPriority) -> TaskPriority {
+    match priority {
+        TaskPriority::High => TaskPriority::Medium,
+        TaskPriority::Medium => TaskPriority::Low,
+        TaskPriority::Low | TaskPriority::None => TaskPriority::None,
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
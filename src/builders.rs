@@ -1,20 +1,22 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
-use crate::{ticktick_datetime_format, TickTick, TickTickError};
+use std::sync::Arc;
+
+use crate::{ticktick_datetime_format, Bound, TickTick, TickTickError};
 
 use super::{
     projects::{Project, ProjectID, ProjectKind, ProjectViewMode},
-    tasks::{Subtask, Task, TaskPriority, TaskStatus},
+    tasks::{Subtask, SubtaskID, Task, TaskPriority, TaskStatus},
 };
 
 /// Builder class for TickTick Projects. Call `build_and_publish` to create task and push to the TickTick API.
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=task-1)
-#[derive(Serialize, Default)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskBuilder {
     #[serde(skip)]
-    http_client: reqwest::Client,
+    ticktick: TickTick,
     title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     project_id: Option<ProjectID>,
@@ -60,9 +62,52 @@ pub struct TaskBuilder {
 impl TaskBuilder {
     pub fn new(ticktick: &TickTick, title: String) -> Self {
         Self {
+            ticktick: ticktick.clone(),
             title,
-            http_client: ticktick.http_client.clone(),
-            ..Default::default()
+            project_id: None,
+            is_all_day: None,
+            completed_time: None,
+            content: None,
+            desc: None,
+            due_date: None,
+            subtasks: Vec::new(),
+            priority: None,
+            reminders: Vec::new(),
+            repeat_flag: None,
+            sort_order: None,
+            start_date: None,
+            status: None,
+            time_zone: None,
+            tags: Vec::new(),
+        }
+    }
+    /// Start a builder with every field of `task` except its ID, e.g. to copy a task to another project.
+    pub fn from_task(ticktick: &TickTick, task: &Task) -> Self {
+        // Unset dates are deserialized as the Unix epoch.
+        let date = |value: &DateTime<Utc>| (value.timestamp() != 0).then_some(*value);
+        let text = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        let mut subtasks = task.subtasks.clone();
+        for subtask in &mut subtasks {
+            subtask.id = SubtaskID::default();
+        }
+        Self {
+            ticktick: ticktick.clone(),
+            title: task.title.clone(),
+            project_id: (!task.project_id.is_empty()).then(|| task.project_id.clone()),
+            is_all_day: Some(task.is_all_day),
+            completed_time: date(&task.completed_time),
+            content: text(&task.content),
+            desc: text(&task.desc),
+            due_date: date(&task.due_date),
+            subtasks,
+            priority: Some(task.priority),
+            reminders: task.reminders.clone(),
+            repeat_flag: text(&task.repeat_flag),
+            sort_order: Some(task.sort_order),
+            start_date: date(&task.start_date),
+            status: Some(task.status),
+            time_zone: text(&task.time_zone),
+            tags: task.tags.clone(),
         }
     }
     pub fn title(mut self, value: &str) -> Self {
@@ -130,28 +175,20 @@ impl TaskBuilder {
         self
     }
 
-    /// Create Task and publish to TickTick API
-    pub async fn build_and_publish(self) -> Result<Task, TickTickError> {
-        let mut task = self
-            .http_client
-            .post("https://ticktick.com/open/v1/task")
-            .body(serde_json::to_string(&self).unwrap())
-            .send()
-            .await?
-            .json::<Task>()
-            .await?;
-        task.http_client = self.http_client.clone();
-        Ok(task)
+    /// Create Task and publish to TickTick API. The created task is bound to the builder's `TickTick` handle.
+    pub async fn build_and_publish(self) -> Result<Bound<Task>, TickTickError> {
+        let task = self.ticktick.post_json("/task", &self).await?;
+        Ok(Bound::new(Arc::new(self.ticktick), task))
     }
 }
 
 /// Builder class for TickTick Projects. Call `build_and_publish` to create project and push to the TickTick API.
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=project-1)
-#[derive(Serialize, Default)]
+#[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectBuilder {
     #[serde(skip)]
-    http_client: reqwest::Client,
+    ticktick: TickTick,
     name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     color: Option<String>,
@@ -166,9 +203,12 @@ pub struct ProjectBuilder {
 impl ProjectBuilder {
     pub fn new(ticktick: &TickTick, name: String) -> Self {
         Self {
+            ticktick: ticktick.clone(),
             name,
-            http_client: ticktick.http_client.clone(),
-            ..Default::default()
+            color: None,
+            sort_order: None,
+            view_mode: None,
+            kind: None,
         }
     }
     pub fn name(mut self, value: &str) -> Self {
@@ -179,6 +219,10 @@ impl ProjectBuilder {
         self.color = Some(value.into());
         self
     }
+    pub fn sort_order(mut self, value: i64) -> Self {
+        self.sort_order = Some(value);
+        self
+    }
     pub fn view_mode(mut self, value: ProjectViewMode) -> Self {
         self.view_mode = Some(value);
         self
@@ -188,17 +232,9 @@ impl ProjectBuilder {
         self
     }
 
-    /// Create Project and publish to TickTick API
-    pub async fn build_and_publish(self) -> Result<Project, TickTickError> {
-        let mut project = self
-            .http_client
-            .post("https://ticktick.com/open/v1/project")
-            .json(&self)
-            .send()
-            .await?
-            .json::<Project>()
-            .await?;
-        project.http_client = self.http_client.clone();
-        Ok(project)
+    /// Create Project and publish to TickTick API. The created project is bound to the builder's `TickTick` handle.
+    pub async fn build_and_publish(self) -> Result<Bound<Project>, TickTickError> {
+        let project = self.ticktick.post_json("/project", &self).await?;
+        Ok(Bound::new(Arc::new(self.ticktick), project))
     }
 }
@@ -0,0 +1,784 @@
+//! Local offline cache persisting projects, tasks, subtasks and columns to SQLite. Enabled with the `cache` feature.
+//!
+//! `SqliteCache` is a `Transport`, so it sits between `TickTick` and the network: reads are served from the
+//! database while fresh, and every write (`publish_changes`, `delete`, `build_and_publish`, ...) invalidates
+//! the cached entries it affects once it succeeds. Cached project data lists tasks in the order TickTick sent them.
+//! ```ignore
+//! let cache = SqliteCache::open("ticks.sqlite", Arc::new(ReqwestTransport::default()))?
+//!     .with_max_age(Duration::from_secs(600));
+//! let ticktick = TickTick::with_transport(token, Arc::new(cache));
+//! ```
+//! A cache file holds data for a single account, use one file per access token.
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::Utc;
+use reqwest::Url;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{
+    tasks::TaskStatus,
+    transport::{
+        HttpMethod, HttpRequest, HttpResponse, Transport, TransportError, TransportFuture,
+    },
+};
+
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+const PROJECT_LIST_KEY: &str = "projects";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS freshness (
+    key TEXT PRIMARY KEY,
+    fetched_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS projects (
+    id TEXT PRIMARY KEY,
+    position INTEGER NOT NULL,
+    json TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    json TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS tasks_project_id ON tasks (project_id);
+CREATE TABLE IF NOT EXISTS subtasks (
+    task_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    json TEXT NOT NULL,
+    PRIMARY KEY (task_id, position)
+);
+CREATE TABLE IF NOT EXISTS columns (
+    id TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    json TEXT NOT NULL
+);
+";
+
+/// Caching `Transport` backed by a SQLite database, wrapping the transport used for cache misses and writes.
+#[derive(Debug)]
+pub struct SqliteCache {
+    inner: Arc<dyn Transport>,
+    db: Mutex<Connection>,
+    max_age: Duration,
+}
+
+/// Open API route a request targets, relative to `/open/v1`.
+enum Route {
+    ProjectList,
+    Project(String),
+    ProjectData(String),
+    Task { project_id: String, task_id: String },
+    CreateProject,
+    UpdateProject(String),
+    DeleteProject(String),
+    CreateTask,
+    UpdateTask(String),
+    CompleteOrDeleteTask { project_id: String, task_id: String },
+    Other,
+}
+
+impl SqliteCache {
+    /// Open (or create) the cache database at `path`.
+    pub fn open(path: impl AsRef<Path>, inner: Arc<dyn Transport>) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open(path)?, inner)
+    }
+    /// Cache held in memory, discarded when dropped.
+    pub fn in_memory(inner: Arc<dyn Transport>) -> rusqlite::Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, inner)
+    }
+    fn from_connection(db: Connection, inner: Arc<dyn Transport>) -> rusqlite::Result<Self> {
+        db.execute_batch(SCHEMA)?;
+        Ok(Self {
+            inner,
+            db: Mutex::new(db),
+            max_age: DEFAULT_MAX_AGE,
+        })
+    }
+    /// How long cached entries are served before being refetched. Defaults to 5 minutes.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+    /// Drop every cached entry.
+    pub fn invalidate_all(&self) -> rusqlite::Result<()> {
+        self.db.lock().unwrap().execute_batch(
+            "DELETE FROM freshness; DELETE FROM projects; DELETE FROM tasks; DELETE FROM subtasks; DELETE FROM columns;",
+        )
+    }
+
+    fn stale_before(&self) -> i64 {
+        Utc::now().timestamp() - self.max_age.as_secs() as i64
+    }
+
+    /// Serve request from the cache, if it's a read with a fresh entry.
+    fn lookup(&self, route: &Route) -> rusqlite::Result<Option<String>> {
+        let db = self.db.lock().unwrap();
+        let stale_before = self.stale_before();
+        let is_fresh = |key: &str| -> rusqlite::Result<bool> {
+            Ok(db
+                .query_row(
+                    "SELECT fetched_at FROM freshness WHERE key = ?1",
+                    [key],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()?
+                .is_some_and(|fetched_at| fetched_at > stale_before))
+        };
+        match route {
+            Route::ProjectList => {
+                if !is_fresh(PROJECT_LIST_KEY)? {
+                    return Ok(None);
+                }
+                let mut statement = db.prepare("SELECT json FROM projects ORDER BY position")?;
+                let projects = statement
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                Ok(Some(format!("[{}]", projects.join(","))))
+            }
+            Route::Project(id) => db
+                .query_row(
+                    "SELECT json FROM projects WHERE id = ?1 AND fetched_at > ?2",
+                    params![id, stale_before],
+                    |row| row.get(0),
+                )
+                .optional(),
+            Route::ProjectData(id) => {
+                if !is_fresh(&data_key(id))? {
+                    return Ok(None);
+                }
+                let mut statement = db.prepare(
+                    "SELECT id, json FROM tasks WHERE project_id = ?1 ORDER BY position",
+                )?;
+                let tasks = statement
+                    .query_map([id], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+                    .into_iter()
+                    .map(|(task_id, json)| with_subtasks(&db, &task_id, &json))
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                let mut statement =
+                    db.prepare("SELECT json FROM columns WHERE project_id = ?1 ORDER BY position")?;
+                let columns = statement
+                    .query_map([id], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                Ok(Some(format!(
+                    "{{\"tasks\":[{}],\"columns\":[{}]}}",
+                    tasks.join(","),
+                    columns.join(",")
+                )))
+            }
+            Route::Task {
+                project_id,
+                task_id,
+            } => {
+                let json: Option<String> = db
+                    .query_row(
+                        "SELECT json FROM tasks WHERE id = ?1 AND project_id = ?2 AND fetched_at > ?3",
+                        params![task_id, project_id, stale_before],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                json.map(|json| with_subtasks(&db, task_id, &json))
+                    .transpose()
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Store the response to a successful read.
+    fn store(&self, route: &Route, body: &str) -> rusqlite::Result<()> {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+            return Ok(());
+        };
+        let mut db = self.db.lock().unwrap();
+        let tx = db.transaction()?;
+        let now = Utc::now().timestamp();
+        match route {
+            Route::ProjectList => {
+                tx.execute("DELETE FROM projects", [])?;
+                for (position, project) in value.as_array().into_iter().flatten().enumerate() {
+                    store_project(&tx, project, position as i64, now)?;
+                }
+                mark_fresh(&tx, PROJECT_LIST_KEY, now)?;
+            }
+            Route::Project(id) => {
+                let position: i64 = tx
+                    .query_row("SELECT position FROM projects WHERE id = ?1", [id], |row| {
+                        row.get(0)
+                    })
+                    .optional()?
+                    .unwrap_or(i64::MAX);
+                store_project(&tx, &value, position, now)?;
+            }
+            Route::ProjectData(id) => {
+                remove_project_tasks(&tx, id)?;
+                tx.execute("DELETE FROM columns WHERE project_id = ?1", [id])?;
+                if let Some(tasks) = value.get("tasks").and_then(|tasks| tasks.as_array()) {
+                    for (position, task) in tasks.iter().enumerate() {
+                        store_task(&tx, task, position as i64, now)?;
+                    }
+                }
+                if let Some(columns) = value.get("columns").and_then(|columns| columns.as_array()) {
+                    for (position, column) in columns.iter().enumerate() {
+                        tx.execute(
+                            "INSERT OR REPLACE INTO columns (id, project_id, position, json) VALUES (?1, ?2, ?3, ?4)",
+                            params![
+                                str_field(column, "id"),
+                                id,
+                                position as i64,
+                                column.to_string()
+                            ],
+                        )?;
+                    }
+                }
+                mark_fresh(&tx, &data_key(id), now)?;
+            }
+            // Project data only lists open tasks, so completed tasks read on their own aren't cached with them.
+            Route::Task { .. } if is_completed(&value) => {
+                remove_task(&tx, &str_field(&value, "id"))?
+            }
+            Route::Task { task_id, .. } => {
+                let position: i64 = tx
+                    .query_row(
+                        "SELECT position FROM tasks WHERE id = ?1",
+                        [task_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?
+                    .unwrap_or(i64::MAX);
+                store_task(&tx, &value, position, now)?
+            }
+            _ => {}
+        }
+        tx.commit()
+    }
+
+    /// Remove cached entries a successful write to `route`, with request body `body`, made stale.
+    fn invalidate(&self, route: &Route, body: Option<&str>) -> rusqlite::Result<()> {
+        let mut db = self.db.lock().unwrap();
+        let tx = db.transaction()?;
+        match route {
+            Route::CreateProject => unmark_fresh(&tx, PROJECT_LIST_KEY)?,
+            Route::UpdateProject(id) => {
+                tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+                unmark_fresh(&tx, PROJECT_LIST_KEY)?;
+            }
+            Route::DeleteProject(id) => {
+                tx.execute("DELETE FROM projects WHERE id = ?1", [id])?;
+                remove_project_tasks(&tx, id)?;
+                tx.execute("DELETE FROM columns WHERE project_id = ?1", [id])?;
+                unmark_fresh(&tx, PROJECT_LIST_KEY)?;
+                unmark_fresh(&tx, &data_key(id))?;
+            }
+            Route::CreateTask | Route::UpdateTask(_) => {
+                // The task may be created in, or moved to, the project named in the body.
+                let body_project_id = body
+                    .and_then(|body| serde_json::from_str::<serde_json::Value>(body).ok())
+                    .map(|body| str_field(&body, "projectId"));
+                if let Some(project_id) = body_project_id.filter(|id| !id.is_empty()) {
+                    unmark_fresh(&tx, &data_key(&project_id))?;
+                } else {
+                    tx.execute("DELETE FROM freshness WHERE key LIKE 'data:%'", [])?;
+                }
+                if let Route::UpdateTask(task_id) = route {
+                    remove_task(&tx, task_id)?;
+                }
+            }
+            Route::CompleteOrDeleteTask {
+                project_id,
+                task_id,
+            } => {
+                remove_task(&tx, task_id)?;
+                unmark_fresh(&tx, &data_key(project_id))?;
+            }
+            _ => {}
+        }
+        tx.commit()
+    }
+}
+
+impl Transport for SqliteCache {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let route = parse_route(&request);
+            if request.method == HttpMethod::Get {
+                if let Some(body) = self.lookup(&route).map_err(cache_error)? {
+                    return Ok(HttpResponse::new(200, body));
+                }
+                let response = self.inner.send(request).await?;
+                if response.is_success() {
+                    self.store(&route, &response.body).map_err(cache_error)?;
+                }
+                return Ok(response);
+            }
+            // Invalidating only once the write is done keeps a read made while it's in flight from caching the
+            // old data after the invalidation.
+            let body = request.body.clone();
+            let response = self.inner.send(request).await?;
+            if response.is_success() {
+                self.invalidate(&route, body.as_deref())
+                    .map_err(cache_error)?;
+            }
+            Ok(response)
+        })
+    }
+}
+
+fn cache_error(err: rusqlite::Error) -> TransportError {
+    TransportError::Other(format!("Cache error: {}", err))
+}
+
+fn parse_route(request: &HttpRequest) -> Route {
+    let Ok(url) = Url::parse(&request.url) else {
+        return Route::Other;
+    };
+    let Some(path) = url.path().split_once("/open/v1/").map(|(_, path)| path) else {
+        return Route::Other;
+    };
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match (request.method, segments.as_slice()) {
+        (HttpMethod::Get, ["project"]) => Route::ProjectList,
+        (HttpMethod::Get, ["project", id]) => Route::Project(id.to_string()),
+        (HttpMethod::Get, ["project", id, "data"]) => Route::ProjectData(id.to_string()),
+        (HttpMethod::Get, ["project", project_id, "task", task_id]) => Route::Task {
+            project_id: project_id.to_string(),
+            task_id: task_id.to_string(),
+        },
+        (HttpMethod::Post, ["project"]) => Route::CreateProject,
+        (HttpMethod::Post, ["project", id]) => Route::UpdateProject(id.to_string()),
+        (HttpMethod::Delete, ["project", id]) => Route::DeleteProject(id.to_string()),
+        (HttpMethod::Post, ["task"]) => Route::CreateTask,
+        (HttpMethod::Post, ["task", id]) => Route::UpdateTask(id.to_string()),
+        (HttpMethod::Post, ["project", project_id, "task", task_id, "complete"])
+        | (HttpMethod::Delete, ["project", project_id, "task", task_id]) => {
+            Route::CompleteOrDeleteTask {
+                project_id: project_id.to_string(),
+                task_id: task_id.to_string(),
+            }
+        }
+        _ => Route::Other,
+    }
+}
+
+fn data_key(project_id: &str) -> String {
+    format!("data:{}", project_id)
+}
+
+fn is_completed(task: &serde_json::Value) -> bool {
+    task.get("status").and_then(|status| status.as_u64()) == Some(TaskStatus::Completed as u64)
+}
+
+fn str_field(value: &serde_json::Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(|field| field.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn mark_fresh(tx: &rusqlite::Transaction, key: &str, now: i64) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO freshness (key, fetched_at) VALUES (?1, ?2)",
+        params![key, now],
+    )?;
+    Ok(())
+}
+
+fn unmark_fresh(tx: &rusqlite::Transaction, key: &str) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM freshness WHERE key = ?1", [key])?;
+    Ok(())
+}
+
+fn store_project(
+    tx: &rusqlite::Transaction,
+    project: &serde_json::Value,
+    position: i64,
+    now: i64,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT OR REPLACE INTO projects (id, position, json, fetched_at) VALUES (?1, ?2, ?3, ?4)",
+        params![str_field(project, "id"), position, project.to_string(), now],
+    )?;
+    Ok(())
+}
+
+/// Store task, splitting its subtasks ("items") into their own table.
+fn store_task(
+    tx: &rusqlite::Transaction,
+    task: &serde_json::Value,
+    position: i64,
+    now: i64,
+) -> rusqlite::Result<()> {
+    let mut task = task.clone();
+    let task_id = str_field(&task, "id");
+    let subtasks = task
+        .as_object_mut()
+        .and_then(|task| task.remove("items"))
+        .and_then(|items| items.as_array().cloned())
+        .unwrap_or_default();
+    tx.execute(
+        "INSERT OR REPLACE INTO tasks (id, project_id, position, json, fetched_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            task_id,
+            str_field(&task, "projectId"),
+            position,
+            task.to_string(),
+            now
+        ],
+    )?;
+    tx.execute("DELETE FROM subtasks WHERE task_id = ?1", [&task_id])?;
+    for (position, subtask) in subtasks.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO subtasks (task_id, position, json) VALUES (?1, ?2, ?3)",
+            params![task_id, position as i64, subtask.to_string()],
+        )?;
+    }
+    Ok(())
+}
+
+fn remove_task(tx: &rusqlite::Transaction, task_id: &str) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM subtasks WHERE task_id = ?1", [task_id])?;
+    tx.execute("DELETE FROM tasks WHERE id = ?1", [task_id])?;
+    Ok(())
+}
+
+fn remove_project_tasks(tx: &rusqlite::Transaction, project_id: &str) -> rusqlite::Result<()> {
+    tx.execute(
+        "DELETE FROM subtasks WHERE task_id IN (SELECT id FROM tasks WHERE project_id = ?1)",
+        [project_id],
+    )?;
+    tx.execute("DELETE FROM tasks WHERE project_id = ?1", [project_id])?;
+    Ok(())
+}
+
+/// Rebuild a task's JSON with its subtasks put back under "items".
+fn with_subtasks(db: &Connection, task_id: &str, json: &str) -> rusqlite::Result<String> {
+    let mut statement =
+        db.prepare("SELECT json FROM subtasks WHERE task_id = ?1 ORDER BY position")?;
+    let subtasks = statement
+        .query_map([task_id], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if subtasks.is_empty() {
+        return Ok(json.to_string());
+    }
+    let Some(fields) = json.strip_suffix('}') else {
+        return Ok(json.to_string());
+    };
+    let separator = if fields.trim_end().ends_with('{') {
+        ""
+    } else {
+        ","
+    };
+    Ok(format!(
+        "{}{}\"items\":[{}]}}",
+        fields,
+        separator,
+        subtasks.join(",")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const BASE: &str = "https://ticktick.com/open/v1";
+
+    /// Answers GETs with fixed bodies by URL, and every write with `{}`, counting the requests it receives.
+    #[derive(Debug, Default)]
+    struct Upstream {
+        bodies: Mutex<HashMap<String, String>>,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl Upstream {
+        fn set(&self, path: &str, body: serde_json::Value) {
+            self.bodies
+                .lock()
+                .unwrap()
+                .insert(format!("{}{}", BASE, path), body.to_string());
+        }
+        fn count(&self, method: HttpMethod, path: &str) -> usize {
+            let url = format!("{}{}", BASE, path);
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|request| request.method == method && request.url == url)
+                .count()
+        }
+    }
+
+    impl Transport for Upstream {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                let body = match request.method {
+                    HttpMethod::Get => self.bodies.lock().unwrap().get(&request.url).cloned(),
+                    _ => Some("{}".into()),
+                };
+                self.requests.lock().unwrap().push(request);
+                Ok(match body {
+                    Some(body) => HttpResponse::new(200, body),
+                    None => HttpResponse::new(404, ""),
+                })
+            })
+        }
+    }
+
+    fn cache() -> (Arc<Upstream>, SqliteCache) {
+        let upstream = Arc::new(Upstream::default());
+        upstream.set(
+            "/project",
+            serde_json::json!([{ "id": "p1", "name": "Work" }]),
+        );
+        upstream.set(
+            "/project/p1/data",
+            serde_json::json!({
+                "tasks": [{ "id": "t1", "projectId": "p1", "title": "Open", "status": 0,
+                            "items": [{ "id": "s1", "title": "Step" }] }],
+                "columns": [{ "id": "c1", "projectId": "p1", "name": "To do" }],
+            }),
+        );
+        let cache = SqliteCache::in_memory(upstream.clone()).unwrap();
+        (upstream, cache)
+    }
+
+    async fn get(cache: &SqliteCache, path: &str) -> serde_json::Value {
+        let response = cache
+            .send(HttpRequest::new(
+                HttpMethod::Get,
+                format!("{}{}", BASE, path),
+            ))
+            .await
+            .unwrap();
+        assert!(
+            response.is_success(),
+            "GET {} returned {}",
+            path,
+            response.status
+        );
+        serde_json::from_str(&response.body).unwrap()
+    }
+
+    async fn send(
+        cache: &SqliteCache,
+        method: HttpMethod,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) {
+        let mut request = HttpRequest::new(method, format!("{}{}", BASE, path));
+        request.body = body.map(|body| body.to_string());
+        cache.send(request).await.unwrap();
+    }
+
+    fn task_ids(data: &serde_json::Value) -> Vec<&str> {
+        data["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn serves_fresh_reads_from_cache() {
+        let (upstream, cache) = cache();
+
+        let first = get(&cache, "/project/p1/data").await;
+        let second = get(&cache, "/project/p1/data").await;
+        get(&cache, "/project").await;
+        get(&cache, "/project").await;
+
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 1);
+        assert_eq!(upstream.count(HttpMethod::Get, "/project"), 1);
+        assert_eq!(first, second);
+        assert_eq!(second["tasks"][0]["items"][0]["id"], "s1");
+        assert_eq!(second["columns"][0]["id"], "c1");
+    }
+
+    #[tokio::test]
+    async fn refetches_stale_reads() {
+        let (upstream, cache) = cache();
+        let cache = cache.with_max_age(Duration::ZERO);
+
+        get(&cache, "/project/p1/data").await;
+        get(&cache, "/project/p1/data").await;
+
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 2);
+    }
+
+    #[tokio::test]
+    async fn post_invalidates_project_data() {
+        let (upstream, cache) = cache();
+        get(&cache, "/project/p1/data").await;
+
+        send(
+            &cache,
+            HttpMethod::Post,
+            "/task/t1",
+            Some(serde_json::json!({ "id": "t1", "projectId": "p1", "title": "Renamed" })),
+        )
+        .await;
+        get(&cache, "/project/p1/data").await;
+
+        assert_eq!(upstream.count(HttpMethod::Post, "/task/t1"), 1);
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 2);
+    }
+
+    #[tokio::test]
+    async fn post_invalidates_project_list() {
+        let (upstream, cache) = cache();
+        get(&cache, "/project").await;
+
+        send(
+            &cache,
+            HttpMethod::Post,
+            "/project",
+            Some(serde_json::json!({ "name": "New" })),
+        )
+        .await;
+        get(&cache, "/project").await;
+
+        assert_eq!(upstream.count(HttpMethod::Get, "/project"), 2);
+    }
+
+    #[tokio::test]
+    async fn delete_invalidates_task_and_project_data() {
+        let (upstream, cache) = cache();
+        upstream.set(
+            "/project/p1/task/t1",
+            serde_json::json!({ "id": "t1", "projectId": "p1", "title": "Open", "status": 0 }),
+        );
+        get(&cache, "/project/p1/data").await;
+        get(&cache, "/project/p1/task/t1").await;
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/task/t1"), 0);
+
+        send(&cache, HttpMethod::Delete, "/project/p1/task/t1", None).await;
+        upstream.bodies.lock().unwrap().clear();
+        upstream.set(
+            "/project/p1/data",
+            serde_json::json!({ "tasks": [], "columns": [] }),
+        );
+
+        assert!(task_ids(&get(&cache, "/project/p1/data").await).is_empty());
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 2);
+        let response = cache
+            .send(HttpRequest::new(
+                HttpMethod::Get,
+                format!("{}/project/p1/task/t1", BASE),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 404);
+    }
+
+    #[tokio::test]
+    async fn completed_task_reads_stay_out_of_project_data() {
+        let (upstream, cache) = cache();
+        upstream.set(
+            "/project/p1/task/t2",
+            serde_json::json!({ "id": "t2", "projectId": "p1", "title": "Done", "status": 2 }),
+        );
+        assert_eq!(task_ids(&get(&cache, "/project/p1/data").await), ["t1"]);
+
+        assert_eq!(get(&cache, "/project/p1/task/t2").await["status"], 2);
+
+        assert_eq!(task_ids(&get(&cache, "/project/p1/data").await), ["t1"]);
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 1);
+        get(&cache, "/project/p1/task/t2").await;
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/task/t2"), 2);
+    }
+
+    #[tokio::test]
+    async fn keeps_task_order() {
+        let (upstream, cache) = cache();
+        let tasks = serde_json::json!([
+            { "id": "t3", "projectId": "p1", "sortOrder": 5 },
+            { "id": "t1", "projectId": "p1", "sortOrder": -5 },
+            { "id": "t2", "projectId": "p1", "sortOrder": 0 },
+        ]);
+        upstream.set(
+            "/project/p1/data",
+            serde_json::json!({ "tasks": tasks, "columns": [] }),
+        );
+        upstream.set("/project/p1/task/t3", tasks[0].clone());
+
+        let fresh = get(&cache, "/project/p1/data").await;
+        get(&cache, "/project/p1/task/t3").await;
+        let cached = get(&cache, "/project/p1/data").await;
+
+        assert_eq!(upstream.count(HttpMethod::Get, "/project/p1/data"), 1);
+        assert_eq!(task_ids(&fresh), ["t3", "t1", "t2"]);
+        assert_eq!(task_ids(&cached), task_ids(&fresh));
+    }
+
+    /// Holds every write until a read has reached it, then applies the write to the project data.
+    #[derive(Debug, Default)]
+    struct SlowWrites {
+        reads: std::sync::atomic::AtomicUsize,
+        written: std::sync::atomic::AtomicBool,
+    }
+
+    impl Transport for SlowWrites {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                if request.method == HttpMethod::Get {
+                    self.reads.fetch_add(1, Ordering::SeqCst);
+                    let title = if self.written.load(Ordering::SeqCst) {
+                        "New"
+                    } else {
+                        "Old"
+                    };
+                    let data = serde_json::json!({
+                        "tasks": [{ "id": "t1", "projectId": "p1", "title": title }],
+                        "columns": [],
+                    });
+                    return Ok(HttpResponse::new(200, data.to_string()));
+                }
+                while self.reads.load(Ordering::SeqCst) == 0 {
+                    tokio::task::yield_now().await;
+                }
+                self.written.store(true, Ordering::SeqCst);
+                Ok(HttpResponse::new(200, "{}"))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn read_during_write_is_invalidated() {
+        let upstream = Arc::new(SlowWrites::default());
+        let cache = SqliteCache::in_memory(upstream.clone()).unwrap();
+
+        futures_util::future::join(
+            send(
+                &cache,
+                HttpMethod::Post,
+                "/task/t1",
+                Some(serde_json::json!({ "id": "t1", "projectId": "p1", "title": "New" })),
+            ),
+            async {
+                assert_eq!(
+                    get(&cache, "/project/p1/data").await["tasks"][0]["title"],
+                    "Old"
+                );
+            },
+        )
+        .await;
+
+        assert_eq!(
+            get(&cache, "/project/p1/data").await["tasks"][0]["title"],
+            "New"
+        );
+    }
+}
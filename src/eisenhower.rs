@@ -0,0 +1,285 @@
+//! Eisenhower matrix, as in TickTick's app, built from fetched tasks.
+//!
+//! By default a task is important if its priority is at least `Medium`, and urgent if it's due within a day (tomorrow
+//! or earlier, including overdue tasks). Both thresholds can be changed, and each quadrant's rule can be replaced
+//! with any `Filter`, like the custom rules of TickTick's matrix:
+//! ```ignore
+//! let matrix = EisenhowerMatrix::new()
+//!     .important_priority(TaskPriority::High)
+//!     .urgent_days(3)
+//!     .rule(Quadrant::NotUrgentUnimportant, "priority:low and not tag:someday".parse()?)
+//!     .group(&ticktick.tasks().get_all_in_projects().await?);
+//! for task in &matrix.urgent_important {
+//!     println!("Do first: {}", task.title);
+//! }
+//! ```
+//! Tasks are checked against the quadrants in order, and placed in the first one whose rule matches. Tasks that
+//! match none of the rules, which can only happen with custom rules, and completed tasks are left out.
+use chrono::{Local, NaiveDate};
+
+use crate::{
+    filter::{Comparison, Filter, FilterDate},
+    tasks::{Task, TaskPriority, TaskStatus},
+};
+
+/// A quadrant of the matrix, in the order tasks are checked against them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quadrant {
+    /// Do first.
+    UrgentImportant,
+    /// Schedule.
+    NotUrgentImportant,
+    /// Delegate.
+    UrgentUnimportant,
+    /// Eliminate.
+    NotUrgentUnimportant,
+}
+
+impl Quadrant {
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::UrgentImportant,
+        Quadrant::NotUrgentImportant,
+        Quadrant::UrgentUnimportant,
+        Quadrant::NotUrgentUnimportant,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Quadrant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::UrgentImportant => "Urgent & Important",
+            Self::NotUrgentImportant => "Not Urgent & Important",
+            Self::UrgentUnimportant => "Urgent & Unimportant",
+            Self::NotUrgentUnimportant => "Not Urgent & Unimportant",
+        })
+    }
+}
+
+/// Rules for sorting tasks into quadrants.
+#[derive(Debug, Clone)]
+pub struct EisenhowerMatrix {
+    important_priority: TaskPriority,
+    urgent_days: i64,
+    rules: [Option<Filter>; 4],
+}
+
+/// Tasks sorted into quadrants by `EisenhowerMatrix::group`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Quadrants {
+    pub urgent_important: Vec<Task>,
+    pub not_urgent_important: Vec<Task>,
+    pub urgent_unimportant: Vec<Task>,
+    pub not_urgent_unimportant: Vec<Task>,
+}
+
+impl Quadrants {
+    pub fn get(&self, quadrant: Quadrant) -> &[Task] {
+        match quadrant {
+            Quadrant::UrgentImportant => &self.urgent_important,
+            Quadrant::NotUrgentImportant => &self.not_urgent_important,
+            Quadrant::UrgentUnimportant => &self.urgent_unimportant,
+            Quadrant::NotUrgentUnimportant => &self.not_urgent_unimportant,
+        }
+    }
+
+    fn get_mut(&mut self, quadrant: Quadrant) -> &mut Vec<Task> {
+        match quadrant {
+            Quadrant::UrgentImportant => &mut self.urgent_important,
+            Quadrant::NotUrgentImportant => &mut self.not_urgent_important,
+            Quadrant::UrgentUnimportant => &mut self.urgent_unimportant,
+            Quadrant::NotUrgentUnimportant => &mut self.not_urgent_unimportant,
+        }
+    }
+}
+
+impl Default for EisenhowerMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EisenhowerMatrix {
+    pub fn new() -> Self {
+        Self {
+            important_priority: TaskPriority::Medium,
+            urgent_days: 1,
+            rules: [None, None, None, None],
+        }
+    }
+    /// Lowest priority of important tasks. Defaults to `Medium`.
+    pub fn important_priority(mut self, value: TaskPriority) -> Self {
+        self.important_priority = value;
+        self
+    }
+    /// Tasks due within this many days of today, or overdue, are urgent. `0` means due today. Defaults to `1`.
+    pub fn urgent_days(mut self, value: i64) -> Self {
+        self.urgent_days = value;
+        self
+    }
+    /// Replace the rule of `quadrant`, ignoring the importance and urgency thresholds for it.
+    pub fn rule(mut self, quadrant: Quadrant, filter: Filter) -> Self {
+        self.rules[quadrant.index()] = Some(filter);
+        self
+    }
+
+    /// The rule tasks in `quadrant` match, either set with `rule` or built from the thresholds.
+    pub fn rule_for(&self, quadrant: Quadrant) -> Filter {
+        if let Some(rule) = &self.rules[quadrant.index()] {
+            return rule.clone();
+        }
+        let important = Filter::Priority(Comparison::GreaterOrEqual, self.important_priority);
+        let urgent = Filter::Due(
+            Comparison::LessOrEqual,
+            FilterDate::Relative(self.urgent_days),
+        );
+        match quadrant {
+            Quadrant::UrgentImportant => important.and(urgent),
+            Quadrant::NotUrgentImportant => important.and(!urgent),
+            Quadrant::UrgentUnimportant => (!important).and(urgent),
+            Quadrant::NotUrgentUnimportant => (!important).and(!urgent),
+        }
+    }
+
+    /// Quadrant of `task`, with urgency counted from today in the local time zone.
+    pub fn classify(&self, task: &Task) -> Option<Quadrant> {
+        self.classify_on(task, Local::now().date_naive())
+    }
+
+    /// Quadrant of `task`, with urgency counted from `today`.
+    pub fn classify_on(&self, task: &Task, today: NaiveDate) -> Option<Quadrant> {
+        if task.status == TaskStatus::Completed {
+            return None;
+        }
+        Quadrant::ALL
+            .into_iter()
+            .find(|quadrant| self.rule_for(*quadrant).matches_on(task, today))
+    }
+
+    /// Sort `tasks` into quadrants, with urgency counted from today in the local time zone.
+    pub fn group(&self, tasks: &[Task]) -> Quadrants {
+        self.group_on(tasks, Local::now().date_naive())
+    }
+
+    /// Sort `tasks` into quadrants, with urgency counted from `today`.
+    pub fn group_on(&self, tasks: &[Task], today: NaiveDate) -> Quadrants {
+        let rules: Vec<(Quadrant, Filter)> = Quadrant::ALL
+            .into_iter()
+            .map(|quadrant| (quadrant, self.rule_for(quadrant)))
+            .collect();
+        let mut quadrants = Quadrants::default();
+        for task in tasks
+            .iter()
+            .filter(|task| task.status != TaskStatus::Completed)
+        {
+            if let Some((quadrant, _)) = rules.iter().find(|(_, rule)| rule.matches_on(task, today))
+            {
+                quadrants.get_mut(*quadrant).push(task.clone());
+            }
+        }
+        quadrants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 14).unwrap()
+    }
+
+    /// Task due `days` after `today`, at noon local time.
+    fn task(title: &str, priority: TaskPriority, days: Option<i64>) -> Task {
+        let due_date = days
+            .map(|days| {
+                let date = today() + chrono::Duration::days(days);
+                Local
+                    .from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+                    .unwrap()
+                    .with_timezone(&Utc)
+            })
+            .unwrap_or_default();
+        Task {
+            title: title.into(),
+            priority,
+            due_date,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classifies_by_default_thresholds() {
+        let matrix = EisenhowerMatrix::new();
+        let cases = [
+            (TaskPriority::High, Some(-3), Quadrant::UrgentImportant),
+            (TaskPriority::Medium, Some(1), Quadrant::UrgentImportant),
+            (TaskPriority::Medium, Some(2), Quadrant::NotUrgentImportant),
+            (TaskPriority::High, None, Quadrant::NotUrgentImportant),
+            (TaskPriority::Low, Some(0), Quadrant::UrgentUnimportant),
+            (TaskPriority::None, Some(1), Quadrant::UrgentUnimportant),
+            (TaskPriority::Low, Some(2), Quadrant::NotUrgentUnimportant),
+            (TaskPriority::None, None, Quadrant::NotUrgentUnimportant),
+        ];
+        for (priority, days, expected) in cases {
+            let task = task("Task", priority, days);
+            assert_eq!(
+                matrix.classify_on(&task, today()),
+                Some(expected),
+                "{:?} due in {:?} days",
+                priority,
+                days
+            );
+        }
+        let mut completed = task("Done", TaskPriority::High, Some(0));
+        completed.status = TaskStatus::Completed;
+        assert_eq!(matrix.classify_on(&completed, today()), None);
+    }
+
+    #[test]
+    fn classifies_by_custom_thresholds() {
+        let matrix = EisenhowerMatrix::new()
+            .important_priority(TaskPriority::High)
+            .urgent_days(3);
+        let task = task("Task", TaskPriority::Medium, Some(3));
+        assert_eq!(
+            matrix.classify_on(&task, today()),
+            Some(Quadrant::UrgentUnimportant)
+        );
+    }
+
+    #[test]
+    fn groups_with_custom_rules_in_order() {
+        let matrix = EisenhowerMatrix::new()
+            .rule(Quadrant::UrgentImportant, "title:boss".parse().unwrap())
+            .rule(
+                Quadrant::NotUrgentUnimportant,
+                "priority:low".parse().unwrap(),
+            );
+        let tasks = [
+            task("Email boss", TaskPriority::None, None),
+            task("Report", TaskPriority::High, Some(5)),
+            task("Tidy desk", TaskPriority::Low, Some(5)),
+            // Unimportant and not urgent, but not low priority: matches no rule.
+            task("Browse", TaskPriority::None, None),
+            task("Call plumber", TaskPriority::None, Some(0)),
+        ];
+        let quadrants = matrix.group_on(&tasks, today());
+        let titles = |quadrant| -> Vec<&str> {
+            quadrants
+                .get(quadrant)
+                .iter()
+                .map(|task| task.title.as_str())
+                .collect()
+        };
+        assert_eq!(titles(Quadrant::UrgentImportant), ["Email boss"]);
+        assert_eq!(titles(Quadrant::NotUrgentImportant), ["Report"]);
+        assert_eq!(titles(Quadrant::UrgentUnimportant), ["Call plumber"]);
+        assert_eq!(titles(Quadrant::NotUrgentUnimportant), ["Tidy desk"]);
+    }
+}
@@ -0,0 +1,613 @@
+//! Filters over fetched tasks, like TickTick's custom smart lists.
+//!
+//! Filters can be built as `Filter` values or parsed from a string:
+//! ```ignore
+//! let filter: Filter = "priority>=medium and tag:work and due<=today+3d and not completed".parse()?;
+//! let tasks = ticktick.tasks().get_all_in_projects().await?;
+//! let matching: Vec<&Task> = tasks.iter().filter(|task| filter.matches(task)).collect();
+//!
+//! // The same filter, limited to two projects.
+//! let filter = Filter::Priority(Comparison::GreaterOrEqual, TaskPriority::Medium)
+//!     .and(Filter::Tag("work".into()))
+//!     .and(Filter::Due(Comparison::LessOrEqual, FilterDate::Relative(3)))
+//!     .and(!Filter::Status(TaskStatus::Completed))
+//!     .and(Filter::in_projects([work_id, side_projects_id]));
+//! ```
+//! The string syntax combines conditions with `and`, `or`, `not` and parentheses. Conditions are:
+//! - `priority` compared with `none`, `low`, `medium` or `high`, using `=` (or `:`), `!=`, `<`, `<=`, `>` or `>=`.
+//! - `due` compared with `today`, `tomorrow`, `yesterday` or a `YYYY-MM-DD` date, optionally offset by days or weeks
+//!   (`today+3d`, `today-1w`). Dates are compared in the local time zone, tasks without a due date never match.
+//!   `due:none` matches tasks without a due date.
+//! - `tag:name`, `project:id` and `title:text`, where the title matches if it contains the text, ignoring case.
+//!   Values containing spaces can be quoted, as in `title:"pay rent"`.
+//! - `completed` and `open`, or `status:completed` and `status:open`.
+use chrono::{Days, Local, NaiveDate};
+
+use crate::{
+    projects::ProjectID,
+    tasks::{Task, TaskPriority, TaskStatus},
+};
+
+/// Error from parsing a filter string.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError {
+    /// Byte offset in the filter string where the error was found.
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn compare<T: PartialOrd>(self, left: &T, right: &T) -> bool {
+        match self {
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// Date a task's due date is compared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterDate {
+    Date(NaiveDate),
+    /// Number of days from the day the filter is evaluated, `0` for today.
+    Relative(i64),
+}
+
+impl FilterDate {
+    fn resolve(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Self::Date(date) => Some(date),
+            Self::Relative(days) if days < 0 => {
+                today.checked_sub_days(Days::new(days.unsigned_abs()))
+            }
+            Self::Relative(days) => today.checked_add_days(Days::new(days as u64)),
+        }
+    }
+}
+
+/// A condition on tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Priority(Comparison, TaskPriority),
+    /// Compares the local date of the due date. Tasks without a due date never match.
+    Due(Comparison, FilterDate),
+    /// Matches tasks without a due date.
+    NoDueDate,
+    Tag(String),
+    Project(ProjectID),
+    /// Matches tasks whose title contains the text, ignoring case.
+    Title(String),
+    Status(TaskStatus),
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Matches tasks in any of `project_ids`.
+    pub fn in_projects(project_ids: impl IntoIterator<Item = ProjectID>) -> Self {
+        Self::Or(project_ids.into_iter().map(Self::Project).collect())
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        match self {
+            Self::And(mut filters) => {
+                filters.push(other);
+                Self::And(filters)
+            }
+            filter => Self::And(vec![filter, other]),
+        }
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        match self {
+            Self::Or(mut filters) => {
+                filters.push(other);
+                Self::Or(filters)
+            }
+            filter => Self::Or(vec![filter, other]),
+        }
+    }
+
+    /// Whether `task` matches, with relative dates counted from today in the local time zone.
+    pub fn matches(&self, task: &Task) -> bool {
+        self.matches_on(task, Local::now().date_naive())
+    }
+
+    /// Whether `task` matches, with relative dates counted from `today`.
+    pub fn matches_on(&self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            Self::Priority(comparison, priority) => comparison.compare(&task.priority, priority),
+            Self::Due(comparison, date) => match (due_date(task), date.resolve(today)) {
+                (Some(due), Some(date)) => comparison.compare(&due, &date),
+                _ => false,
+            },
+            Self::NoDueDate => due_date(task).is_none(),
+            Self::Tag(tag) => task
+                .tags
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(tag)),
+            Self::Project(project_id) => task.project_id == *project_id,
+            Self::Title(text) => task.title.to_lowercase().contains(&text.to_lowercase()),
+            Self::Status(status) => task.status == *status,
+            Self::And(filters) => filters.iter().all(|filter| filter.matches_on(task, today)),
+            Self::Or(filters) => filters.iter().any(|filter| filter.matches_on(task, today)),
+            Self::Not(filter) => !filter.matches_on(task, today),
+        }
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Filter;
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Not(filter) => *filter,
+            filter => Self::Not(Box::new(filter)),
+        }
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = FilterError;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(value)?,
+            index: 0,
+            end: value.len(),
+        };
+        let filter = parser.parse_or()?;
+        match parser.tokens.get(parser.index) {
+            None => Ok(filter),
+            Some((position, token)) => Err(FilterError {
+                position: *position,
+                message: format!("Unexpected {}", token),
+            }),
+        }
+    }
+}
+
+/// Local date of the task's due date, `None` if it's unset (deserialized as the Unix epoch).
+fn due_date(task: &Task) -> Option<NaiveDate> {
+    (task.due_date.timestamp() != 0).then(|| task.due_date.with_timezone(&Local).date_naive())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(Comparison),
+    LeftParen,
+    RightParen,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "\"{}\"", word),
+            Self::Quoted(text) => write!(f, "quoted \"{}\"", text),
+            Self::Operator(_) => f.write_str("operator"),
+            Self::LeftParen => f.write_str("\"(\""),
+            Self::RightParen => f.write_str("\")\""),
+        }
+    }
+}
+
+fn tokenize(value: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = value.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            ':' | '=' => Token::Operator(Comparison::Equal),
+            '<' | '>' | '!' => {
+                let equals = chars.next_if(|(_, next)| *next == '=').is_some();
+                Token::Operator(match (c, equals) {
+                    ('<', false) => Comparison::Less,
+                    ('<', true) => Comparison::LessOrEqual,
+                    ('>', false) => Comparison::Greater,
+                    ('>', true) => Comparison::GreaterOrEqual,
+                    ('!', true) => Comparison::NotEqual,
+                    _ => {
+                        return Err(FilterError {
+                            position,
+                            message: "Expected \"!=\"".into(),
+                        })
+                    }
+                })
+            }
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => text.push(c),
+                        None => {
+                            return Err(FilterError {
+                                position,
+                                message: "Unterminated quote".into(),
+                            })
+                        }
+                    }
+                }
+                Token::Quoted(text)
+            }
+            _ => {
+                let mut word = String::from(c);
+                while let Some((_, c)) =
+                    chars.next_if(|(_, next)| !next.is_whitespace() && !"()<>=!:\"".contains(*next))
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    /// Length of the filter string, the position of errors at its end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |(position, _)| *position)
+    }
+
+    fn error(&self, message: String) -> FilterError {
+        FilterError {
+            position: self.position(),
+            message,
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, FilterError> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error("Unexpected end of filter".into()))?;
+        self.index += 1;
+        Ok(token)
+    }
+
+    /// Consume the next token if it's the keyword `keyword`.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let matches =
+            matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if matches {
+            self.index += 1;
+        }
+        matches
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.parse_and()?;
+        while self.keyword("or") {
+            filter = filter.or(self.parse_and()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, FilterError> {
+        let mut filter = self.parse_unary()?;
+        while self.keyword("and") {
+            filter = filter.and(self.parse_unary()?);
+        }
+        Ok(filter)
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, FilterError> {
+        if self.keyword("not") {
+            return Ok(!self.parse_unary()?);
+        }
+        if self.peek() == Some(&Token::LeftParen) {
+            self.index += 1;
+            let filter = self.parse_or()?;
+            return match self.next()? {
+                Token::RightParen => Ok(filter),
+                token => {
+                    self.index -= 1;
+                    Err(self.error(format!("Expected \")\", found {}", token)))
+                }
+            };
+        }
+        self.parse_condition()
+    }
+
+    fn parse_condition(&mut self) -> Result<Filter, FilterError> {
+        let position = self.position();
+        let field = match self.next()? {
+            Token::Word(word) => word.to_lowercase(),
+            token => {
+                self.index -= 1;
+                return Err(self.error(format!("Expected a condition, found {}", token)));
+            }
+        };
+        match field.as_str() {
+            "completed" => return Ok(Filter::Status(TaskStatus::Completed)),
+            "open" => return Ok(Filter::Status(TaskStatus::Normal)),
+            "priority" | "due" | "tag" | "project" | "title" | "status" => {}
+            _ => {
+                return Err(FilterError {
+                    position,
+                    message: format!("Unknown field \"{}\"", field),
+                })
+            }
+        }
+        let comparison = match self.next()? {
+            Token::Operator(comparison) => comparison,
+            token => {
+                self.index -= 1;
+                return Err(self.error(format!("Expected an operator, found {}", token)));
+            }
+        };
+        let value_position = self.position();
+        let value = match self.next()? {
+            Token::Word(value) | Token::Quoted(value) => value,
+            token => {
+                self.index -= 1;
+                return Err(self.error(format!("Expected a value, found {}", token)));
+            }
+        };
+        let value_error = |message: String| FilterError {
+            position: value_position,
+            message,
+        };
+        let filter = match field.as_str() {
+            "priority" => {
+                let priority = value.parse::<TaskPriority>().map_err(value_error)?;
+                return Ok(Filter::Priority(comparison, priority));
+            }
+            "due" if value.eq_ignore_ascii_case("none") => Filter::NoDueDate,
+            "due" => {
+                let date = parse_date(&value)
+                    .ok_or_else(|| value_error(format!("Invalid date \"{}\"", value)))?;
+                return Ok(Filter::Due(comparison, date));
+            }
+            "tag" => Filter::Tag(value.trim_start_matches('#').into()),
+            "project" => Filter::Project(ProjectID(value)),
+            "title" => Filter::Title(value),
+            "status" => match value.to_lowercase().as_str() {
+                "completed" => Filter::Status(TaskStatus::Completed),
+                "open" => Filter::Status(TaskStatus::Normal),
+                _ => return Err(value_error(format!("Unknown status \"{}\"", value))),
+            },
+            _ => unreachable!("fields are checked above"),
+        };
+        match comparison {
+            Comparison::Equal => Ok(filter),
+            Comparison::NotEqual => Ok(!filter),
+            _ => Err(FilterError {
+                position: value_position,
+                message: format!("\"{}\" can only be compared with \"=\" or \"!=\"", field),
+            }),
+        }
+    }
+}
+
+/// Parse `today`, `tomorrow`, `yesterday` or `YYYY-MM-DD`, followed by an optional offset like `+3d` or `-1w`.
+fn parse_date(value: &str) -> Option<FilterDate> {
+    let value = value.to_lowercase();
+    let (base, offset) = [("today", 0), ("tomorrow", 1), ("yesterday", -1)]
+        .iter()
+        .find_map(|(name, days)| {
+            value
+                .strip_prefix(name)
+                .map(|offset| (FilterDate::Relative(*days), offset))
+        })
+        .or_else(|| {
+            let date = value.get(..10)?;
+            let offset = &value[10..];
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+            Some((FilterDate::Date(date), offset))
+        })?;
+    if offset.is_empty() {
+        return Some(base);
+    }
+    let (sign, offset) = match offset.strip_prefix('+') {
+        Some(offset) => (1, offset),
+        None => (-1, offset.strip_prefix('-')?),
+    };
+    let (count, unit) = match offset.strip_suffix('w') {
+        Some(count) => (count, 7),
+        None => (offset.strip_suffix('d').unwrap_or(offset), 1),
+    };
+    let days = sign * unit * count.parse::<i64>().ok()?;
+    match base {
+        FilterDate::Relative(base) => Some(FilterDate::Relative(base + days)),
+        FilterDate::Date(date) if days < 0 => Some(FilterDate::Date(
+            date.checked_sub_days(Days::new(days.unsigned_abs()))?,
+        )),
+        FilterDate::Date(date) => Some(FilterDate::Date(
+            date.checked_add_days(Days::new(days as u64))?,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn task(title: &str, priority: TaskPriority, due: Option<&str>, tags: &[&str]) -> Task {
+        let due_date = due
+            .map(|due| {
+                Local
+                    .from_local_datetime(&date(due).and_hms_opt(9, 0, 0).unwrap())
+                    .unwrap()
+                    .with_timezone(&Utc)
+            })
+            .unwrap_or_default();
+        Task {
+            title: title.into(),
+            priority,
+            due_date,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            project_id: ProjectID("p1".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_conditions() {
+        let cases = [
+            (
+                "priority>=medium",
+                Filter::Priority(Comparison::GreaterOrEqual, TaskPriority::Medium),
+            ),
+            (
+                "due<=today+3d",
+                Filter::Due(Comparison::LessOrEqual, FilterDate::Relative(3)),
+            ),
+            (
+                "due=tomorrow-1w",
+                Filter::Due(Comparison::Equal, FilterDate::Relative(-6)),
+            ),
+            (
+                "due>2026-10-01+1w",
+                Filter::Due(Comparison::Greater, FilterDate::Date(date("2026-10-08"))),
+            ),
+            ("due:none", Filter::NoDueDate),
+            ("tag:#Work", Filter::Tag("Work".into())),
+            ("project != p1", !Filter::Project(ProjectID("p1".into()))),
+            ("title:\"pay rent\"", Filter::Title("pay rent".into())),
+            ("status:open", Filter::Status(TaskStatus::Normal)),
+            ("not completed", !Filter::Status(TaskStatus::Completed)),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(text.parse::<Filter>(), Ok(expected), "parsing {:?}", text);
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let tag = |name: &str| Filter::Tag(name.into());
+        assert_eq!(
+            "tag:a or tag:b and not tag:c".parse::<Filter>(),
+            Ok(tag("a").or(tag("b").and(!tag("c"))))
+        );
+        assert_eq!(
+            "(tag:a or tag:b) and tag:c".parse::<Filter>(),
+            Ok(tag("a").or(tag("b")).and(tag("c")))
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        let cases = [
+            ("colour:red", 0, "Unknown field \"colour\""),
+            ("tag:work and", 12, "Unexpected end of filter"),
+            ("priority>urgent", 9, "Unknown task priority"),
+            ("due<someday", 4, "Invalid date \"someday\""),
+            ("tag>work", 4, "\"tag\" can only be compared"),
+            ("(tag:a or tag:b", 15, "Unexpected end of filter"),
+            ("tag:a tag:b", 6, "Unexpected \"tag\""),
+            ("title:\"pay", 6, "Unterminated quote"),
+            ("tag!work", 3, "Expected \"!=\""),
+        ];
+        for (text, position, message) in cases {
+            let error = text.parse::<Filter>().unwrap_err();
+            assert_eq!(
+                error.position, position,
+                "position for {:?}: {}",
+                text, error
+            );
+            assert!(
+                error.message.starts_with(message),
+                "message for {:?}: {}",
+                text,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn matches_tasks() {
+        let today = date("2026-10-14");
+        let filter: Filter = "priority>=medium and tag:work and due<=today+3d and not completed"
+            .parse()
+            .unwrap();
+        let cases = [
+            (
+                task("Report", TaskPriority::High, Some("2026-10-17"), &["Work"]),
+                true,
+            ),
+            (
+                task("Report", TaskPriority::High, Some("2026-10-18"), &["work"]),
+                false,
+            ),
+            (
+                task("Report", TaskPriority::Low, Some("2026-10-14"), &["work"]),
+                false,
+            ),
+            (task("Report", TaskPriority::Medium, None, &["work"]), false),
+            (
+                task("Report", TaskPriority::Medium, Some("2026-10-01"), &[]),
+                false,
+            ),
+        ];
+        for (task, expected) in cases {
+            assert_eq!(filter.matches_on(&task, today), expected, "{:?}", task);
+        }
+        let mut completed = task("Report", TaskPriority::High, Some("2026-10-14"), &["work"]);
+        completed.status = TaskStatus::Completed;
+        assert!(!filter.matches_on(&completed, today));
+
+        let undated = task("Pay Rent", TaskPriority::None, None, &[]);
+        assert!("due:none and title:rent"
+            .parse::<Filter>()
+            .unwrap()
+            .matches_on(&undated, today));
+        assert!(!"due<today"
+            .parse::<Filter>()
+            .unwrap()
+            .matches_on(&undated, today));
+        assert!(!"due>=today"
+            .parse::<Filter>()
+            .unwrap()
+            .matches_on(&undated, today));
+        assert!(
+            Filter::in_projects([ProjectID("p2".into()), ProjectID("p1".into())])
+                .matches_on(&undated, today)
+        );
+    }
+}
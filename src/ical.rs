@@ -0,0 +1,1187 @@
+//! iCalendar ([RFC 5545](https://datatracker.ietf.org/doc/html/rfc5545)) export and import. Enabled with the `ical` feature.
+//!
+//! Tasks become VTODOs (or VEVENTs, for calendar tools that ignore to-dos), `repeat_flag` becomes an RRULE,
+//! `reminders` become VALARMs and subtasks become VTODOs related to their parent task.
+//! ```ignore
+//! let data = ticktick.projects().get_data(project.get_id()).await?;
+//! let ics = ICalExporter::new().calendar_name(&project.name).export(&data.tasks);
+//! std::fs::write("tasks.ics", ics)?;
+//! ```
+//! `ICalImporter` does the reverse, creating tasks from VTODOs (and optionally VEVENTs).
+use std::{collections::HashSet, str::FromStr};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    builders::TaskBuilder,
+    projects::ProjectID,
+    tasks::{Subtask, SubtaskStatus, Task, TaskPriority, TaskStatus},
+    TickTick, TickTickError,
+};
+
+/// Domain appended to task IDs to build globally unique UIDs.
+const UID_DOMAIN: &str = "ticktick.com";
+/// Non-standard property holding a task's time zone, needed to convert all-day dates back on import.
+const TIME_ZONE_PROPERTY: &str = "X-TICKTICK-TIME-ZONE";
+/// Lines longer than this many octets are folded, as required by RFC 5545.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Calendar component tasks are exported as.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ICalComponent {
+    /// VTODO, keeps completion status and priority. Every task is exported.
+    #[default]
+    Todo,
+    /// VEVENT, shown by calendar tools that don't display to-dos. Tasks without a date are skipped.
+    Event,
+}
+
+/// Writes tasks as an iCalendar file. Configure with the builder methods, then call `export`.
+#[derive(Debug, Clone, Default)]
+pub struct ICalExporter {
+    component: ICalComponent,
+    calendar_name: Option<String>,
+}
+
+impl ICalExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Component tasks are exported as. Defaults to `ICalComponent::Todo`. Subtasks are always VTODOs.
+    pub fn component(mut self, value: ICalComponent) -> Self {
+        self.component = value;
+        self
+    }
+    /// Name shown by calendar tools, usually the project's name.
+    pub fn calendar_name(mut self, value: &str) -> Self {
+        self.calendar_name = Some(value.into());
+        self
+    }
+
+    /// Export `tasks`, e.g. `ProjectData::tasks`, as the contents of an `.ics` file.
+    pub fn export(&self, tasks: &[Task]) -> String {
+        let mut ics = ICalWriter::default();
+        ics.line("BEGIN", "VCALENDAR");
+        ics.line("VERSION", "2.0");
+        ics.line(
+            "PRODID",
+            &format!("-//ticks//ticks {}//EN", env!("CARGO_PKG_VERSION")),
+        );
+        ics.line("CALSCALE", "GREGORIAN");
+        if let Some(name) = &self.calendar_name {
+            ics.line("X-WR-CALNAME", &escape_text(name));
+        }
+        let now = Utc::now();
+        for task in tasks {
+            let written = match self.component {
+                ICalComponent::Todo => {
+                    write_todo(&mut ics, task, now);
+                    true
+                }
+                ICalComponent::Event => write_event(&mut ics, task, now),
+            };
+            if written {
+                for (index, subtask) in task.subtasks.iter().enumerate() {
+                    write_subtask(&mut ics, task, index, subtask, now);
+                }
+            }
+        }
+        ics.line("END", "VCALENDAR");
+        ics.finish()
+    }
+}
+
+fn write_todo(ics: &mut ICalWriter, task: &Task, now: DateTime<Utc>) {
+    ics.line("BEGIN", "VTODO");
+    write_common(ics, task, now);
+    let has_start = is_set(&task.start_date);
+    if has_start {
+        write_date(ics, "DTSTART", task, &task.start_date);
+    }
+    if is_set(&task.due_date) {
+        write_date(ics, "DUE", task, &task.due_date);
+    }
+    if task.status == TaskStatus::Completed {
+        ics.line("STATUS", "COMPLETED");
+        if is_set(&task.completed_time) {
+            ics.line("COMPLETED", &format_utc(&task.completed_time));
+        }
+    } else {
+        ics.line("STATUS", "NEEDS-ACTION");
+    }
+    if let Some(priority) = ical_priority(task.priority) {
+        ics.line("PRIORITY", &priority.to_string());
+    }
+    // Without DTSTART, alarms have to be relative to DUE.
+    write_alarms(ics, task, if has_start { "" } else { ";RELATED=END" });
+    ics.line("END", "VTODO");
+}
+
+/// Returns false if the task was skipped because it has no date.
+fn write_event(ics: &mut ICalWriter, task: &Task, now: DateTime<Utc>) -> bool {
+    let start = if is_set(&task.start_date) {
+        &task.start_date
+    } else if is_set(&task.due_date) {
+        &task.due_date
+    } else {
+        return false;
+    };
+    ics.line("BEGIN", "VEVENT");
+    write_common(ics, task, now);
+    write_date(ics, "DTSTART", task, start);
+    if is_set(&task.due_date) && task.due_date > *start {
+        write_date(ics, "DTEND", task, &task.due_date);
+    } else if task.is_all_day {
+        let end = local_date(task, start) + Duration::days(1);
+        ics.line("DTEND;VALUE=DATE", &end.format("%Y%m%d").to_string());
+    }
+    if task.status == TaskStatus::Completed {
+        // Events have no completed status, keep it visible to tools that understand it.
+        ics.line("X-TICKTICK-STATUS", "COMPLETED");
+    }
+    write_alarms(ics, task, "");
+    ics.line("END", "VEVENT");
+    true
+}
+
+fn write_common(ics: &mut ICalWriter, task: &Task, now: DateTime<Utc>) {
+    ics.line("UID", &task_uid(&task.get_id().0));
+    ics.line("DTSTAMP", &format_utc(&task.modified_time().unwrap_or(now)));
+    if let Some(created) = task.created_time() {
+        ics.line("CREATED", &format_utc(&created));
+    }
+    if let Some(modified) = task.modified_time() {
+        ics.line("LAST-MODIFIED", &format_utc(&modified));
+    }
+    ics.line("SUMMARY", &escape_text(&task.title));
+    let description = if task.content.is_empty() {
+        &task.desc
+    } else {
+        &task.content
+    };
+    if !description.is_empty() {
+        ics.line("DESCRIPTION", &escape_text(description));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|tag| escape_text(tag)).collect();
+        ics.line("CATEGORIES", &tags.join(","));
+    }
+    if let Some(rule) = task.repeat_flag.strip_prefix("RRULE:") {
+        ics.line("RRULE", rule);
+    }
+    if !task.time_zone.is_empty() {
+        ics.line(TIME_ZONE_PROPERTY, &task.time_zone);
+    }
+}
+
+fn write_subtask(
+    ics: &mut ICalWriter,
+    task: &Task,
+    index: usize,
+    subtask: &Subtask,
+    now: DateTime<Utc>,
+) {
+    ics.line("BEGIN", "VTODO");
+    // Subtasks created outside TickTick's apps may not have an ID, fall back to their position.
+    let id = if subtask.get_id().is_empty() {
+        format!("{}-{}", task.get_id().0, index)
+    } else {
+        subtask.get_id().0.clone()
+    };
+    ics.line("UID", &task_uid(&id));
+    ics.line("DTSTAMP", &format_utc(&task.modified_time().unwrap_or(now)));
+    ics.line("RELATED-TO;RELTYPE=PARENT", &task_uid(&task.get_id().0));
+    ics.line("SUMMARY", &escape_text(&subtask.title));
+    if is_set(&subtask.start_date) {
+        if subtask.is_all_day {
+            ics.line(
+                "DTSTART;VALUE=DATE",
+                &local_date(task, &subtask.start_date)
+                    .format("%Y%m%d")
+                    .to_string(),
+            );
+        } else {
+            ics.line("DTSTART", &format_utc(&subtask.start_date));
+        }
+    }
+    if subtask.status == SubtaskStatus::Completed {
+        ics.line("STATUS", "COMPLETED");
+        if is_set(&subtask.completed_time) {
+            ics.line("COMPLETED", &format_utc(&subtask.completed_time));
+        }
+    } else {
+        ics.line("STATUS", "NEEDS-ACTION");
+    }
+    ics.line("END", "VTODO");
+}
+
+/// TickTick reminders are stored as iCalendar triggers already, e.g. `TRIGGER:-PT15M`.
+fn write_alarms(ics: &mut ICalWriter, task: &Task, related: &str) {
+    for reminder in &task.reminders {
+        let Some(trigger) = reminder.strip_prefix("TRIGGER:") else {
+            continue;
+        };
+        ics.line("BEGIN", "VALARM");
+        ics.line("ACTION", "DISPLAY");
+        ics.line("DESCRIPTION", &escape_text(&task.title));
+        ics.line(&format!("TRIGGER{}", related), trigger);
+        ics.line("END", "VALARM");
+    }
+}
+
+/// Write a date property, as a `VALUE=DATE` in the task's time zone for all-day tasks, or as UTC.
+fn write_date(ics: &mut ICalWriter, name: &str, task: &Task, date: &DateTime<Utc>) {
+    if task.is_all_day {
+        ics.line(
+            &format!("{};VALUE=DATE", name),
+            &local_date(task, date).format("%Y%m%d").to_string(),
+        );
+    } else {
+        ics.line(name, &format_utc(date));
+    }
+}
+
+/// All-day dates are stored as midnight in the task's time zone, convert them back to a calendar date.
+fn local_date(task: &Task, date: &DateTime<Utc>) -> NaiveDate {
+    match Tz::from_str(&task.time_zone) {
+        Ok(tz) => date.with_timezone(&tz).date_naive(),
+        Err(_) => date.date_naive(),
+    }
+}
+
+/// Unset dates are deserialized as the Unix epoch.
+fn is_set(date: &DateTime<Utc>) -> bool {
+    date.timestamp() != 0
+}
+
+fn task_uid(id: &str) -> String {
+    format!("{}@{}", id, UID_DOMAIN)
+}
+
+fn format_utc(date: &DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// iCalendar priorities run from 1 (highest) to 9 (lowest), 0 is undefined.
+fn ical_priority(priority: TaskPriority) -> Option<u8> {
+    match priority {
+        TaskPriority::None => None,
+        TaskPriority::Low => Some(9),
+        TaskPriority::Medium => Some(5),
+        TaskPriority::High => Some(1),
+    }
+}
+
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds iCalendar content lines, folding long lines and terminating them with CRLF.
+#[derive(Default)]
+struct ICalWriter {
+    output: String,
+}
+
+impl ICalWriter {
+    fn line(&mut self, name: &str, value: &str) {
+        let line = format!("{}:{}", name, value);
+        let mut length = 0;
+        for c in line.chars() {
+            if length + c.len_utf8() > MAX_LINE_LENGTH {
+                self.output.push_str("\r\n ");
+                length = 1;
+            }
+            self.output.push(c);
+            length += c.len_utf8();
+        }
+        self.output.push_str("\r\n");
+    }
+    fn finish(self) -> String {
+        self.output
+    }
+}
+
+/// Errors that can stop an import before any task is created.
+#[derive(Debug)]
+pub enum ICalError {
+    /// The file isn't valid iCalendar.
+    ParseError(String),
+    /// Existing tasks couldn't be fetched to check for duplicates.
+    ApiError(TickTickError),
+}
+
+impl From<TickTickError> for ICalError {
+    fn from(value: TickTickError) -> Self {
+        Self::ApiError(value)
+    }
+}
+
+impl std::fmt::Display for ICalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ParseError(message) => write!(f, "Invalid iCalendar file: {}", message),
+            Self::ApiError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ICalError {}
+
+/// Component that failed to be created as a task.
+#[derive(Debug)]
+pub struct ImportFailure {
+    pub uid: String,
+    pub error: TickTickError,
+}
+
+/// Result of `ICalImporter::import`.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Tasks created, or in dry-run mode, the tasks that would be created.
+    pub created: Vec<Task>,
+    /// UIDs skipped because a task with the same UID already exists.
+    pub duplicates: Vec<String>,
+    pub failures: Vec<ImportFailure>,
+}
+
+/// Creates TickTick tasks from an iCalendar file. Configure with the builder methods, then call `import`.
+/// ```ignore
+/// let report = ICalImporter::new(&ticktick)
+///     .project_id(project_id)
+///     .dry_run(true)
+///     .import(&std::fs::read_to_string("tasks.ics")?)
+///     .await?;
+/// ```
+/// Each task's UID is appended to its content, so importing the same file twice doesn't create duplicates
+/// of tasks that are still open.
+#[derive(Debug, Clone)]
+pub struct ICalImporter {
+    ticktick: TickTick,
+    project_id: Option<ProjectID>,
+    include_events: bool,
+    dry_run: bool,
+    time_zone: Option<Tz>,
+}
+
+impl ICalImporter {
+    pub fn new(ticktick: &TickTick) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            project_id: None,
+            include_events: false,
+            dry_run: false,
+            time_zone: None,
+        }
+    }
+    /// Project to create tasks in. Defaults to the Inbox.
+    pub fn project_id(mut self, value: ProjectID) -> Self {
+        self.project_id = Some(value);
+        self
+    }
+    /// Also import VEVENTs. Only VTODOs are imported by default.
+    pub fn include_events(mut self, value: bool) -> Self {
+        self.include_events = value;
+        self
+    }
+    /// Report what would be created without creating anything.
+    pub fn dry_run(mut self, value: bool) -> Self {
+        self.dry_run = value;
+        self
+    }
+    /// Time zone of dates without one. Defaults to the file's `X-WR-TIMEZONE`, or UTC.
+    pub fn time_zone(mut self, value: Tz) -> Self {
+        self.time_zone = Some(value);
+        self
+    }
+
+    /// Import the contents of an `.ics` file. VTODOs related to another imported VTODO become subtasks of the
+    /// top-level VTODO they descend from. TickTick subtasks can't be nested, so deeper levels are flattened, each
+    /// subtask followed by its own.
+    /// Duplicates are checked against open tasks in the target project, or in every project and the Inbox when
+    /// importing to the Inbox. Completed tasks can't be listed, so a VTODO whose task was completed since it was
+    /// last imported (or exported) is imported again.
+    pub async fn import(&self, ics: &str) -> Result<ImportReport, ICalError> {
+        let calendars = parse_components(ics)?;
+        let mut report = ImportReport::default();
+        let existing = match &self.project_id {
+            Some(project_id) => self.ticktick.projects().get_data(project_id).await?.tasks,
+            None => self.ticktick.tasks().get_all_with_inbox().await?,
+        };
+        for calendar in calendars
+            .iter()
+            .filter(|component| component.name == "VCALENDAR")
+        {
+            let time_zone = self
+                .time_zone
+                .or_else(|| {
+                    calendar
+                        .property("X-WR-TIMEZONE")
+                        .and_then(|property| Tz::from_str(&property.value).ok())
+                })
+                .unwrap_or(Tz::UTC);
+            let components: Vec<&Component> = calendar
+                .children
+                .iter()
+                .filter(|component| {
+                    component.name == "VTODO" || (self.include_events && component.name == "VEVENT")
+                })
+                .collect();
+            let uids: HashSet<&str> = components.iter().map(|component| component.uid()).collect();
+            for component in &components {
+                if parent_uid(component, &uids).is_some() {
+                    continue;
+                }
+                let uid = component.uid();
+                if is_duplicate(&existing, uid) {
+                    report.duplicates.push(uid.into());
+                    continue;
+                }
+                let mut task = component_to_task(component, time_zone);
+                task.project_id = self.project_id.clone().unwrap_or_default();
+                task.subtasks = descendants(&components, &uids, uid)
+                    .into_iter()
+                    .map(|child| {
+                        component_to_subtask(
+                            child,
+                            Tz::from_str(&task.time_zone).unwrap_or(time_zone),
+                        )
+                    })
+                    .collect();
+                if self.dry_run {
+                    report.created.push(task);
+                    continue;
+                }
+                match self.builder(&task).build_and_publish().await {
+                    Ok(created) => report.created.push(created.into_inner()),
+                    Err(error) => report.failures.push(ImportFailure {
+                        uid: uid.into(),
+                        error,
+                    }),
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    fn builder(&self, task: &Task) -> TaskBuilder {
+        let mut builder = self
+            .ticktick
+            .tasks()
+            .builder(&task.title)
+            .content(&task.content)
+            .is_all_day(task.is_all_day)
+            .priority(task.priority)
+            .status(task.status)
+            .time_zone(&task.time_zone)
+            .subtasks(task.subtasks.clone())
+            .reminders(task.reminders.clone())
+            .tags(task.tags.clone());
+        if !task.project_id.is_empty() {
+            builder = builder.project_id(task.project_id.clone());
+        }
+        if is_set(&task.start_date) {
+            builder = builder.start_date(task.start_date);
+        }
+        if is_set(&task.due_date) {
+            builder = builder.due_date(task.due_date);
+        }
+        if is_set(&task.completed_time) {
+            builder = builder.completed_time(task.completed_time);
+        }
+        if !task.repeat_flag.is_empty() {
+            builder = builder.repeat_flag(&task.repeat_flag);
+        }
+        builder
+    }
+}
+
+/// Prefix of the content line an imported task's UID is stored in.
+const UID_MARKER: &str = "iCalendar UID: ";
+
+/// UID of the VTODO `component` is a subtask of, if it's one of `uids`.
+fn parent_uid<'a>(component: &'a Component, uids: &HashSet<&str>) -> Option<&'a str> {
+    component
+        .property("RELATED-TO")
+        .filter(|property| {
+            property
+                .param("RELTYPE")
+                .is_none_or(|reltype| reltype.eq_ignore_ascii_case("PARENT"))
+        })
+        .map(|property| property.value.as_str())
+        .filter(|parent| component.name == "VTODO" && uids.contains(parent))
+}
+
+/// VTODOs descending from `uid`, depth first, each followed by its own descendants.
+fn descendants<'a>(
+    components: &[&'a Component],
+    uids: &HashSet<&str>,
+    uid: &'a str,
+) -> Vec<&'a Component> {
+    fn visit<'a>(
+        components: &[&'a Component],
+        uids: &HashSet<&str>,
+        uid: &'a str,
+        visited: &mut HashSet<&'a str>,
+        found: &mut Vec<&'a Component>,
+    ) {
+        for &child in components {
+            // Relations that loop back are ignored.
+            if parent_uid(child, uids) == Some(uid) && visited.insert(child.uid()) {
+                found.push(child);
+                visit(components, uids, child.uid(), visited, found);
+            }
+        }
+    }
+    let mut found = Vec::new();
+    visit(components, uids, uid, &mut HashSet::from([uid]), &mut found);
+    found
+}
+
+/// A task is a duplicate if its UID is stored in an existing task's content, or if it was exported from one of them.
+fn is_duplicate(existing: &[Task], uid: &str) -> bool {
+    let exported_id = uid.strip_suffix(&format!("@{}", UID_DOMAIN));
+    existing.iter().any(|task| {
+        exported_id == Some(task.get_id().0.as_str())
+            || task
+                .content
+                .lines()
+                .any(|line| line.strip_prefix(UID_MARKER) == Some(uid))
+    })
+}
+
+fn component_to_task(component: &Component, time_zone: Tz) -> Task {
+    let time_zone = component
+        .property(TIME_ZONE_PROPERTY)
+        .and_then(|property| Tz::from_str(&property.value).ok())
+        .unwrap_or(time_zone);
+    let mut task = Task {
+        title: component.text("SUMMARY").unwrap_or_default(),
+        time_zone: time_zone.name().into(),
+        ..Default::default()
+    };
+    let description = component.text("DESCRIPTION").unwrap_or_default();
+    task.content = if description.is_empty() {
+        format!("{}{}", UID_MARKER, component.uid())
+    } else {
+        format!("{}\n\n{}{}", description, UID_MARKER, component.uid())
+    };
+    let start = component
+        .property("DTSTART")
+        .and_then(|property| parse_date(property, time_zone));
+    let end_name = if component.name == "VEVENT" {
+        "DTEND"
+    } else {
+        "DUE"
+    };
+    let mut end = component
+        .property(end_name)
+        .and_then(|property| parse_date(property, time_zone));
+    if let Some(date) = start.as_ref().or(end.as_ref()) {
+        task.is_all_day = date.is_all_day;
+        if let Some(zone) = &date.time_zone {
+            task.time_zone = zone.clone();
+        }
+    }
+    if let (Some(start), Some(date)) = (&start, &mut end) {
+        // All-day events end at the start of the following day, TickTick's due date is the last day.
+        if component.name == "VEVENT" && date.is_all_day {
+            date.value -= Duration::days(1);
+        }
+        if date.value < start.value {
+            date.value = start.value;
+        }
+    }
+    if let Some(start) = &start {
+        task.start_date = start.value;
+        task.due_date = start.value;
+    }
+    if let Some(end) = &end {
+        task.due_date = end.value;
+    }
+    task.priority = component
+        .property("PRIORITY")
+        .and_then(|property| property.value.trim().parse().ok())
+        .map(task_priority)
+        .unwrap_or_default();
+    if component.is_completed() {
+        task.status = TaskStatus::Completed;
+        if let Some(completed) = component.completed_time() {
+            task.completed_time = completed;
+        }
+    }
+    if let Some(rule) = component.property("RRULE") {
+        task.repeat_flag = format!("RRULE:{}", rule.value);
+    }
+    task.reminders = component
+        .children
+        .iter()
+        .filter(|alarm| alarm.name == "VALARM")
+        .filter_map(|alarm| alarm.property("TRIGGER"))
+        // Absolute triggers (`VALUE=DATE-TIME`) have no TickTick equivalent.
+        .filter(|trigger| trigger.param("VALUE").is_none())
+        .map(|trigger| format!("TRIGGER:{}", trigger.value))
+        .collect();
+    task.tags = component
+        .properties
+        .iter()
+        .filter(|property| property.name == "CATEGORIES")
+        .flat_map(|property| split_list(&property.value))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    task
+}
+
+fn component_to_subtask(component: &Component, time_zone: Tz) -> Subtask {
+    let mut subtask = Subtask::new(&component.text("SUMMARY").unwrap_or_default());
+    if let Some(start) = component
+        .property("DTSTART")
+        .and_then(|property| parse_date(property, time_zone))
+    {
+        subtask.start_date = start.value;
+        subtask.is_all_day = start.is_all_day;
+    }
+    if component.is_completed() {
+        subtask.status = SubtaskStatus::Completed;
+        if let Some(completed) = component.completed_time() {
+            subtask.completed_time = completed;
+        }
+    }
+    subtask
+}
+
+/// Inverse of `ical_priority`.
+fn task_priority(priority: u8) -> TaskPriority {
+    match priority {
+        0 => TaskPriority::None,
+        1..=4 => TaskPriority::High,
+        5 => TaskPriority::Medium,
+        _ => TaskPriority::Low,
+    }
+}
+
+struct ParsedDate {
+    value: DateTime<Utc>,
+    is_all_day: bool,
+    /// Time zone named by the property's TZID.
+    time_zone: Option<String>,
+}
+
+/// Parse a DATE or DATE-TIME value. Floating times and dates are in `TZID`, or `default` if it has none.
+fn parse_date(property: &Property, default: Tz) -> Option<ParsedDate> {
+    let named_zone = property
+        .param("TZID")
+        .and_then(|name| Tz::from_str(name).ok());
+    let time_zone = named_zone.unwrap_or(default);
+    let value = property.value.trim();
+    let (local, is_all_day) = if let Some(utc) = value.strip_suffix('Z') {
+        let datetime = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(ParsedDate {
+            value: datetime.and_utc(),
+            is_all_day: false,
+            time_zone: named_zone.map(|zone| zone.name().into()),
+        });
+    } else if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        (date.and_hms_opt(0, 0, 0)?, true)
+    } else {
+        (
+            NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?,
+            false,
+        )
+    };
+    Some(ParsedDate {
+        value: time_zone
+            .from_local_datetime(&local)
+            .earliest()?
+            .with_timezone(&Utc),
+        is_all_day,
+        time_zone: named_zone.map(|zone| zone.name().into()),
+    })
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug)]
+struct Component {
+    name: String,
+    properties: Vec<Property>,
+    children: Vec<Component>,
+}
+
+impl Component {
+    fn property(&self, name: &str) -> Option<&Property> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+    }
+    fn text(&self, name: &str) -> Option<String> {
+        self.property(name)
+            .map(|property| unescape_text(&property.value))
+    }
+    fn uid(&self) -> &str {
+        self.property("UID")
+            .map(|property| property.value.as_str())
+            .unwrap_or_default()
+    }
+    fn is_completed(&self) -> bool {
+        self.property("STATUS")
+            .is_some_and(|status| status.value.eq_ignore_ascii_case("COMPLETED"))
+            || self.property("COMPLETED").is_some()
+    }
+    fn completed_time(&self) -> Option<DateTime<Utc>> {
+        self.property("COMPLETED")
+            .and_then(|property| parse_date(property, Tz::UTC))
+            .map(|date| date.value)
+    }
+}
+
+/// Parse the components of an iCalendar file, unfolding lines and nesting components by BEGIN/END.
+fn parse_components(ics: &str) -> Result<Vec<Component>, ICalError> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.into()),
+        }
+    }
+    let mut stack: Vec<Component> = Vec::new();
+    let mut components = Vec::new();
+    for (number, line) in lines.iter().enumerate() {
+        let property = parse_property(line).ok_or_else(|| {
+            ICalError::ParseError(format!("Malformed content line {}: {}", number + 1, line))
+        })?;
+        match property.name.as_str() {
+            "BEGIN" => stack.push(Component {
+                name: property.value.to_ascii_uppercase(),
+                properties: Vec::new(),
+                children: Vec::new(),
+            }),
+            "END" => {
+                let component = stack
+                    .pop()
+                    .filter(|component| component.name.eq_ignore_ascii_case(&property.value))
+                    .ok_or_else(|| {
+                        ICalError::ParseError(format!("Unexpected END:{}", property.value))
+                    })?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(component),
+                    None => components.push(component),
+                }
+            }
+            _ => match stack.last_mut() {
+                Some(component) => component.properties.push(property),
+                None => {
+                    return Err(ICalError::ParseError(format!(
+                        "Property {} outside of a component",
+                        property.name
+                    )))
+                }
+            },
+        }
+    }
+    if let Some(component) = stack.last() {
+        return Err(ICalError::ParseError(format!(
+            "Missing END:{}",
+            component.name
+        )));
+    }
+    Ok(components)
+}
+
+/// Parse `NAME;PARAM=value;PARAM="quoted:value":VALUE`.
+fn parse_property(line: &str) -> Option<Property> {
+    let mut in_quotes = false;
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut value_start = None;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                segments.push(&line[start..index]);
+                start = index + 1;
+            }
+            ':' if !in_quotes => {
+                segments.push(&line[start..index]);
+                value_start = Some(index + 1);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let value = &line[value_start?..];
+    let (name, params) = segments.split_first()?;
+    if name.is_empty() {
+        return None;
+    }
+    let params = params
+        .iter()
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.to_ascii_uppercase(),
+                value.trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name: name.to_ascii_uppercase(),
+        params,
+        value: value.into(),
+    })
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Split a comma separated list of text values, e.g. CATEGORIES.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    items.last_mut().unwrap().push('\\');
+                    items.last_mut().unwrap().push(escaped);
+                }
+            }
+            ',' => items.push(String::new()),
+            _ => items.last_mut().unwrap().push(c),
+        }
+    }
+    items
+        .iter()
+        .map(|item| unescape_text(item.trim()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(fields: serde_json::Value) -> Task {
+        let mut task = serde_json::json!({ "id": "t1", "projectId": "p1", "title": "Pay rent" });
+        task.as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(task).unwrap()
+    }
+
+    fn lines(ics: &str) -> Vec<&str> {
+        ics.split("\r\n").collect()
+    }
+
+    #[test]
+    fn exports_todo() {
+        let ics = ICalExporter::new()
+            .calendar_name("Home")
+            .export(&[task(serde_json::json!({
+                "content": "Bank transfer; by noon",
+                "dueDate": "2024-03-01T15:00:00.000+0000",
+                "timeZone": "Europe/London",
+                "priority": 3,
+                "tags": ["bills", "home"],
+                "repeatFlag": "RRULE:FREQ=MONTHLY;INTERVAL=1",
+                "reminders": ["TRIGGER:-PT15M"],
+                "modifiedTime": "2024-02-20T08:00:00.000+0000",
+            }))]);
+        let lines = lines(&ics);
+        for expected in [
+            "X-WR-CALNAME:Home",
+            "UID:t1@ticktick.com",
+            "DTSTAMP:20240220T080000Z",
+            "SUMMARY:Pay rent",
+            r"DESCRIPTION:Bank transfer\; by noon",
+            "CATEGORIES:bills,home",
+            "RRULE:FREQ=MONTHLY;INTERVAL=1",
+            "DUE:20240301T150000Z",
+            "STATUS:NEEDS-ACTION",
+            "PRIORITY:5",
+            "TRIGGER;RELATED=END:-PT15M",
+        ] {
+            assert!(
+                lines.contains(&expected),
+                "missing {} in\n{}",
+                expected,
+                ics
+            );
+        }
+        assert!(!ics.contains("DTSTART"));
+    }
+
+    #[test]
+    fn exports_all_day_dates_in_task_time_zone() {
+        let ics = ICalExporter::new().export(&[task(serde_json::json!({
+            "isAllDay": true,
+            // Midnight on the 2nd in Shanghai.
+            "startDate": "2024-03-01T16:00:00.000+0000",
+            "timeZone": "Asia/Shanghai",
+        }))]);
+        assert!(lines(&ics).contains(&"DTSTART;VALUE=DATE:20240302"));
+    }
+
+    #[test]
+    fn exports_events_with_dates_only() {
+        let ics = ICalExporter::new()
+            .component(ICalComponent::Event)
+            .export(&[
+                task(serde_json::json!({
+                    "isAllDay": true,
+                    "startDate": "2024-03-01T00:00:00.000+0000",
+                    "timeZone": "UTC",
+                    "items": [{ "id": "s1", "title": "Log in" }],
+                })),
+                task(serde_json::json!({ "id": "t2", "title": "Someday" })),
+            ]);
+        let lines = lines(&ics);
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20240301"));
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20240302"));
+        assert!(lines.contains(&"RELATED-TO;RELTYPE=PARENT:t1@ticktick.com"));
+        assert!(!ics.contains("Someday"));
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let title = "é".repeat(60);
+        let ics = ICalExporter::new().export(&[task(serde_json::json!({ "title": title }))]);
+        assert!(lines(&ics).iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(lines(&unfolded).contains(&format!("SUMMARY:{}", title).as_str()));
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(escape_text("a,b;c\\d\r\ne"), r"a\,b\;c\\d\ne");
+    }
+
+    fn parse_tasks(ics: &str) -> Vec<Task> {
+        parse_components(ics).unwrap()[0]
+            .children
+            .iter()
+            .map(|component| component_to_task(component, Tz::UTC))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_tasks() {
+        let exported = task(serde_json::json!({
+            "content": "Bank transfer, by noon",
+            "isAllDay": true,
+            "startDate": "2024-03-01T16:00:00.000+0000",
+            "dueDate": "2024-03-02T16:00:00.000+0000",
+            "timeZone": "Asia/Shanghai",
+            "priority": 5,
+            "tags": ["bills", "a,b"],
+            "repeatFlag": "RRULE:FREQ=MONTHLY;INTERVAL=1",
+            "reminders": ["TRIGGER:-PT15M"],
+        }));
+        let imported = parse_tasks(&ICalExporter::new().export(std::slice::from_ref(&exported)));
+        let [imported] = &imported[..] else {
+            panic!("expected one task, got {:?}", imported);
+        };
+        assert_eq!(imported.title, exported.title);
+        assert_eq!(
+            imported.content,
+            "Bank transfer, by noon\n\niCalendar UID: t1@ticktick.com"
+        );
+        assert!(imported.is_all_day);
+        assert_eq!(imported.start_date, exported.start_date);
+        assert_eq!(imported.due_date, exported.due_date);
+        assert_eq!(imported.time_zone, "Asia/Shanghai");
+        assert_eq!(imported.priority, TaskPriority::High);
+        assert_eq!(imported.tags, exported.tags);
+        assert_eq!(imported.repeat_flag, exported.repeat_flag);
+        assert_eq!(imported.reminders, exported.reminders);
+    }
+
+    #[test]
+    fn round_trips_completed_events() {
+        let exported = task(serde_json::json!({
+            "isAllDay": true,
+            "startDate": "2024-03-01T00:00:00.000+0000",
+            "timeZone": "UTC",
+            "status": 2,
+            "completedTime": "2024-03-01T09:30:00.000+0000",
+        }));
+        let ics = ICalExporter::new().export(std::slice::from_ref(&exported));
+        let [imported] = &parse_tasks(&ics)[..] else {
+            panic!("expected one task");
+        };
+        assert_eq!(imported.status, TaskStatus::Completed);
+        assert_eq!(imported.completed_time, exported.completed_time);
+
+        let ics = ICalExporter::new()
+            .component(ICalComponent::Event)
+            .export(std::slice::from_ref(&exported));
+        let [imported] = &parse_tasks(&ics)[..] else {
+            panic!("expected one task");
+        };
+        // The exclusive DTEND of an all-day event is the day after its due date.
+        assert_eq!(imported.due_date, exported.start_date);
+    }
+
+    #[test]
+    fn parses_folded_and_quoted_lines() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:a\r\nSUMMARY:Call \r\n Alice\r\n\
+                   DUE;TZID=\"Europe/Paris\":20240301T090000\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let [task] = &parse_tasks(ics)[..] else {
+            panic!("expected one task");
+        };
+        assert_eq!(task.title, "Call Alice");
+        assert_eq!(task.time_zone, "Europe/Paris");
+        assert_eq!(task.due_date.to_rfc3339(), "2024-03-01T08:00:00+00:00");
+    }
+
+    #[test]
+    fn rejects_unbalanced_components() {
+        assert!(matches!(
+            parse_components("BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nEND:VCALENDAR\r\n"),
+            Err(ICalError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn finds_duplicates_by_content_or_exported_id() {
+        let existing = [
+            task(serde_json::json!({ "content": "Notes\n\niCalendar UID: a@example.com" })),
+            task(serde_json::json!({ "id": "t2" })),
+        ];
+        assert!(is_duplicate(&existing, "a@example.com"));
+        assert!(is_duplicate(&existing, "t2@ticktick.com"));
+        assert!(!is_duplicate(&existing, "b@example.com"));
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn import_checks_inbox_for_duplicates() {
+        use std::sync::Arc;
+
+        use crate::{testing::ScriptedTransport, AccessToken};
+
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .push_json(200, &serde_json::json!([{ "id": "p1", "name": "Work" }]))
+            .push_json(200, &serde_json::json!({ "tasks": [], "columns": [] }))
+            .push_json(
+                200,
+                &serde_json::json!({
+                    "tasks": [{ "id": "t1", "projectId": "inbox1", "title": "Call",
+                                "content": "iCalendar UID: a@example.com" }],
+                    "columns": [],
+                }),
+            );
+        let ticktick = TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        );
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTODO\r\nUID:a@example.com\r\nSUMMARY:Call\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:b@example.com\r\nSUMMARY:Write\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:c@example.com\r\nRELATED-TO:b@example.com\r\nSUMMARY:Outline\r\nEND:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+        let report = ICalImporter::new(&ticktick)
+            .dry_run(true)
+            .import(ics)
+            .await
+            .unwrap();
+        assert!(transport.requests()[2].url.ends_with("/project/inbox/data"));
+        assert_eq!(report.duplicates, ["a@example.com"]);
+        let [created] = &report.created[..] else {
+            panic!("expected one task, got {:?}", report.created);
+        };
+        assert_eq!(created.title, "Write");
+        assert_eq!(created.subtasks[0].title, "Outline");
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn import_flattens_nested_subtasks() {
+        use std::sync::Arc;
+
+        use crate::{testing::ScriptedTransport, AccessToken};
+
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_json(200, &serde_json::json!({ "tasks": [], "columns": [] }));
+        let ticktick = TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        );
+        let ics = "BEGIN:VCALENDAR\r\n\
+                   BEGIN:VTODO\r\nUID:step@x\r\nRELATED-TO:plan@x\r\nSUMMARY:Step\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:detail@x\r\nRELATED-TO:step@x\r\nSUMMARY:Detail\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:plan@x\r\nSUMMARY:Plan\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:review@x\r\nRELATED-TO:plan@x\r\nSUMMARY:Review\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:loop-a@x\r\nRELATED-TO:loop-b@x\r\nSUMMARY:A\r\nEND:VTODO\r\n\
+                   BEGIN:VTODO\r\nUID:loop-b@x\r\nRELATED-TO:loop-a@x\r\nSUMMARY:B\r\nEND:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+        let report = ICalImporter::new(&ticktick)
+            .project_id(ProjectID("p1".into()))
+            .dry_run(true)
+            .import(ics)
+            .await
+            .unwrap();
+        let [created] = &report.created[..] else {
+            panic!("expected one task, got {:?}", report.created);
+        };
+        assert_eq!(created.title, "Plan");
+        let subtasks: Vec<&str> = created
+            .subtasks
+            .iter()
+            .map(|subtask| subtask.title.as_str())
+            .collect();
+        assert_eq!(subtasks, ["Step", "Detail", "Review"]);
+    }
+}
@@ -1,23 +1,53 @@
 //! Simple, ergonomic Rust wrapper for the TickTick Open API
+pub mod backup;
+pub mod batch;
 pub mod builders;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod eisenhower;
+pub mod filter;
+#[cfg(feature = "ical")]
+pub mod ical;
+pub mod markdown;
 pub mod projects;
+pub mod quick_add;
+pub mod stats;
+pub mod sync;
+pub mod tags;
 pub mod tasks;
+pub mod templates;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "csv")]
+pub mod ticktick_csv;
 pub(crate) mod ticktick_datetime_format;
+pub mod todotxt;
+pub mod transport;
+#[cfg(feature = "views")]
+pub mod views;
+#[cfg(feature = "watch")]
+pub mod watch;
 use oauth2::{AuthUrl, ClientId, CsrfToken, RedirectUrl, Scope, TokenUrl};
-use projects::{Project, ProjectData, ProjectID};
-use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    Url,
+use projects::{Project, ProjectData, ProjectID, Projects};
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::Arc;
+use tasks::{Task, TaskID, Tasks};
+use transport::{
+    HttpMethod, HttpRequest, HttpResponse, ReqwestTransport, Transport, TransportError,
 };
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tasks::{Task, TaskID};
 
 /// Errors that can occur while calling the TickTick API.
 #[derive(Debug)]
 pub enum TickTickError {
     ClientError(reqwest::Error),
     ResponseParseError(serde_json::Error),
+    TransportError(TransportError),
+    /// The API responded with a non-success status code.
+    StatusError {
+        status: u16,
+        body: String,
+    },
 }
 
 impl From<reqwest::Error> for TickTickError {
@@ -32,114 +62,219 @@ impl From<serde_json::Error> for TickTickError {
     }
 }
 
+impl From<TransportError> for TickTickError {
+    fn from(value: TransportError) -> Self {
+        Self::TransportError(value)
+    }
+}
+
+impl std::fmt::Display for TickTickError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ClientError(err) => write!(f, "HTTP client error: {}", err),
+            Self::ResponseParseError(err) => write!(f, "Failed to parse response: {}", err),
+            Self::TransportError(err) => write!(f, "Transport error: {}", err),
+            Self::StatusError { status, body } => {
+                write!(f, "TickTick API responded with status {}: {}", status, body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TickTickError {}
+
+const DEFAULT_BASE_URL: &str = "https://ticktick.com";
+const API_PATH: &str = "/open/v1";
+
 /// Wraps an HTTP Client containing the API Authorization header.
 /// Used for making calls to and from the TickTick API.
-/// You can retrieve tasks and projects from here, but it might be more ergonomic to use `Task::get` or `Project::get`.
-#[derive(Debug)]
+/// Models such as `Task` and `Project` are plain data, all API calls go through this handle,
+/// either directly (`ticktick.tasks().update(&task)`) or through a `Bound` model (`task.publish_changes()`).
+/// Cloning is cheap, clones share the same underlying `Transport`.
+#[derive(Clone)]
 pub struct TickTick {
-    http_client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    authorization: String,
+    base_url: String,
+}
+
+impl std::fmt::Debug for TickTick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TickTick")
+            .field("transport", &self.transport)
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
 }
 
 impl TickTick {
     /// Create new TickTick wrapper using provided authorization.
     pub fn new(access_token: AccessToken) -> Result<Self, TickTickError> {
-        let mut headers_map = HeaderMap::new();
-        let mut auth_header_value =
-            HeaderValue::from_str(format!("Bearer {}", access_token.value).as_str())
-                .expect("Invalid access token value.");
-        auth_header_value.set_sensitive(true);
-        headers_map.insert(reqwest::header::AUTHORIZATION, auth_header_value);
-        let http_client_result = reqwest::Client::builder()
-            .default_headers(headers_map)
-            .build();
-        Ok(Self {
-            http_client: http_client_result?,
-        })
+        let http_client = reqwest::Client::builder().build()?;
+        Ok(Self::with_transport(
+            access_token,
+            Arc::new(ReqwestTransport::new(http_client)),
+        ))
+    }
+
+    /// Create new TickTick wrapper which sends all requests through `transport`.
+    pub fn with_transport(access_token: AccessToken, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            authorization: format!("Bearer {}", access_token.value),
+            base_url: DEFAULT_BASE_URL.into(),
+        }
     }
+
+    /// Send API requests to `base_url` (e.g. `http://127.0.0.1:8080`) instead of `https://ticktick.com`.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').into();
+        self
+    }
+
+    pub fn get_base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Task operations: get, update, complete, delete.
+    pub fn tasks(&self) -> Tasks<'_> {
+        Tasks::new(self)
+    }
+
+    /// Project operations: get, list, update, delete.
+    pub fn projects(&self) -> Projects<'_> {
+        Projects::new(self)
+    }
+
     /// Get Project Data using ProjectID
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-project-with-data)
     pub async fn get_project_data(
         &self,
         project_id: &ProjectID,
     ) -> Result<ProjectData, TickTickError> {
-        let resp = self
-            .http_client
-            .get(format!(
-                "https://ticktick.com/open/v1/project/{}/data",
-                project_id.0
-            ))
-            .send()
-            .await?
-            .error_for_status()?;
-        let mut project_data = resp.json::<ProjectData>().await?;
-        project_data
-            .tasks
-            .iter_mut()
-            .for_each(|task| task.http_client = self.http_client.clone());
-        Ok(project_data)
-    }
-    /// Get task using ProjectID & TaskID
+        self.projects().get_data(project_id).await
+    }
+    /// Get task using ProjectID & TaskID, bound to this handle.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-task-by-project-id-and-task-id)
     pub async fn get_task(
         &self,
         project_id: &ProjectID,
         task_id: &TaskID,
-    ) -> Result<Task, TickTickError> {
-        let resp = self
-            .http_client
-            .get(format!(
-                "https://ticktick.com/open/v1/project/{}/task/{}",
-                project_id.0, task_id.0
-            ))
-            .send()
-            .await?
-            .error_for_status()?;
-        let mut task = resp.json::<Task>().await?;
-        task.http_client = self.http_client.clone();
-        Ok(task)
-    }
-
-    /// Get all tasks associated with projects.
-    pub async fn get_all_tasks_in_projects(&self) -> Result<Vec<Task>, TickTickError> {
-        let projects = self.get_all_projects().await?;
-        let mut value: Vec<Task> = Vec::new();
-        for proj in projects {
-            value.append(&mut proj.get_tasks().await?);
-        }
-        Ok(value)
+    ) -> Result<Bound<Task>, TickTickError> {
+        Ok(self
+            .tasks()
+            .bind(self.tasks().get(project_id, task_id).await?))
     }
 
-    /// Get project using ProjectID
+    /// Get all tasks associated with projects, bound to this handle.
+    pub async fn get_all_tasks_in_projects(&self) -> Result<Vec<Bound<Task>>, TickTickError> {
+        Ok(self.bind_all(self.tasks().get_all_in_projects().await?))
+    }
+
+    /// Get project using ProjectID, bound to this handle.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-project-by-id)
-    pub async fn get_project(&self, project_id: &ProjectID) -> Result<Project, TickTickError> {
-        let resp = self
-            .http_client
-            .get(format!(
-                "https://ticktick.com/open/v1/project/{}",
-                project_id.0
-            ))
-            .send()
-            .await?
-            .error_for_status()?;
-        let mut proj = resp.json::<Project>().await?;
-        proj.http_client = self.http_client.clone();
-        Ok(proj)
-    }
-
-    /// Get user projects.
+    pub async fn get_project(
+        &self,
+        project_id: &ProjectID,
+    ) -> Result<Bound<Project>, TickTickError> {
+        Ok(self.projects().bind(self.projects().get(project_id).await?))
+    }
+
+    /// Get user projects, bound to this handle.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-user-project)
-    pub async fn get_all_projects(&self) -> Result<Vec<Project>, TickTickError> {
-        let mut projects = self
-            .http_client
-            .get("https://ticktick.com/open/v1/project/")
-            .send()
-            .await?
-            .json::<Vec<Project>>()
+    pub async fn get_all_projects(&self) -> Result<Vec<Bound<Project>>, TickTickError> {
+        Ok(self.bind_all(self.projects().get_all().await?))
+    }
+
+    /// Bind every value to one shared copy of this handle.
+    pub(crate) fn bind_all<T>(&self, values: Vec<T>) -> Vec<Bound<T>> {
+        let ticktick = Arc::new(self.clone());
+        values
+            .into_iter()
+            .map(|value| Bound::new(ticktick.clone(), value))
+            .collect()
+    }
+
+    pub(crate) async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<T, TickTickError> {
+        let resp = self.send(self.request(HttpMethod::Get, path)).await?;
+        Ok(serde_json::from_str(&resp.body)?)
+    }
+
+    pub(crate) async fn post_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, TickTickError> {
+        let resp = self
+            .send(self.request(HttpMethod::Post, path).json(body)?)
             .await?;
-        for proj in &mut projects {
-            proj.http_client = self.http_client.clone();
+        Ok(serde_json::from_str(&resp.body)?)
+    }
+
+    /// POST without a request body, ignoring the response body.
+    pub(crate) async fn post_empty(&self, path: &str) -> Result<(), TickTickError> {
+        self.send(self.request(HttpMethod::Post, path)).await?;
+        Ok(())
+    }
+
+    pub(crate) async fn delete(&self, path: &str) -> Result<(), TickTickError> {
+        self.send(self.request(HttpMethod::Delete, path)).await?;
+        Ok(())
+    }
+
+    fn request(&self, method: HttpMethod, path: &str) -> HttpRequest {
+        HttpRequest::new(method, format!("{}{}{}", self.base_url, API_PATH, path))
+            .header("Authorization", &self.authorization)
+    }
+
+    /// Send request through the transport, turning non-success statuses into `TickTickError::StatusError`.
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, TickTickError> {
+        let resp = self.transport.send(request).await?;
+        if !resp.is_success() {
+            return Err(TickTickError::StatusError {
+                status: resp.status,
+                body: resp.body,
+            });
         }
-        Ok(projects)
+        Ok(resp)
+    }
+}
+
+/// A `Task` or `Project` bound to the `TickTick` handle it came from, so the API can be called through the value
+/// itself (`task.publish_changes()`), like in 1.x. Derefs to the plain model, use `into_inner` to take it out.
+/// Returned by `TickTick::get_task`, `Task::get`, `build_and_publish` and the other 1.x entry points,
+/// or by `ticktick.tasks().bind(task)`.
+#[derive(Debug, Clone)]
+pub struct Bound<T> {
+    ticktick: Arc<TickTick>,
+    value: T,
+}
+
+impl<T> Bound<T> {
+    pub fn new(ticktick: Arc<TickTick>, value: T) -> Self {
+        Self { ticktick, value }
+    }
+    pub fn get_ticktick(&self) -> &Arc<TickTick> {
+        &self.ticktick
+    }
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> std::ops::Deref for Bound<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> std::ops::DerefMut for Bound<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
     }
 }
 
@@ -151,6 +286,13 @@ pub enum AuthorizationError {
         expected: CsrfToken,
         recieved: CsrfToken,
     },
+    TransportError(TransportError),
+    ResponseParseError(serde_json::Error),
+    /// The token endpoint responded with a non-success status code.
+    StatusError {
+        status: u16,
+        body: String,
+    },
 }
 
 impl From<reqwest::Error> for AuthorizationError {
@@ -159,6 +301,38 @@ impl From<reqwest::Error> for AuthorizationError {
     }
 }
 
+impl From<TransportError> for AuthorizationError {
+    fn from(value: TransportError) -> Self {
+        Self::TransportError(value)
+    }
+}
+
+impl From<serde_json::Error> for AuthorizationError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ResponseParseError(value)
+    }
+}
+
+impl std::fmt::Display for AuthorizationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReqwestClientError(err) => write!(f, "HTTP client error: {}", err),
+            Self::InvalidCSRFState { .. } => write!(f, "OAuth state did not match"),
+            Self::TransportError(err) => write!(f, "Transport error: {}", err),
+            Self::ResponseParseError(err) => write!(f, "Failed to parse token response: {}", err),
+            Self::StatusError { status, body } => {
+                write!(
+                    f,
+                    "Token endpoint responded with status {}: {}",
+                    status, body
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuthorizationError {}
+
 pub struct Authorization {}
 
 impl Authorization {
@@ -183,6 +357,8 @@ impl Authorization {
             authorization_url,
             csrf_state,
             auth_client,
+            transport: Arc::new(ReqwestTransport::default()),
+            base_url: DEFAULT_BASE_URL.into(),
         })
     }
 }
@@ -191,6 +367,8 @@ pub struct AwaitingAuthCode {
     pub authorization_url: Url,
     csrf_state: CsrfToken,
     auth_client: oauth2::basic::BasicClient,
+    transport: Arc<dyn Transport>,
+    base_url: String,
 }
 
 impl AwaitingAuthCode {
@@ -199,6 +377,18 @@ impl AwaitingAuthCode {
         &self.authorization_url
     }
 
+    /// Send the token request through `transport` instead of the default `ReqwestTransport`.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Request the access token from `base_url` instead of `https://ticktick.com`.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').into();
+        self
+    }
+
     /// Finish OAuth sequence and retrieve AccessToken
     pub async fn finish_auth(
         self,
@@ -206,26 +396,29 @@ impl AwaitingAuthCode {
         auth_code: String,
         state: String,
     ) -> Result<AccessToken, AuthorizationError> {
-        let http_client = reqwest::Client::new();
-        let mut token_request_form = HashMap::new();
-        token_request_form.insert("client_id", self.auth_client.client_id().as_str());
-        token_request_form.insert("client_secret", &client_secret);
-        token_request_form.insert("code", &auth_code);
-        token_request_form.insert("grant_type", "authorization_code");
-        token_request_form.insert("scope", "tasks:write tasks:read");
-        token_request_form.insert("redirect_uri", self.auth_client.redirect_url().unwrap());
         if &state != self.csrf_state.secret() {
             return Err(AuthorizationError::InvalidCSRFState {
                 expected: self.csrf_state,
                 recieved: CsrfToken::new(state),
             });
         };
-        let token_request_result = http_client
-            .post("https://ticktick.com/oauth/token")
-            .form(&token_request_form)
-            .send()
-            .await;
-        Ok(token_request_result?.json::<AccessToken>().await?)
+        let token_request =
+            HttpRequest::new(HttpMethod::Post, format!("{}/oauth/token", self.base_url)).form(&[
+                ("client_id", self.auth_client.client_id().as_str()),
+                ("client_secret", &client_secret),
+                ("code", &auth_code),
+                ("grant_type", "authorization_code"),
+                ("scope", "tasks:write tasks:read"),
+                ("redirect_uri", self.auth_client.redirect_url().unwrap()),
+            ]);
+        let resp = self.transport.send(token_request).await?;
+        if !resp.is_success() {
+            return Err(AuthorizationError::StatusError {
+                status: resp.status,
+                body: resp.body,
+            });
+        }
+        Ok(serde_json::from_str::<AccessToken>(&resp.body)?)
     }
 }
 
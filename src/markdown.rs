@@ -0,0 +1,745 @@
+//! Markdown export of projects, e.g. for pasting into reports and wikis, and import of Markdown checklists.
+//!
+//! Tasks are rendered as checkbox items, with subtasks as nested checkboxes and content as indented text. Kanban
+//! projects get a heading per column. Every line is rendered from a template, where `{{name}}` is replaced by a value
+//! and `{{#name}}...{{/name}}` is only kept if the value isn't empty:
+//! ```ignore
+//! let markdown = MarkdownExporter::new()
+//!     .task_template("- [{{check}}] {{title}}{{#due}} (due {{due}}){{/due}}")
+//!     .export(&project, &ticktick.projects().get_data(project.get_id()).await?);
+//! ```
+//! Task templates can use `check` (`x` or a space), `title`, `priority`, `due`, `tags` (as `#tag`), `id` and
+//! `project`. Subtask templates can use `check` and `title`, headings can use `name`.
+//!
+//! `MarkdownImporter` goes the other way, creating tasks from a checklist.
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::{
+    builders::TaskBuilder,
+    projects::{Project, ProjectData, ProjectID, ProjectViewMode},
+    tasks::{Subtask, SubtaskStatus, Task, TaskPriority, TaskStatus},
+    TickTick, TickTickError,
+};
+
+const DEFAULT_PROJECT_HEADING: &str = "# {{name}}";
+const DEFAULT_COLUMN_HEADING: &str = "## {{name}}";
+const DEFAULT_TASK_TEMPLATE: &str = "- [{{check}}] {{title}}{{#priority}} ({{priority}} priority){{/priority}}{{#due}}, due {{due}}{{/due}}{{#tags}} {{tags}}{{/tags}}";
+const DEFAULT_SUBTASK_TEMPLATE: &str = "- [{{check}}] {{title}}";
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Indentation of nested items and task content.
+const INDENT: &str = "  ";
+
+/// Renders projects as Markdown. Configure with the builder methods, then call `export`.
+#[derive(Debug, Clone)]
+pub struct MarkdownExporter {
+    project_heading: String,
+    column_heading: String,
+    task_template: String,
+    subtask_template: String,
+    date_format: String,
+    include_content: bool,
+}
+
+impl Default for MarkdownExporter {
+    fn default() -> Self {
+        Self {
+            project_heading: DEFAULT_PROJECT_HEADING.into(),
+            column_heading: DEFAULT_COLUMN_HEADING.into(),
+            task_template: DEFAULT_TASK_TEMPLATE.into(),
+            subtask_template: DEFAULT_SUBTASK_TEMPLATE.into(),
+            date_format: DEFAULT_DATE_FORMAT.into(),
+            include_content: true,
+        }
+    }
+}
+
+impl MarkdownExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Template of the project's heading. Defaults to `# {{name}}`, an empty template omits the heading.
+    pub fn project_heading(mut self, value: &str) -> Self {
+        self.project_heading = value.into();
+        self
+    }
+    /// Template of column headings in kanban projects. Defaults to `## {{name}}`.
+    pub fn column_heading(mut self, value: &str) -> Self {
+        self.column_heading = value.into();
+        self
+    }
+    /// Template of each task's line.
+    pub fn task_template(mut self, value: &str) -> Self {
+        self.task_template = value.into();
+        self
+    }
+    /// Template of each subtask's line. Defaults to `- [{{check}}] {{title}}`.
+    pub fn subtask_template(mut self, value: &str) -> Self {
+        self.subtask_template = value.into();
+        self
+    }
+    /// `chrono` format of due dates, in the local time zone. Defaults to `%Y-%m-%d`, times are appended for tasks that
+    /// aren't all-day.
+    pub fn date_format(mut self, value: &str) -> Self {
+        self.date_format = value.into();
+        self
+    }
+    /// Whether to render task content and descriptions as indented text. Defaults to true.
+    pub fn include_content(mut self, value: bool) -> Self {
+        self.include_content = value;
+        self
+    }
+
+    /// Render `project` and its tasks.
+    pub fn export(&self, project: &Project, data: &ProjectData) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        let heading = render(&self.project_heading, &[("name", project.name.clone())]);
+        if !heading.is_empty() {
+            lines.push(heading);
+            lines.push(String::new());
+        }
+        let mut tasks: Vec<&Task> = data.tasks.iter().collect();
+        tasks.sort_by_key(|task| task.sort_order);
+        if project.view_mode != ProjectViewMode::Kanban || data.columns.is_empty() {
+            for task in &tasks {
+                self.push_task(&mut lines, project, task);
+            }
+            return finish(lines);
+        }
+        let mut columns: Vec<_> = data.columns.iter().collect();
+        columns.sort_by_key(|column| column.get_sort_order());
+        // Tasks without a known column are listed before the first column heading.
+        let (unsorted, mut in_columns): (Vec<&Task>, Vec<&Task>) =
+            tasks.into_iter().partition(|task| {
+                task.column_id()
+                    .is_none_or(|id| columns.iter().all(|column| *column.get_id() != id))
+            });
+        for task in unsorted {
+            self.push_task(&mut lines, project, task);
+        }
+        for column in columns {
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+            lines.push(render(
+                &self.column_heading,
+                &[("name", column.get_name().into())],
+            ));
+            lines.push(String::new());
+            in_columns.retain(|task| {
+                if task.column_id().as_ref() != Some(column.get_id()) {
+                    return true;
+                }
+                self.push_task(&mut lines, project, task);
+                false
+            });
+        }
+        finish(lines)
+    }
+
+    /// Render several projects, separated by blank lines.
+    pub fn export_all<'a>(
+        &self,
+        projects: impl IntoIterator<Item = (&'a Project, &'a ProjectData)>,
+    ) -> String {
+        projects
+            .into_iter()
+            .map(|(project, data)| self.export(project, data))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn push_task(&self, lines: &mut Vec<String>, project: &Project, task: &Task) {
+        let priority = match task.priority {
+            TaskPriority::None => String::new(),
+            priority => priority.to_string(),
+        };
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        lines.push(render(
+            &self.task_template,
+            &[
+                ("check", check(task.status == TaskStatus::Completed)),
+                ("title", task.title.clone()),
+                ("priority", priority),
+                ("due", self.format_due(task)),
+                ("tags", tags.join(" ")),
+                ("id", task.get_id().0.clone()),
+                ("project", project.name.clone()),
+            ],
+        ));
+        if self.include_content {
+            for text in [&task.content, &task.desc] {
+                // Blank lines would end the list item.
+                for line in text.lines().filter(|line| !line.trim().is_empty()) {
+                    lines.push(format!("{}{}", INDENT, line.trim_end()));
+                }
+            }
+        }
+        for subtask in &task.subtasks {
+            lines.push(format!("{}{}", INDENT, self.render_subtask(subtask)));
+        }
+    }
+
+    fn render_subtask(&self, subtask: &Subtask) -> String {
+        render(
+            &self.subtask_template,
+            &[
+                ("check", check(subtask.status == SubtaskStatus::Completed)),
+                ("title", subtask.title.clone()),
+            ],
+        )
+    }
+
+    fn format_due(&self, task: &Task) -> String {
+        if task.due_date.timestamp() == 0 {
+            return String::new();
+        }
+        let local = task.due_date.with_timezone(&Local);
+        if task.is_all_day {
+            local.format(&self.date_format).to_string()
+        } else {
+            format!(
+                "{} {}",
+                local.format(&self.date_format),
+                local.format("%H:%M")
+            )
+        }
+    }
+}
+
+fn check(done: bool) -> String {
+    if done { "x" } else { " " }.into()
+}
+
+fn finish(mut lines: Vec<String>) -> String {
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Replace `{{name}}` with its value, and drop `{{#name}}...{{/name}}` sections whose value is empty.
+/// Unknown names are treated as empty.
+fn render(template: &str, values: &[(&str, String)]) -> String {
+    let values: HashMap<&str, &str> = values
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect();
+    render_with(template, &values)
+}
+
+pub(crate) fn render_with(template: &str, values: &HashMap<&str, &str>) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+        match tag.strip_prefix('#') {
+            Some(section) => {
+                let closing = format!("{{{{/{}}}}}", section);
+                let (inner, after) = rest.split_once(&closing).unwrap_or((rest, ""));
+                if values.get(section).is_some_and(|value| !value.is_empty()) {
+                    output.push_str(&render_with(inner, values));
+                }
+                rest = after;
+            }
+            None => output.push_str(values.get(tag).copied().unwrap_or_default()),
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Task or project that failed to be created by `MarkdownImporter::import`.
+#[derive(Debug)]
+pub struct ImportFailure {
+    /// Title of the task, or name of the project.
+    pub title: String,
+    pub error: TickTickError,
+}
+
+/// Result of `MarkdownImporter::import`.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Projects created for headings that didn't match an existing project. In dry-run mode these have no ID, and
+    /// tasks under them no project ID.
+    pub projects: Vec<Project>,
+    /// Tasks created, or in dry-run mode, the tasks that would be created.
+    pub created: Vec<Task>,
+    pub failures: Vec<ImportFailure>,
+}
+
+/// Creates tasks from a Markdown checklist, e.g. meeting notes.
+/// ```markdown
+/// # Website
+/// - [ ] Fix login redirect !high due:2026-10-20 #bug
+///   - [ ] Reproduce on staging
+///   - [x] Check logs
+///   Reported by support.
+/// ```
+/// Top-level checkbox items become tasks, items nested under them become subtasks and other indented text becomes
+/// the task's content. Headings select the project tasks below them are created in, by name, creating it if none
+/// matches. `#tag`, `!high` (or `!medium`, `!low`) and `due:YYYY-MM-DD` (optionally with `THH:MM`, in the local time
+/// zone) are taken out of the title.
+#[derive(Debug, Clone)]
+pub struct MarkdownImporter {
+    ticktick: TickTick,
+    project_id: Option<ProjectID>,
+    dry_run: bool,
+}
+
+impl MarkdownImporter {
+    pub fn new(ticktick: &TickTick) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            project_id: None,
+            dry_run: false,
+        }
+    }
+    /// Project for tasks before the first heading. Defaults to the Inbox.
+    pub fn project_id(mut self, value: ProjectID) -> Self {
+        self.project_id = Some(value);
+        self
+    }
+    /// Report what would be created without creating anything.
+    pub fn dry_run(mut self, value: bool) -> Self {
+        self.dry_run = value;
+        self
+    }
+
+    /// Create the tasks in `markdown`, continuing past failures.
+    pub async fn import(&self, markdown: &str) -> Result<ImportReport, TickTickError> {
+        let sections = parse_checklist(markdown);
+        let mut report = ImportReport::default();
+        let mut projects = if sections.iter().any(|section| section.heading.is_some()) {
+            self.ticktick.projects().get_all().await?
+        } else {
+            Vec::new()
+        };
+        // Names of the projects a dry run would create, which have no ID to match tasks to.
+        let mut planned: Vec<String> = Vec::new();
+        for section in sections {
+            if section.tasks.is_empty() {
+                continue;
+            }
+            let project_id = match &section.heading {
+                None => self.project_id.clone(),
+                Some(name) => match projects
+                    .iter()
+                    .find(|project| project.name.eq_ignore_ascii_case(name))
+                {
+                    Some(project) => Some(project.get_id().clone()),
+                    None if self.dry_run => {
+                        if !planned
+                            .iter()
+                            .any(|planned| planned.eq_ignore_ascii_case(name))
+                        {
+                            planned.push(name.clone());
+                            report.projects.push(Project {
+                                name: name.clone(),
+                                ..Default::default()
+                            });
+                        }
+                        None
+                    }
+                    None => match self
+                        .ticktick
+                        .projects()
+                        .builder(name)
+                        .build_and_publish()
+                        .await
+                    {
+                        Ok(project) => {
+                            let project = project.into_inner();
+                            report.projects.push(project.clone());
+                            let project_id = project.get_id().clone();
+                            projects.push(project);
+                            Some(project_id)
+                        }
+                        Err(error) => {
+                            report.failures.push(ImportFailure {
+                                title: name.clone(),
+                                error,
+                            });
+                            continue;
+                        }
+                    },
+                },
+            };
+            for mut task in section.tasks {
+                if let Some(project_id) = &project_id {
+                    task.project_id = project_id.clone();
+                }
+                if self.dry_run {
+                    report.created.push(task);
+                    continue;
+                }
+                match TaskBuilder::from_task(&self.ticktick, &task)
+                    .build_and_publish()
+                    .await
+                {
+                    Ok(created) => report.created.push(created.into_inner()),
+                    Err(error) => report.failures.push(ImportFailure {
+                        title: task.title,
+                        error,
+                    }),
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Tasks under a heading, or before the first one.
+struct Section {
+    heading: Option<String>,
+    tasks: Vec<Task>,
+}
+
+fn parse_checklist(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        heading: None,
+        tasks: Vec::new(),
+    }];
+    // Whether lines are currently continuing the last task.
+    let mut in_task = false;
+    for line in markdown.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent = line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum::<usize>();
+        let text = line.trim();
+        let section = sections.last_mut().unwrap();
+        if indent == 0 {
+            in_task = false;
+            if let Some(heading) = parse_heading(text) {
+                sections.push(Section {
+                    heading: Some(heading.into()),
+                    tasks: Vec::new(),
+                });
+            } else if let Some((Some(done), text)) = parse_list_item(text) {
+                section.tasks.push(parse_task(text, done));
+                in_task = true;
+            }
+            continue;
+        }
+        let Some(task) = section.tasks.last_mut().filter(|_| in_task) else {
+            continue;
+        };
+        match parse_list_item(text) {
+            Some((done, title)) => {
+                let mut subtask = Subtask::new(title);
+                if done == Some(true) {
+                    subtask.status = SubtaskStatus::Completed;
+                }
+                task.subtasks.push(subtask);
+            }
+            None if task.content.is_empty() => task.content = text.into(),
+            None => {
+                task.content.push('\n');
+                task.content.push_str(text);
+            }
+        }
+    }
+    sections
+}
+
+fn parse_heading(text: &str) -> Option<&str> {
+    let level = text.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let heading = text[level..].strip_prefix(' ')?.trim();
+    Some(heading.trim_end_matches('#').trim()).filter(|heading| !heading.is_empty())
+}
+
+/// Parse `- text` or `- [ ] text`, returning whether the checkbox is ticked, if there is one.
+fn parse_list_item(text: &str) -> Option<(Option<bool>, &str)> {
+    let rest = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("* "))
+        .or_else(|| text.strip_prefix("+ "))?
+        .trim_start();
+    for (checkbox, done) in [("[ ]", false), ("[x]", true), ("[X]", true)] {
+        if let Some(title) = rest.strip_prefix(checkbox) {
+            return Some((Some(done), title.trim()));
+        }
+    }
+    Some((None, rest))
+}
+
+/// Build a task from a checklist item, taking `#tag`, `!priority` and `due:` tokens out of the title.
+fn parse_task(text: &str, done: bool) -> Task {
+    let mut task = Task::default();
+    if done {
+        task.status = TaskStatus::Completed;
+    }
+    let mut title = Vec::new();
+    for word in text.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('#').filter(|tag| is_tag(tag)) {
+            task.tags.push(tag.into());
+        } else if let Some(priority) = word
+            .strip_prefix('!')
+            .and_then(|priority| priority.parse::<TaskPriority>().ok())
+        {
+            task.priority = priority;
+        } else if let Some((due, is_all_day)) = word.strip_prefix("due:").and_then(parse_due) {
+            task.due_date = due;
+            task.is_all_day = is_all_day;
+        } else {
+            title.push(word);
+        }
+    }
+    task.title = title.join(" ");
+    task
+}
+
+/// Tags can't start with a digit, so issue references like `#123` stay in the title.
+pub(crate) fn is_tag(tag: &str) -> bool {
+    tag.chars()
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '/')
+}
+
+fn parse_due(value: &str) -> Option<(DateTime<Utc>, bool)> {
+    let (datetime, is_all_day) = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => (date.and_hms_opt(0, 0, 0)?, true),
+        Err(_) => (
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").ok()?,
+            false,
+        ),
+    };
+    let local = Local.from_local_datetime(&datetime).earliest()?;
+    Some((local.with_timezone(&Utc), is_all_day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(view_mode: &str) -> Project {
+        serde_json::from_value(serde_json::json!({
+            "id": "p1",
+            "name": "Website",
+            "viewMode": view_mode,
+        }))
+        .unwrap()
+    }
+
+    fn data(value: serde_json::Value) -> ProjectData {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn renders_sections() {
+        let values = [("title", "Fix login".to_string()), ("due", String::new())];
+        assert_eq!(
+            render(
+                "{{title}}{{#due}} (due {{due}}){{/due}}{{#missing}}!{{/missing}}",
+                &values
+            ),
+            "Fix login"
+        );
+        let values = [("title", "Fix login".to_string()), ("due", "today".into())];
+        assert_eq!(
+            render(
+                "{{ title }}{{#due}} (due {{due}}){{/due}} {{unknown}}{{",
+                &values
+            ),
+            "Fix login (due today) {{"
+        );
+    }
+
+    #[test]
+    fn exports_list_project() {
+        let due = Local
+            .with_ymd_and_hms(2024, 5, 31, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let data = data(serde_json::json!({
+            "tasks": [
+                { "id": "t2", "title": "Write release notes", "sortOrder": 2, "status": 2 },
+                {
+                    "id": "t1",
+                    "title": "Fix login redirect",
+                    "sortOrder": 1,
+                    "priority": 5,
+                    "tags": ["bug"],
+                    "isAllDay": true,
+                    "dueDate": crate::ticktick_datetime_format::format(&due),
+                    "content": "Reported by support.\n\nSee ticket.",
+                    "items": [{ "id": "s1", "title": "Reproduce", "status": 1 }],
+                },
+            ],
+            "columns": [],
+        }));
+        assert_eq!(
+            MarkdownExporter::new().export(&project("list"), &data),
+            "# Website\n\
+             \n\
+             - [ ] Fix login redirect (high priority), due 2024-05-31 #bug\n  \
+             Reported by support.\n  \
+             See ticket.\n  \
+             - [x] Reproduce\n\
+             - [x] Write release notes\n"
+        );
+    }
+
+    #[test]
+    fn exports_kanban_columns() {
+        let data = data(serde_json::json!({
+            "tasks": [
+                { "id": "t1", "title": "Deploy", "columnId": "c2" },
+                { "id": "t2", "title": "Design", "columnId": "c1" },
+                { "id": "t3", "title": "Triage" },
+            ],
+            "columns": [
+                { "id": "c2", "projectId": "p1", "name": "Done", "sortOrder": 2 },
+                { "id": "c1", "projectId": "p1", "name": "To do", "sortOrder": 1 },
+            ],
+        }));
+        let markdown = MarkdownExporter::new()
+            .project_heading("")
+            .task_template("* {{title}} ({{project}})")
+            .export(&project("kanban"), &data);
+        assert_eq!(
+            markdown,
+            "* Triage (Website)\n\
+             \n\
+             ## To do\n\
+             \n\
+             * Design (Website)\n\
+             \n\
+             ## Done\n\
+             \n\
+             * Deploy (Website)\n"
+        );
+    }
+
+    #[test]
+    fn parses_checklist() {
+        let sections = parse_checklist(
+            "Intro text\n\
+             - [ ] Call Alice\n\
+             \n\
+             # Website #\n\
+             - [ ] Fix login redirect !high due:2024-05-31 #bug #123\n\
+             \x20 - [ ] Reproduce on staging\n\
+             \x20 - [x] Check logs\n\
+             \x20 Reported by support.\n\
+             \tSee ticket.\n\
+             - [x] Deploy due:2024-05-31T09:30\n\
+             - Not a task\n\
+             \x20 - [ ] Not a subtask\n",
+        );
+        let [intro, website] = &sections[..] else {
+            panic!("expected two sections");
+        };
+        assert_eq!(intro.heading, None);
+        assert_eq!(intro.tasks[0].title, "Call Alice");
+        assert_eq!(website.heading.as_deref(), Some("Website"));
+        let [fix, deploy] = &website.tasks[..] else {
+            panic!("expected two tasks, got {:?}", website.tasks);
+        };
+        assert_eq!(fix.title, "Fix login redirect #123");
+        assert_eq!(fix.priority, TaskPriority::High);
+        assert_eq!(fix.tags, ["bug"]);
+        assert!(fix.is_all_day);
+        assert_eq!(fix.due_date, parse_due("2024-05-31").unwrap().0);
+        assert_eq!(fix.content, "Reported by support.\nSee ticket.");
+        let subtasks: Vec<_> = fix
+            .subtasks
+            .iter()
+            .map(|subtask| (subtask.title.as_str(), subtask.status))
+            .collect();
+        assert_eq!(
+            subtasks,
+            [
+                ("Reproduce on staging", SubtaskStatus::Normal),
+                ("Check logs", SubtaskStatus::Completed)
+            ]
+        );
+        assert_eq!(deploy.status, TaskStatus::Completed);
+        assert!(!deploy.is_all_day);
+        assert_eq!(
+            deploy
+                .due_date
+                .with_timezone(&Local)
+                .format("%H:%M")
+                .to_string(),
+            "09:30"
+        );
+    }
+
+    #[test]
+    fn parses_headings_and_items() {
+        assert_eq!(parse_heading("## Notes ##"), Some("Notes"));
+        assert_eq!(parse_heading("#tag"), None);
+        assert_eq!(parse_heading("####### Too deep"), None);
+        assert_eq!(parse_list_item("* [X] Done"), Some((Some(true), "Done")));
+        assert_eq!(parse_list_item("+ Plain"), Some((None, "Plain")));
+        assert_eq!(parse_list_item("Text"), None);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn dry_run_matches_projects_by_name() {
+        use std::sync::Arc;
+
+        use crate::{testing::ScriptedTransport, AccessToken};
+
+        let transport = Arc::new(ScriptedTransport::new());
+        transport.push_json(200, &serde_json::json!([{ "id": "p1", "name": "Website" }]));
+        let ticktick = TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        );
+        let report = MarkdownImporter::new(&ticktick)
+            .project_id(ProjectID("p0".into()))
+            .dry_run(true)
+            .import(
+                "- [ ] Call Alice\n# website\n- [ ] Fix login\n# Blog\n- [ ] Write post\n# blog\n- [ ] Edit post\n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.requests().len(), 1);
+        let projects: Vec<_> = report
+            .projects
+            .iter()
+            .map(|project| &project.name)
+            .collect();
+        assert_eq!(projects, ["Blog"]);
+        let created: Vec<_> = report
+            .created
+            .iter()
+            .map(|task| (task.title.as_str(), task.project_id.0.as_str()))
+            .collect();
+        assert_eq!(
+            created,
+            [
+                ("Call Alice", "p0"),
+                ("Fix login", "p1"),
+                ("Write post", ""),
+                ("Edit post", "")
+            ]
+        );
+    }
+}
@@ -1,11 +1,14 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{TickTick, TickTickError};
+use std::sync::Arc;
+
+use crate::{ticktick_datetime_format, Bound, TickTick, TickTickError};
 
 use super::{builders::ProjectBuilder, tasks::Task};
 
 /// ID used to identify Projects from TickTick.
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct ProjectID(pub String);
 
@@ -16,7 +19,7 @@ impl ProjectID {
 }
 
 /// ID used to identify Project Groups from TickTick.
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct GroupID(pub String);
 
@@ -28,11 +31,9 @@ impl GroupID {
 
 /// TickTick Project info
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=project-1)
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Project {
-    #[serde(skip)]
-    pub(crate) http_client: reqwest::Client,
     pub(crate) id: ProjectID,
     pub name: String,
     pub color: String,
@@ -43,62 +44,120 @@ pub struct Project {
     pub view_mode: ProjectViewMode,
     pub permission: ProjectUserPermissions,
     pub kind: ProjectKind,
+    /// Fields returned by the API that aren't modeled above (e.g. `modifiedTime`, `etag`).
+    /// These are kept so they survive being sent back through `publish_changes`.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Project {
     pub fn builder(ticktick: &TickTick, name: String) -> ProjectBuilder {
         ProjectBuilder::new(ticktick, name)
     }
-    pub fn get_id(self) -> ProjectID {
-        self.id
+    pub fn get_id(&self) -> &ProjectID {
+        &self.id
     }
-    pub async fn get_data(&self) -> Result<ProjectData, TickTickError> {
-        let resp = self
-            .http_client
-            .get(format!(
-                "https://ticktick.com/open/v1/project/{}/data",
-                self.id.0
-            ))
-            .send()
-            .await?
-            .error_for_status()?;
-        let mut project_data = resp.json::<ProjectData>().await?;
-        project_data
-            .tasks
-            .iter_mut()
-            .for_each(|task| task.http_client = self.http_client.clone());
-        Ok(project_data)
-    }
-    pub async fn get_all(ticktick: &TickTick) -> Result<Vec<Project>, TickTickError> {
+    /// Time this project was last modified, if returned by the API.
+    pub fn modified_time(&self) -> Option<DateTime<Utc>> {
+        self.extra
+            .get("modifiedTime")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|value| ticktick_datetime_format::parse(value).ok())
+    }
+    /// Entity tag of this project, changes whenever the project is modified.
+    pub fn etag(&self) -> Option<&str> {
+        self.extra.get("etag").and_then(serde_json::Value::as_str)
+    }
+    /// Get all projects, bound to `ticktick`.
+    pub async fn get_all(ticktick: &TickTick) -> Result<Vec<Bound<Project>>, TickTickError> {
         ticktick.get_all_projects().await
     }
-    pub async fn get_tasks(&self) -> Result<Vec<Task>, TickTickError> {
-        Ok(self.get_data().await?.tasks)
+    /// Get project using ProjectID, bound to `ticktick`.
+    pub async fn get(ticktick: &TickTick, id: &ProjectID) -> Result<Bound<Project>, TickTickError> {
+        ticktick.get_project(id).await
+    }
+}
+
+impl Bound<Project> {
+    /// Get this project's tasks and columns, same as `ticktick.projects().get_data(project.get_id())`.
+    pub async fn get_data(&self) -> Result<ProjectData, TickTickError> {
+        self.ticktick.projects().get_data(&self.value.id).await
+    }
+    /// Get this project's tasks, bound to the same handle as the project.
+    pub async fn get_tasks(&self) -> Result<Vec<Bound<Task>>, TickTickError> {
+        Ok(self.ticktick.bind_all(self.get_data().await?.tasks))
     }
     pub async fn get_columns(&self) -> Result<Vec<Column>, TickTickError> {
         Ok(self.get_data().await?.columns)
     }
-    pub async fn get(ticktick: &TickTick, id: &ProjectID) -> Result<Project, TickTickError> {
-        ticktick.get_project(id).await
-    }
     /// Send changes made to this project to the TickTick API. Clients will require a refresh/sync for changes to take effect.
+    /// Same as `ticktick.projects().update(&project)`, without the returned project.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=update-project)
-    pub async fn publish_changes(&self) -> Result<(), reqwest::Error> {
-        self.http_client
-            .post(format!(
-                "https://ticktick.com/open/v1/project/{}",
-                self.id.0
-            ))
-            .json(self)
-            .send()
-            .await?
-            .text()
-            .await?;
+    pub async fn publish_changes(&self) -> Result<(), TickTickError> {
+        self.ticktick.projects().update(&self.value).await?;
         Ok(())
     }
+    /// Delete project, same as `ticktick.projects().delete(project.get_id())`.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=delete-project)
+    pub async fn delete(self) -> Result<(), TickTickError> {
+        self.ticktick.projects().delete(&self.value.id).await
+    }
+}
+
+/// Project operations, borrowed from a `TickTick` handle using `TickTick::projects`.
+#[derive(Debug, Clone, Copy)]
+pub struct Projects<'a> {
+    ticktick: &'a TickTick,
+}
+
+impl<'a> Projects<'a> {
+    pub(crate) fn new(ticktick: &'a TickTick) -> Self {
+        Self { ticktick }
+    }
+    /// Start building a new project. Call `build_and_publish` on the builder to create it.
+    pub fn builder(&self, name: &str) -> ProjectBuilder {
+        ProjectBuilder::new(self.ticktick, name.into())
+    }
+    /// Bind `project` to this handle, so it can be updated with `project.publish_changes()`.
+    pub fn bind(&self, project: Project) -> Bound<Project> {
+        Bound::new(Arc::new(self.ticktick.clone()), project)
+    }
+    /// Get project using ProjectID
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-project-by-id)
+    pub async fn get(&self, project_id: &ProjectID) -> Result<Project, TickTickError> {
+        self.ticktick
+            .get_json(&format!("/project/{}", project_id.0))
+            .await
+    }
+    /// Get user projects.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-user-project)
+    pub async fn get_all(&self) -> Result<Vec<Project>, TickTickError> {
+        self.ticktick.get_json("/project").await
+    }
+    /// Get Project Data (tasks & columns) using ProjectID
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-project-with-data)
+    pub async fn get_data(&self, project_id: &ProjectID) -> Result<ProjectData, TickTickError> {
+        self.ticktick
+            .get_json(&format!("/project/{}/data", project_id.0))
+            .await
+    }
+    /// Send changes made to a project to the TickTick API, returning the project as stored by TickTick.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=update-project)
+    pub async fn update(&self, project: &Project) -> Result<Project, TickTickError> {
+        self.ticktick
+            .post_json(&format!("/project/{}", project.id.0), project)
+            .await
+    }
+    /// Delete a project.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=delete-project)
+    pub async fn delete(&self, project_id: &ProjectID) -> Result<(), TickTickError> {
+        self.ticktick
+            .delete(&format!("/project/{}", project_id.0))
+            .await
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(from = "String", rename_all = "lowercase")]
 pub enum ProjectViewMode {
     #[default]
@@ -118,7 +177,7 @@ impl From<String> for ProjectViewMode {
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(from = "String", rename_all = "lowercase")]
 pub enum ProjectUserPermissions {
     #[default]
@@ -138,7 +197,7 @@ impl From<String> for ProjectUserPermissions {
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(from = "String", rename_all = "UPPERCASE")]
 pub enum ProjectKind {
     #[default]
@@ -158,14 +217,14 @@ impl From<String> for ProjectKind {
 
 /// TickTick ProjectData
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=projectdata)
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectData {
     pub tasks: Vec<Task>,
     pub columns: Vec<Column>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct ColumnID(pub String);
 
@@ -175,11 +234,26 @@ impl ColumnID {
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Column {
-    id: ColumnID,
-    project_id: ProjectID,
-    name: String,
-    sort_order: i64,
+    pub(crate) id: ColumnID,
+    pub(crate) project_id: ProjectID,
+    pub(crate) name: String,
+    pub(crate) sort_order: i64,
+}
+
+impl Column {
+    pub fn get_id(&self) -> &ColumnID {
+        &self.id
+    }
+    pub fn get_project_id(&self) -> &ProjectID {
+        &self.project_id
+    }
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn get_sort_order(&self) -> i64 {
+        self.sort_order
+    }
 }
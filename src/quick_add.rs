@@ -0,0 +1,715 @@
+//! Natural-language quick-add, like the task input of TickTick's apps.
+//!
+//! `parse` takes dates, times, repeats, `#tags`, `!priority` and a `^project` out of a line of text, leaving the rest
+//! as the title. Rules are English only and don't depend on the system locale, so numeric dates must be `YYYY-MM-DD`.
+//! ```text
+//! Pay rent tomorrow 9am #finance !!       "Pay rent", due tomorrow at 09:00, medium priority, tagged finance
+//! Dentist next friday at 14:30 !high      "Dentist", due the first Friday after today at 14:30, high priority
+//! Renew passport oct 20                   "Renew passport", all-day on the next October 20th
+//! Water plants every monday ^Home         "Water plants", repeats weekly on Mondays from the next Monday, in Home
+//! Standup every weekday at 9:30 ^Work     "Standup", repeats Monday to Friday at 09:30, in Work
+//! Pay invoices every 2 weeks              "Pay invoices", repeats every other week from today
+//! Call mom in 3 days !                    "Call mom", all-day 3 days from today, low priority
+//! Submit report by 2026-11-01 #work       "Submit report", all-day on 2026-11-01, tagged work
+//! Take out bins at 8pm                    "Take out bins", due today at 20:00, or tomorrow if that's passed
+//! ```
+//! Dates can be `today`, `tomorrow`, a weekday (`fri`, `this fri`, `next friday`), `next week`/`month`/`year`,
+//! `in N days`/`weeks`/`months`/`years`, `YYYY-MM-DD` or a month and day (`oct 20`, `20 october`, `oct 20 2027`),
+//! optionally after `on`, `by` or `due`. A weekday is the next one on or after today, `next <weekday>` the next one
+//! after today. Weekday abbreviations on their own need `on`, `by` or `due` before them, as in `on fri`. Times are `9am`, `9:30 pm`, `21:00`, `noon` or `at 9`.
+//! A bare hour after `at` is on the 24-hour clock, so `at 5` is 05:00 and `at 5pm` or `at 17` is 17:00.
+//!
+//! Repeats are `daily`, `weekly`, `monthly`, `yearly`, or `every` followed by `day`, `week`, `month`, `year`,
+//! `weekday`, `weekend`, `other week`, `N days`, or weekdays (`every mon, wed and fri`). Repeating tasks without a
+//! date are due on their first occurrence.
+//!
+//! Priorities are `!`, `!!` and `!!!` for low, medium and high, or `!low`, `!medium`, `!high` and `!none`. Project
+//! names containing spaces are written with `_` instead, as in `^Side_projects`. Only the first date, time, repeat
+//! and project are used, later ones stay in the title.
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+
+use crate::{
+    builders::TaskBuilder, markdown::is_tag, projects::ProjectID, tasks::TaskPriority, TickTick,
+    TickTickError,
+};
+
+/// Fields parsed from a quick-add line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    /// The line without any of the parsed tokens.
+    pub title: String,
+    pub due_date: Option<DateTime<Utc>>,
+    /// Whether the due date has no time.
+    pub is_all_day: bool,
+    pub priority: TaskPriority,
+    pub tags: Vec<String>,
+    /// Name of the `^project`, with `_` replaced by spaces.
+    pub project: Option<String>,
+    /// Repeat rule, in the `RRULE:...` form used by `Task::repeat_flag`.
+    pub repeat_flag: Option<String>,
+}
+
+/// Parse a quick-add line relative to the current local time.
+pub fn parse(text: &str) -> QuickAdd {
+    parse_at(text, &Local::now())
+}
+
+/// Parse a quick-add line relative to `now`. Dates and times are in `now`'s time zone.
+pub fn parse_at<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> QuickAdd {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let lower: Vec<String> = words
+        .iter()
+        .map(|word| word.to_lowercase().trim_end_matches(',').to_string())
+        .collect();
+    let today = now.date_naive();
+    let mut parsed = QuickAdd {
+        title: String::new(),
+        due_date: None,
+        is_all_day: false,
+        priority: TaskPriority::None,
+        tags: Vec::new(),
+        project: None,
+        repeat_flag: None,
+    };
+    let mut date = None;
+    let mut time = None;
+    let mut repeat = None;
+    let mut title = Vec::new();
+    let mut index = 0;
+    while index < words.len() {
+        let word = words[index];
+        let rest = &lower[index..];
+        if let Some(tag) = word.strip_prefix('#').filter(|tag| is_tag(tag)) {
+            parsed.tags.push(tag.into());
+            index += 1;
+            continue;
+        }
+        if let Some(priority) = parse_priority(word) {
+            parsed.priority = priority;
+            index += 1;
+            continue;
+        }
+        if parsed.project.is_none() {
+            if let Some(name) = word.strip_prefix('^').filter(|name| !name.is_empty()) {
+                parsed.project = Some(name.replace('_', " "));
+                index += 1;
+                continue;
+            }
+        }
+        if repeat.is_none() {
+            if let Some((value, used)) = parse_repeat(rest) {
+                repeat = Some(value);
+                index += used;
+                continue;
+            }
+        }
+        if date.is_none() {
+            if let Some((value, used)) = parse_date(rest, today) {
+                date = Some(value);
+                index += used;
+                continue;
+            }
+        }
+        if time.is_none() {
+            if let Some((value, used)) = parse_time(rest) {
+                time = Some(value);
+                index += used;
+                continue;
+            }
+        }
+        title.push(word);
+        index += 1;
+    }
+    parsed.title = title.join(" ");
+
+    // A time that has already passed today means the next occurrence of it.
+    let first_day = match time {
+        Some(time) if time <= now.time() => today + Days::new(1),
+        _ => today,
+    };
+    let due_day = date
+        .or_else(|| {
+            repeat
+                .as_ref()
+                .map(|repeat| repeat.first_on_or_after(first_day))
+        })
+        .or(time.map(|_| first_day));
+    if let Some(day) = due_day {
+        let local = day.and_time(time.unwrap_or(NaiveTime::MIN));
+        let timezone = now.timezone();
+        parsed.due_date = Some(
+            timezone
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|due| due.with_timezone(&Utc))
+                .unwrap_or_else(|| local.and_utc()),
+        );
+        parsed.is_all_day = time.is_none();
+    }
+    parsed.repeat_flag = repeat.map(|repeat| repeat.rule());
+    parsed
+}
+
+impl QuickAdd {
+    /// Start a builder with the parsed fields, looking up the `^project` by name (case-insensitively). If no project
+    /// matches, the task goes to the Inbox and `^project` is put back in the title.
+    pub async fn into_builder(self, ticktick: &TickTick) -> Result<TaskBuilder, TickTickError> {
+        let mut title = self.title;
+        let mut project_id = None;
+        if let Some(name) = &self.project {
+            project_id = find_project(ticktick, name).await?;
+            if project_id.is_none() {
+                if !title.is_empty() {
+                    title.push(' ');
+                }
+                title.push('^');
+                title.push_str(&name.replace(' ', "_"));
+            }
+        }
+        let mut builder = TaskBuilder::new(ticktick, title)
+            .priority(self.priority)
+            .tags(self.tags);
+        if let Some(project_id) = project_id {
+            builder = builder.project_id(project_id);
+        }
+        if let Some(due_date) = self.due_date {
+            builder = builder.due_date(due_date).is_all_day(self.is_all_day);
+        }
+        if let Some(repeat_flag) = &self.repeat_flag {
+            builder = builder.repeat_flag(repeat_flag);
+        }
+        Ok(builder)
+    }
+}
+
+async fn find_project(ticktick: &TickTick, name: &str) -> Result<Option<ProjectID>, TickTickError> {
+    let name = name.to_lowercase();
+    Ok(ticktick
+        .projects()
+        .get_all()
+        .await?
+        .into_iter()
+        .find(|project| project.name.to_lowercase() == name)
+        .map(|project| project.get_id().clone()))
+}
+
+fn parse_priority(word: &str) -> Option<TaskPriority> {
+    match word {
+        "!" => Some(TaskPriority::Low),
+        "!!" => Some(TaskPriority::Medium),
+        "!!!" => Some(TaskPriority::High),
+        // `from_str` also accepts API values, which would make `!1` low priority.
+        _ => word
+            .strip_prefix('!')
+            .filter(|name| name.chars().all(|c| c.is_ascii_alphabetic()))
+            .and_then(|name| name.parse().ok()),
+    }
+}
+
+/// Parse a date at the start of `words`, returning it with the number of words used.
+fn parse_date(words: &[String], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let skip = match words.first()?.as_str() {
+        "on" | "by" | "due" => 1,
+        _ => 0,
+    };
+    let words = &words[skip..];
+    let first = words.first()?.as_str();
+    let second = words.get(1).map(String::as_str);
+    let (date, used) = match first {
+        "today" => (today, 1),
+        "tomorrow" | "tmrw" => (today + Days::new(1), 1),
+        "next" | "this" => {
+            let date = match second? {
+                "week" if first == "next" => {
+                    today + Days::new(7 - u64::from(today.weekday().num_days_from_monday()))
+                }
+                "month" if first == "next" => (today + Months::new(1)).with_day(1)?,
+                "year" if first == "next" => NaiveDate::from_ymd_opt(today.year() + 1, 1, 1)?,
+                day => next_weekday(today, parse_weekday(day)?, first == "this"),
+            };
+            (date, 2)
+        }
+        "in" => {
+            let count = match second? {
+                "a" | "an" | "one" => 1,
+                count => count.parse().ok()?,
+            };
+            (add_units(today, count, words.get(2)?)?, 3)
+        }
+        _ => {
+            // Abbreviations like `sun` and `wed` are also English words, so they need a preceding `on`, `by` or `due`.
+            if let Some(weekday) = parse_weekday(first).filter(|_| skip == 1 || first.len() > 3) {
+                (next_weekday(today, weekday, true), 1)
+            } else if let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") {
+                (date, 1)
+            } else {
+                parse_month_day(words, today)?
+            }
+        }
+    };
+    Some((date, used + skip))
+}
+
+/// Parse `oct 20`, `october 20th` or `20 oct`, with an optional year after.
+fn parse_month_day(words: &[String], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let second = words.get(1)?;
+    let (month, day) = match parse_month(&words[0]) {
+        Some(month) => (month, parse_day(second)?),
+        None => (parse_month(second)?, parse_day(&words[0])?),
+    };
+    let year = words
+        .get(2)
+        .filter(|year| year.len() == 4)
+        .and_then(|year| year.parse::<i32>().ok());
+    match year {
+        Some(year) => Some((NaiveDate::from_ymd_opt(year, month, day)?, 3)),
+        None => {
+            let date = NaiveDate::from_ymd_opt(today.year(), month, day)
+                .filter(|date| *date >= today)
+                .or_else(|| NaiveDate::from_ymd_opt(today.year() + 1, month, day))?;
+            Some((date, 2))
+        }
+    }
+}
+
+fn parse_day(word: &str) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .unwrap_or(word);
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let word = word.trim_end_matches('.');
+    MONTHS
+        .iter()
+        .position(|month| word == *month || (word.len() >= 3 && month.starts_with(word)))
+        .map(|index| index as u32 + 1)
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    let word = word.trim_end_matches('.');
+    let weekday = match word.get(..3)? {
+        "mon" => Weekday::Mon,
+        "tue" => Weekday::Tue,
+        "wed" => Weekday::Wed,
+        "thu" => Weekday::Thu,
+        "fri" => Weekday::Fri,
+        "sat" => Weekday::Sat,
+        "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    // Accept `fri`, `friday` and `fridays`, but not other words starting with a day's abbreviation like `month`.
+    let name = format!("{}day", weekday_name(weekday));
+    let matches =
+        word.len() == 3 || name.starts_with(word) || word.strip_suffix('s') == Some(name.as_str());
+    matches.then_some(weekday)
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "mon",
+        Weekday::Tue => "tues",
+        Weekday::Wed => "wednes",
+        Weekday::Thu => "thurs",
+        Weekday::Fri => "fri",
+        Weekday::Sat => "satur",
+        Weekday::Sun => "sun",
+    }
+}
+
+/// The next `weekday` after `today`, or on `today` if `include_today` is set.
+fn next_weekday(today: NaiveDate, weekday: Weekday, include_today: bool) -> NaiveDate {
+    let mut days =
+        (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    if days == 0 && !include_today {
+        days = 7;
+    }
+    today + Days::new(u64::from(days))
+}
+
+fn add_units(date: NaiveDate, count: u32, unit: &str) -> Option<NaiveDate> {
+    match unit.trim_end_matches('s') {
+        "day" => date.checked_add_days(Days::new(u64::from(count))),
+        "week" => date.checked_add_days(Days::new(u64::from(count) * 7)),
+        "month" => date.checked_add_months(Months::new(count)),
+        "year" => date.checked_add_months(Months::new(count * 12)),
+        _ => None,
+    }
+}
+
+/// Parse a time at the start of `words`, returning it with the number of words used.
+fn parse_time(words: &[String]) -> Option<(NaiveTime, usize)> {
+    let at = words.first()? == "at";
+    let words = &words[usize::from(at)..];
+    let first = words.first()?.as_str();
+    if first == "noon" {
+        return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1 + usize::from(at)));
+    }
+    let meridiem = |word: &str| match word {
+        "am" | "a.m." => Some(false),
+        "pm" | "p.m." => Some(true),
+        _ => None,
+    };
+    // `9am`, `9 am`, `21:00`, or after `at`, just `9`.
+    let (clock, is_pm, used) = if let Some(index) = first.find(['a', 'p']) {
+        (&first[..index], Some(meridiem(&first[index..])?), 1)
+    } else if let Some(is_pm) = words.get(1).and_then(|word| meridiem(word)) {
+        (first, Some(is_pm), 2)
+    } else if first.contains(':') || at {
+        (first, None, 1)
+    } else {
+        return None;
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        None => (clock.parse().ok()?, 0),
+    };
+    let hour = match is_pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    Some((
+        NaiveTime::from_hms_opt(hour, minute, 0)?,
+        used + usize::from(at),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone)]
+struct Repeat {
+    frequency: Frequency,
+    interval: u32,
+    weekdays: Vec<Weekday>,
+}
+
+impl Repeat {
+    fn new(frequency: Frequency, interval: u32) -> Self {
+        Self {
+            frequency,
+            interval,
+            weekdays: Vec::new(),
+        }
+    }
+
+    fn rule(&self) -> String {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        let mut rule = format!("RRULE:FREQ={};INTERVAL={}", frequency, self.interval);
+        if !self.weekdays.is_empty() {
+            let days: Vec<&str> = self
+                .weekdays
+                .iter()
+                .map(|weekday| &weekday_name(*weekday)[..2])
+                .collect();
+            rule.push_str(";BYDAY=");
+            rule.push_str(&days.join(",").to_uppercase());
+        }
+        rule
+    }
+
+    fn first_on_or_after(&self, date: NaiveDate) -> NaiveDate {
+        self.weekdays
+            .iter()
+            .map(|weekday| next_weekday(date, *weekday, true))
+            .min()
+            .unwrap_or(date)
+    }
+}
+
+/// Parse a repeat at the start of `words`, returning it with the number of words used.
+fn parse_repeat(words: &[String]) -> Option<(Repeat, usize)> {
+    let frequency = |unit: &str| match unit.trim_end_matches('s') {
+        "day" => Some(Frequency::Daily),
+        "week" => Some(Frequency::Weekly),
+        "month" => Some(Frequency::Monthly),
+        "year" => Some(Frequency::Yearly),
+        _ => None,
+    };
+    match words.first()?.as_str() {
+        "daily" => return Some((Repeat::new(Frequency::Daily, 1), 1)),
+        "weekly" => return Some((Repeat::new(Frequency::Weekly, 1), 1)),
+        "monthly" => return Some((Repeat::new(Frequency::Monthly, 1), 1)),
+        "yearly" | "annually" => return Some((Repeat::new(Frequency::Yearly, 1), 1)),
+        "every" => {}
+        _ => return None,
+    }
+    let second = words.get(1)?.as_str();
+    if let Some(frequency) = frequency(second) {
+        return Some((Repeat::new(frequency, 1), 2));
+    }
+    let interval = match second {
+        "other" => Some(2),
+        count => count.parse().ok().filter(|count| *count > 0),
+    };
+    if let Some(interval) = interval {
+        return Some((Repeat::new(frequency(words.get(2)?)?, interval), 3));
+    }
+    let mut repeat = Repeat::new(Frequency::Weekly, 1);
+    match second {
+        "weekday" => {
+            repeat.weekdays = vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]
+        }
+        "weekend" => repeat.weekdays = vec![Weekday::Sat, Weekday::Sun],
+        _ => {}
+    }
+    if !repeat.weekdays.is_empty() {
+        return Some((repeat, 2));
+    }
+    // A list of weekdays, e.g. `every mon,wed and fri`.
+    let mut used = 1;
+    for word in &words[1..] {
+        let days: Option<Vec<Weekday>> = word
+            .split(',')
+            .filter(|day| !day.is_empty())
+            .map(parse_weekday)
+            .collect();
+        match days {
+            Some(days) if !days.is_empty() => repeat.weekdays.extend(days),
+            _ if word == "and" && !repeat.weekdays.is_empty() => {}
+            _ => break,
+        }
+        used += 1;
+    }
+    // Don't swallow a trailing `and`.
+    if words[used - 1] == "and" {
+        used -= 1;
+    }
+    if repeat.weekdays.is_empty() {
+        return None;
+    }
+    repeat
+        .weekdays
+        .sort_by_key(|weekday| weekday.num_days_from_monday());
+    repeat.weekdays.dedup();
+    Some((repeat, used))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    /// Wednesday 2026-10-14, 10:00 at UTC+2.
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2026-10-14T10:00:00+02:00").unwrap()
+    }
+
+    /// Due date in `now`'s time zone, as `YYYY-MM-DD` for all-day dates or `YYYY-MM-DD HH:MM`.
+    fn due(parsed: &QuickAdd) -> Option<String> {
+        let format = if parsed.is_all_day {
+            "%Y-%m-%d"
+        } else {
+            "%Y-%m-%d %H:%M"
+        };
+        parsed.due_date.map(|due| {
+            due.with_timezone(&now().timezone())
+                .format(format)
+                .to_string()
+        })
+    }
+
+    #[test]
+    fn parses_dates_and_times() {
+        let cases = [
+            (
+                "Pay rent tomorrow 9am",
+                "Pay rent",
+                Some("2026-10-15 09:00"),
+            ),
+            (
+                "Dentist next friday at 14:30",
+                "Dentist",
+                Some("2026-10-16 14:30"),
+            ),
+            (
+                "Renew passport oct 20",
+                "Renew passport",
+                Some("2026-10-20"),
+            ),
+            (
+                "Renew passport 20th october 2027",
+                "Renew passport",
+                Some("2027-10-20"),
+            ),
+            // Already passed this year.
+            ("Birthday mar 3", "Birthday", Some("2027-03-03")),
+            ("Call mom in 3 days", "Call mom", Some("2026-10-17")),
+            ("Call mom in a week", "Call mom", Some("2026-10-21")),
+            (
+                "Submit report by 2026-11-01",
+                "Submit report",
+                Some("2026-11-01"),
+            ),
+            ("Meet on wed", "Meet", Some("2026-10-14")),
+            ("Meet this wed", "Meet", Some("2026-10-14")),
+            ("Meet next wed", "Meet", Some("2026-10-21")),
+            ("Meet friday", "Meet", Some("2026-10-16")),
+            ("Plan next week", "Plan", Some("2026-10-19")),
+            ("Budget next month", "Budget", Some("2026-11-01")),
+            ("Taxes next year", "Taxes", Some("2027-01-01")),
+            ("Buy sun screen", "Buy sun screen", None),
+            ("Review monthly report", "Review report", Some("2026-10-14")),
+            (
+                "Call tomorrow or today",
+                "Call or today",
+                Some("2026-10-15"),
+            ),
+            (
+                "Take out bins at 8pm",
+                "Take out bins",
+                Some("2026-10-14 20:00"),
+            ),
+            ("Meeting 9 pm", "Meeting", Some("2026-10-14 21:00")),
+            ("Lunch noon", "Lunch", Some("2026-10-14 12:00")),
+            // Times that have passed today are tomorrow.
+            ("Ship at 12am", "Ship", Some("2026-10-15 00:00")),
+            ("Alarm at 9:30", "Alarm", Some("2026-10-15 09:30")),
+            ("Alarm at 5", "Alarm", Some("2026-10-15 05:00")),
+            ("Alarm at 17", "Alarm", Some("2026-10-14 17:00")),
+            ("Alarm at 5pm", "Alarm", Some("2026-10-14 17:00")),
+            ("Gate 25 at 25", "Gate 25 at 25", None),
+            ("Rent 13pm", "Rent 13pm", None),
+            ("Read 9:5", "Read 9:5", None),
+        ];
+        for (text, title, expected) in cases {
+            let parsed = parse_at(text, &now());
+            assert_eq!(parsed.title, title, "title of {:?}", text);
+            assert_eq!(due(&parsed).as_deref(), expected, "due date of {:?}", text);
+        }
+    }
+
+    #[test]
+    fn parses_repeats() {
+        let cases = [
+            ("Read daily", "RRULE:FREQ=DAILY;INTERVAL=1", "2026-10-14"),
+            (
+                "Backup every week",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1",
+                "2026-10-14",
+            ),
+            (
+                "Pay invoices every 2 weeks",
+                "RRULE:FREQ=WEEKLY;INTERVAL=2",
+                "2026-10-14",
+            ),
+            (
+                "Gym every other day",
+                "RRULE:FREQ=DAILY;INTERVAL=2",
+                "2026-10-14",
+            ),
+            (
+                "Water plants every monday",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO",
+                "2026-10-19",
+            ),
+            (
+                "Review every fri, mon and wed",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,WE,FR",
+                "2026-10-14",
+            ),
+            (
+                "Hike every weekend",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=SA,SU",
+                "2026-10-17",
+            ),
+            (
+                "Standup every weekday at 9:30",
+                "RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO,TU,WE,TH,FR",
+                "2026-10-15 09:30",
+            ),
+            // An explicit date overrides the first occurrence.
+            (
+                "Report yearly on 2026-12-31",
+                "RRULE:FREQ=YEARLY;INTERVAL=1",
+                "2026-12-31",
+            ),
+        ];
+        for (text, rule, expected) in cases {
+            let parsed = parse_at(text, &now());
+            assert_eq!(
+                parsed.repeat_flag.as_deref(),
+                Some(rule),
+                "repeat of {:?}",
+                text
+            );
+            assert_eq!(
+                due(&parsed).as_deref(),
+                Some(expected),
+                "due date of {:?}",
+                text
+            );
+        }
+        let parsed = parse_at("Call every and then", &now());
+        assert_eq!(parsed.repeat_flag, None);
+        assert_eq!(parsed.title, "Call every and then");
+    }
+
+    #[test]
+    fn parses_priority_tags_and_project() {
+        let cases = [
+            ("Call mom !", TaskPriority::Low, &[][..], None),
+            ("Call mom !!", TaskPriority::Medium, &[], None),
+            (
+                "Call mom !!! #family",
+                TaskPriority::High,
+                &["family"],
+                None,
+            ),
+            ("Call mom !high !none", TaskPriority::None, &[], None),
+            ("Fix #123 !1", TaskPriority::None, &[], None),
+            (
+                "Plan #work #q4 ^Side_projects ^Home",
+                TaskPriority::None,
+                &["work", "q4"],
+                Some("Side projects"),
+            ),
+        ];
+        for (text, priority, tags, project) in cases {
+            let parsed = parse_at(text, &now());
+            assert_eq!(parsed.priority, priority, "priority of {:?}", text);
+            assert_eq!(parsed.tags, tags, "tags of {:?}", text);
+            assert_eq!(parsed.project.as_deref(), project, "project of {:?}", text);
+        }
+        assert_eq!(parse_at("Fix #123 !1", &now()).title, "Fix #123 !1");
+        assert_eq!(parse_at("Plan #work ^A ^Home", &now()).title, "Plan ^Home");
+    }
+}
@@ -0,0 +1,515 @@
+//! Productivity statistics over tasks, exportable as JSON or CSV.
+//!
+//! Project data only lists open tasks, so completion statistics need tasks collected over time, e.g. the tasks of a
+//! `Snapshot` kept up to date with `Snapshot::fetch_since`, which keeps tasks completed since the last fetch.
+//! ```ignore
+//! let snapshot = Snapshot::fetch_since(&ticktick, &previous).await?;
+//! let stats = Stats::compute(&snapshot.tasks, &snapshot.projects);
+//! std::fs::write("stats.json", stats.to_json())?;
+//! std::fs::write("stats.csv", stats.to_csv())?;
+//! ```
+//! Days and weeks are in the local time zone, or the one of the time passed to `compute_at`. Weeks are ISO weeks,
+//! labelled like `2026-W42`.
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::{
+    projects::{Project, ProjectID},
+    tasks::{Task, TaskPriority, TaskStatus},
+    ticktick_datetime_format,
+};
+
+/// Statistics computed by `Stats::compute`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    #[serde(with = "ticktick_datetime_format")]
+    pub generated_time: DateTime<Utc>,
+    pub total_tasks: usize,
+    pub completed_tasks: usize,
+    /// Open tasks whose due date has passed.
+    pub overdue_tasks: usize,
+    /// Completed tasks that were completed after their due date.
+    pub late_completions: usize,
+    /// Share of tasks with a due date that are overdue or were completed late, from `0` to `1`.
+    pub overdue_rate: f64,
+    /// Average hours from creation to completion, over completed tasks with a known creation time.
+    pub average_hours_to_complete: Option<f64>,
+    /// Completed tasks per day, oldest first.
+    pub completed_per_day: Vec<PeriodCount>,
+    /// Completed tasks per ISO week, oldest first.
+    pub completed_per_week: Vec<PeriodCount>,
+    /// Tasks per project, most tasks first.
+    pub projects: Vec<ProjectStats>,
+    /// Tasks per priority, highest first.
+    pub priorities: Vec<GroupCount>,
+    /// Tasks per tag, most used first.
+    pub tags: Vec<GroupCount>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodCount {
+    /// `YYYY-MM-DD` for days, `YYYY-Www` for weeks.
+    pub period: String,
+    pub completed: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub project_id: ProjectID,
+    /// Empty if the project wasn't passed to `compute`.
+    pub project_name: String,
+    pub total: usize,
+    pub completed: usize,
+    pub overdue: usize,
+}
+
+/// Number of tasks with a priority or tag.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupCount {
+    pub name: String,
+    pub total: usize,
+    pub completed: usize,
+}
+
+impl Stats {
+    /// Compute statistics for `tasks` as of now, in the local time zone. `projects` are used for project names.
+    pub fn compute(tasks: &[Task], projects: &[Project]) -> Self {
+        Self::compute_at(tasks, projects, &Local::now())
+    }
+
+    /// Compute statistics for `tasks` as of `now`, with days in `now`'s time zone.
+    pub fn compute_at<Tz: TimeZone>(
+        tasks: &[Task],
+        projects: &[Project],
+        now: &DateTime<Tz>,
+    ) -> Self {
+        let zone = now.timezone();
+        let now_utc = now.with_timezone(&Utc);
+        let local_date = |date: &DateTime<Utc>| date.with_timezone(&zone).date_naive();
+        let mut stats = Self {
+            generated_time: now_utc,
+            total_tasks: tasks.len(),
+            completed_tasks: 0,
+            overdue_tasks: 0,
+            late_completions: 0,
+            overdue_rate: 0.0,
+            average_hours_to_complete: None,
+            completed_per_day: Vec::new(),
+            completed_per_week: Vec::new(),
+            projects: Vec::new(),
+            priorities: Vec::new(),
+            tags: Vec::new(),
+        };
+        let mut with_due_date = 0;
+        let mut hours_to_complete = Vec::new();
+        let mut per_day: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        let mut per_week: BTreeMap<String, usize> = BTreeMap::new();
+        let mut per_project: HashMap<&ProjectID, ProjectStats> = HashMap::new();
+        let mut per_priority: BTreeMap<TaskPriority, GroupCount> = BTreeMap::new();
+        let mut per_tag: HashMap<String, GroupCount> = HashMap::new();
+        for task in tasks {
+            let completed = task.status == TaskStatus::Completed;
+            let due = (task.due_date.timestamp() != 0).then_some(task.due_date);
+            let completed_time =
+                (completed && task.completed_time.timestamp() != 0).then_some(task.completed_time);
+            // All-day tasks are due for the whole day.
+            let is_late = |time: &DateTime<Utc>| match due {
+                Some(due) if task.is_all_day => local_date(time) > local_date(&due),
+                Some(due) => *time > due,
+                None => false,
+            };
+            let overdue = !completed && is_late(&now_utc);
+            let late = completed_time.is_some_and(|time| is_late(&time));
+            if due.is_some() {
+                with_due_date += 1;
+            }
+            if completed {
+                stats.completed_tasks += 1;
+            }
+            if overdue {
+                stats.overdue_tasks += 1;
+            }
+            if late {
+                stats.late_completions += 1;
+            }
+            if let Some(time) = completed_time {
+                let day = local_date(&time);
+                *per_day.entry(day).or_default() += 1;
+                *per_week
+                    .entry(day.format("%G-W%V").to_string())
+                    .or_default() += 1;
+                if let Some(created) = task.created_time().filter(|created| *created <= time) {
+                    hours_to_complete.push((time - created).num_seconds() as f64 / 3600.0);
+                }
+            }
+
+            let project = per_project
+                .entry(&task.project_id)
+                .or_insert_with(|| ProjectStats {
+                    project_id: task.project_id.clone(),
+                    project_name: projects
+                        .iter()
+                        .find(|project| *project.get_id() == task.project_id)
+                        .map(|project| project.name.clone())
+                        .unwrap_or_default(),
+                    total: 0,
+                    completed: 0,
+                    overdue: 0,
+                });
+            project.total += 1;
+            project.completed += usize::from(completed);
+            project.overdue += usize::from(overdue);
+
+            let count = |group: &mut GroupCount| {
+                group.total += 1;
+                group.completed += usize::from(completed);
+            };
+            count(
+                per_priority
+                    .entry(task.priority)
+                    .or_insert_with(|| GroupCount {
+                        name: task.priority.to_string(),
+                        total: 0,
+                        completed: 0,
+                    }),
+            );
+            for tag in &task.tags {
+                count(
+                    per_tag
+                        .entry(tag.to_lowercase())
+                        .or_insert_with(|| GroupCount {
+                            name: tag.clone(),
+                            total: 0,
+                            completed: 0,
+                        }),
+                );
+            }
+        }
+        if with_due_date > 0 {
+            stats.overdue_rate =
+                (stats.overdue_tasks + stats.late_completions) as f64 / with_due_date as f64;
+        }
+        if !hours_to_complete.is_empty() {
+            stats.average_hours_to_complete =
+                Some(hours_to_complete.iter().sum::<f64>() / hours_to_complete.len() as f64);
+        }
+        stats.completed_per_day = per_day
+            .into_iter()
+            .map(|(day, completed)| PeriodCount {
+                period: day.to_string(),
+                completed,
+            })
+            .collect();
+        stats.completed_per_week = per_week
+            .into_iter()
+            .map(|(period, completed)| PeriodCount { period, completed })
+            .collect();
+        stats.projects = per_project.into_values().collect();
+        stats.projects.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.project_name.cmp(&b.project_name))
+                .then_with(|| a.project_id.0.cmp(&b.project_id.0))
+        });
+        stats.priorities = per_priority.into_values().rev().collect();
+        stats.tags = per_tag.into_values().collect();
+        stats
+            .tags
+            .sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        stats
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Stats are always serializable")
+    }
+
+    /// Write the statistics as CSV in long format, one value per row, with `section,key,name,metric,value` columns:
+    /// ```text
+    /// section,key,name,metric,value
+    /// summary,,,completed_tasks,12
+    /// day,2026-10-18,,completed,3
+    /// project,6226ff9877acee87727f6bca,Work,overdue,1
+    /// tag,finance,,total,4
+    /// ```
+    /// Projects are keyed by ID, so projects sharing a name get their own rows, with the name (if known) in `name`.
+    pub fn to_csv(&self) -> String {
+        let mut rows: Vec<[String; 5]> = Vec::new();
+        let mut row = |section: &str, key: &str, name: &str, metric: &str, value: String| {
+            rows.push([
+                section.into(),
+                key.into(),
+                name.into(),
+                metric.into(),
+                value,
+            ]);
+        };
+        row(
+            "summary",
+            "",
+            "",
+            "total_tasks",
+            self.total_tasks.to_string(),
+        );
+        row(
+            "summary",
+            "",
+            "",
+            "completed_tasks",
+            self.completed_tasks.to_string(),
+        );
+        row(
+            "summary",
+            "",
+            "",
+            "overdue_tasks",
+            self.overdue_tasks.to_string(),
+        );
+        row(
+            "summary",
+            "",
+            "",
+            "late_completions",
+            self.late_completions.to_string(),
+        );
+        row(
+            "summary",
+            "",
+            "",
+            "overdue_rate",
+            self.overdue_rate.to_string(),
+        );
+        if let Some(hours) = self.average_hours_to_complete {
+            row(
+                "summary",
+                "",
+                "",
+                "average_hours_to_complete",
+                hours.to_string(),
+            );
+        }
+        for (section, periods) in [
+            ("day", &self.completed_per_day),
+            ("week", &self.completed_per_week),
+        ] {
+            for period in periods {
+                row(
+                    section,
+                    &period.period,
+                    "",
+                    "completed",
+                    period.completed.to_string(),
+                );
+            }
+        }
+        for project in &self.projects {
+            let (key, name) = (&project.project_id.0, &project.project_name);
+            row("project", key, name, "total", project.total.to_string());
+            row(
+                "project",
+                key,
+                name,
+                "completed",
+                project.completed.to_string(),
+            );
+            row("project", key, name, "overdue", project.overdue.to_string());
+        }
+        for (section, groups) in [("priority", &self.priorities), ("tag", &self.tags)] {
+            for group in groups {
+                row(section, &group.name, "", "total", group.total.to_string());
+                row(
+                    section,
+                    &group.name,
+                    "",
+                    "completed",
+                    group.completed.to_string(),
+                );
+            }
+        }
+        write_csv(&CSV_HEADER, &rows)
+    }
+}
+
+const CSV_HEADER: [&str; 5] = ["section", "key", "name", "metric", "value"];
+
+fn write_csv(header: &[&str], rows: &[[String; 5]]) -> String {
+    let mut csv = header.join(",") + "\n";
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote `value` if it contains a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    /// Wednesday 2026-10-14, 10:00 at UTC+2.
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2026-10-14T10:00:00+02:00").unwrap()
+    }
+
+    fn task(fields: serde_json::Value) -> Task {
+        let mut task = serde_json::json!({ "id": "t", "projectId": "p1", "title": "Task" });
+        task.as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(task).unwrap()
+    }
+
+    fn project(id: &str, name: &str) -> Project {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            // Completed on time, 26 hours after creation.
+            task(serde_json::json!({
+                "status": 2,
+                "priority": 5,
+                "tags": ["Work"],
+                "createdTime": "2026-10-11T08:00:00.000+0000",
+                "completedTime": "2026-10-12T10:00:00.000+0000",
+                "dueDate": "2026-10-12T12:00:00.000+0000",
+            })),
+            // All-day, completed late the next local day.
+            task(serde_json::json!({
+                "status": 2,
+                "tags": ["work", "home"],
+                "isAllDay": true,
+                "createdTime": "2026-10-12T08:00:00.000+0000",
+                "completedTime": "2026-10-13T23:30:00.000+0000",
+                "dueDate": "2026-10-12T22:00:00.000+0000",
+            })),
+            // Overdue.
+            task(serde_json::json!({
+                "projectId": "p2",
+                "priority": 3,
+                "dueDate": "2026-10-14T07:00:00.000+0000",
+            })),
+            // All-day and due today, not overdue.
+            task(serde_json::json!({
+                "projectId": "p3",
+                "isAllDay": true,
+                "dueDate": "2026-10-13T22:00:00.000+0000",
+            })),
+            task(serde_json::json!({ "projectId": "p4" })),
+        ]
+    }
+
+    #[test]
+    fn computes_stats() {
+        let projects = [
+            project("p1", "Work"),
+            project("p2", "Home"),
+            project("p3", "Work"),
+        ];
+        let stats = Stats::compute_at(&tasks(), &projects, &now());
+        assert_eq!(stats.total_tasks, 5);
+        assert_eq!(stats.completed_tasks, 2);
+        assert_eq!(stats.overdue_tasks, 1);
+        assert_eq!(stats.late_completions, 1);
+        assert_eq!(stats.overdue_rate, 0.5);
+        assert_eq!(stats.average_hours_to_complete, Some((26.0 + 39.5) / 2.0));
+        let periods = |counts: &[PeriodCount]| -> Vec<(String, usize)> {
+            counts
+                .iter()
+                .map(|count| (count.period.clone(), count.completed))
+                .collect()
+        };
+        assert_eq!(
+            periods(&stats.completed_per_day),
+            [("2026-10-12".into(), 1), ("2026-10-14".into(), 1)]
+        );
+        assert_eq!(periods(&stats.completed_per_week), [("2026-W42".into(), 2)]);
+        let projects: Vec<_> = stats
+            .projects
+            .iter()
+            .map(|project| {
+                (
+                    project.project_id.0.as_str(),
+                    project.project_name.as_str(),
+                    project.total,
+                )
+            })
+            .collect();
+        assert_eq!(
+            projects,
+            [
+                ("p1", "Work", 2),
+                ("p4", "", 1),
+                ("p2", "Home", 1),
+                ("p3", "Work", 1)
+            ]
+        );
+        let groups = |groups: &[GroupCount]| -> Vec<(String, usize, usize)> {
+            groups
+                .iter()
+                .map(|group| (group.name.clone(), group.total, group.completed))
+                .collect()
+        };
+        assert_eq!(
+            groups(&stats.priorities),
+            [
+                ("high".into(), 1, 1),
+                ("medium".into(), 1, 0),
+                ("none".into(), 3, 1)
+            ]
+        );
+        assert_eq!(
+            groups(&stats.tags),
+            [("Work".into(), 2, 2), ("home".into(), 1, 1)]
+        );
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        assert_eq!(csv_field("Work"), "Work");
+        assert_eq!(csv_field("Work, main"), "\"Work, main\"");
+        assert_eq!(csv_field("The \"big\" one"), "\"The \"\"big\"\" one\"");
+        assert_eq!(csv_field("Two\nlines"), "\"Two\nlines\"");
+    }
+
+    #[test]
+    fn csv_keeps_projects_with_the_same_name_apart() {
+        let projects = [project("p1", "Work, main"), project("p3", "Work, main")];
+        let csv = Stats::compute_at(&tasks(), &projects, &now()).to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "section,key,name,metric,value");
+        assert_eq!(lines[1], "summary,,,total_tasks,5");
+        for expected in [
+            "day,2026-10-12,,completed,1",
+            "week,2026-W42,,completed,2",
+            "project,p1,\"Work, main\",total,2",
+            "project,p3,\"Work, main\",total,1",
+            "project,p2,,overdue,1",
+            "priority,high,,completed,1",
+            "tag,Work,,total,2",
+        ] {
+            assert!(
+                lines.contains(&expected),
+                "missing {} in\n{}",
+                expected,
+                csv
+            );
+        }
+    }
+}
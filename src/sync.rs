@@ -0,0 +1,770 @@
+//! Two-way sync between a local copy of projects and tasks and the TickTick API.
+//!
+//! Keep the `Snapshot` from your last sync as the base. To sync, fetch the remote state, let a `SyncEngine`
+//! merge it with your local state (resolving conflicts with a `ConflictResolver`), apply `SyncPlan::to_local`
+//! to your own store, push `SyncPlan::to_remote` with `SyncEngine::apply`, and keep `SyncPlan::merged` as the next base.
+use std::collections::{HashMap, HashSet};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    projects::{Project, ProjectID},
+    tasks::{Task, TaskStatus},
+    TickTick, TickTickError,
+};
+
+/// State of an account's projects and tasks at a point in time.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub projects: Vec<Project>,
+    pub tasks: Vec<Task>,
+}
+
+impl Snapshot {
+    /// Fetch every project and its open tasks.
+    pub async fn fetch(ticktick: &TickTick) -> Result<Self, TickTickError> {
+        Self::fetch_projects(ticktick, |_| true).await
+    }
+    /// Fetch projects matching `filter`, and their open tasks.
+    pub async fn fetch_projects(
+        ticktick: &TickTick,
+        filter: impl Fn(&Project) -> bool,
+    ) -> Result<Self, TickTickError> {
+        let mut projects = ticktick.projects().get_all().await?;
+        projects.retain(|project| filter(project));
+        let mut tasks = Vec::new();
+        for project in &projects {
+            tasks.append(&mut ticktick.projects().get_data(project.get_id()).await?.tasks);
+        }
+        Ok(Self { projects, tasks })
+    }
+    /// Fetch current state, see `include_completed_since`.
+    pub async fn fetch_since(ticktick: &TickTick, base: &Snapshot) -> Result<Self, TickTickError> {
+        let mut snapshot = Self::fetch(ticktick).await?;
+        snapshot.include_completed_since(ticktick, base).await?;
+        Ok(snapshot)
+    }
+    /// Project data only lists open tasks. Look up tasks from `base` that are missing here,
+    /// so that tasks completed since `base` are reported as completed rather than deleted.
+    /// Tasks already completed in `base` are assumed unchanged. Only a 404 means a task was deleted,
+    /// any other error is returned.
+    pub async fn include_completed_since(
+        &mut self,
+        ticktick: &TickTick,
+        base: &Snapshot,
+    ) -> Result<(), TickTickError> {
+        let present: HashSet<_> = self
+            .tasks
+            .iter()
+            .map(|task| task.get_id().clone())
+            .collect();
+        let projects: HashSet<_> = self
+            .projects
+            .iter()
+            .map(|project| project.get_id().clone())
+            .collect();
+        for task in &base.tasks {
+            if present.contains(task.get_id()) || !projects.contains(&task.project_id) {
+                continue;
+            }
+            if task.status == TaskStatus::Completed {
+                self.tasks.push(task.clone());
+                continue;
+            }
+            match ticktick.tasks().get(&task.project_id, task.get_id()).await {
+                Ok(task) if task.status == TaskStatus::Completed => self.tasks.push(task),
+                // Deleted since `base`.
+                Ok(_) | Err(TickTickError::StatusError { status: 404, .. }) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+    pub fn get_project(&self, id: &ProjectID) -> Option<&Project> {
+        self.projects.iter().find(|project| project.get_id() == id)
+    }
+}
+
+/// Change to a single project between two snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectChange {
+    Created(Project),
+    Updated {
+        before: Project,
+        after: Project,
+        changed_fields: Vec<String>,
+    },
+    Deleted(Project),
+}
+
+/// Change to a single task between two snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskChange {
+    Created(Task),
+    Updated {
+        before: Task,
+        after: Task,
+        changed_fields: Vec<String>,
+    },
+    /// Task status changed to `TaskStatus::Completed`. Other fields may have changed too.
+    Completed {
+        before: Task,
+        after: Task,
+    },
+    Deleted(Task),
+}
+
+/// Every change between two snapshots.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChangeSet {
+    pub projects: Vec<ProjectChange>,
+    pub tasks: Vec<TaskChange>,
+}
+
+impl ChangeSet {
+    /// Changes turning `before` into `after`. Entities are matched by ID, entities without an ID (not yet created in TickTick) by value.
+    pub fn between(before: &Snapshot, after: &Snapshot) -> Self {
+        let projects = diff_entities(&before.projects, &after.projects)
+            .into_iter()
+            .map(|(before, after)| match (before, after) {
+                (None, Some(after)) => ProjectChange::Created(after.clone()),
+                (Some(before), None) => ProjectChange::Deleted(before.clone()),
+                (Some(before), Some(after)) => ProjectChange::Updated {
+                    changed_fields: changed_fields(before, after),
+                    before: before.clone(),
+                    after: after.clone(),
+                },
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        let tasks = diff_entities(&before.tasks, &after.tasks)
+            .into_iter()
+            .map(|(before, after)| match (before, after) {
+                (None, Some(after)) => TaskChange::Created(after.clone()),
+                (Some(before), None) => TaskChange::Deleted(before.clone()),
+                (Some(before), Some(after))
+                    if before.status != TaskStatus::Completed
+                        && after.status == TaskStatus::Completed =>
+                {
+                    TaskChange::Completed {
+                        before: before.clone(),
+                        after: after.clone(),
+                    }
+                }
+                (Some(before), Some(after)) => TaskChange::Updated {
+                    changed_fields: changed_fields(before, after),
+                    before: before.clone(),
+                    after: after.clone(),
+                },
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        Self { projects, tasks }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty() && self.tasks.is_empty()
+    }
+}
+
+/// Names (as used by the API, e.g. `dueDate`) of the top-level fields that differ between two values.
+pub fn changed_fields<T: Serialize>(before: &T, after: &T) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(before)), Ok(serde_json::Value::Object(after))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Project or task, identified by ID within a snapshot.
+trait SyncEntity: Serialize + DeserializeOwned + Clone + PartialEq {
+    fn key(&self) -> &str;
+}
+
+impl SyncEntity for Project {
+    fn key(&self) -> &str {
+        &self.get_id().0
+    }
+}
+
+impl SyncEntity for Task {
+    fn key(&self) -> &str {
+        &self.get_id().0
+    }
+}
+
+/// Pairs of differing entities, in `after` order followed by deletions in `before` order.
+fn diff_entities<'a, T: SyncEntity>(
+    before: &'a [T],
+    after: &'a [T],
+) -> Vec<(Option<&'a T>, Option<&'a T>)> {
+    let before_by_key: HashMap<&str, &T> = before
+        .iter()
+        .filter(|entity| !entity.key().is_empty())
+        .map(|entity| (entity.key(), entity))
+        .collect();
+    let after_keys: HashSet<&str> = after.iter().map(|entity| entity.key()).collect();
+    let mut changes = Vec::new();
+    for entity in after {
+        if entity.key().is_empty() {
+            // Not yet created in TickTick, only new if it isn't already in `before`.
+            if !before.contains(entity) {
+                changes.push((None, Some(entity)));
+            }
+            continue;
+        }
+        match before_by_key.get(entity.key()) {
+            Some(previous) if *previous == entity => {}
+            Some(previous) => changes.push((Some(*previous), Some(entity))),
+            None => changes.push((None, Some(entity))),
+        }
+    }
+    for entity in before {
+        if !entity.key().is_empty() && !after_keys.contains(entity.key()) {
+            changes.push((Some(entity), None));
+        }
+    }
+    changes
+}
+
+/// Side of a sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Local,
+    Remote,
+}
+
+/// Decides what happens when the same project or task changed both locally and remotely since the last sync.
+/// Entities are passed as their JSON representation, so one resolver handles both projects and tasks.
+pub trait ConflictResolver {
+    /// Entity was modified on both sides. `base` is its state at the last sync, if it existed then.
+    fn merge(
+        &self,
+        base: Option<&serde_json::Value>,
+        local: &serde_json::Value,
+        remote: &serde_json::Value,
+    ) -> serde_json::Value;
+    /// Entity was deleted on `deleted_side` and modified on the other. Return true to keep the modified entity.
+    fn keep_modified(&self, deleted_side: Side) -> bool;
+}
+
+/// Remote changes override local ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoteWins;
+
+impl ConflictResolver for RemoteWins {
+    fn merge(
+        &self,
+        _base: Option<&serde_json::Value>,
+        _local: &serde_json::Value,
+        remote: &serde_json::Value,
+    ) -> serde_json::Value {
+        remote.clone()
+    }
+    fn keep_modified(&self, deleted_side: Side) -> bool {
+        deleted_side == Side::Local
+    }
+}
+
+/// Local changes override remote ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalWins;
+
+impl ConflictResolver for LocalWins {
+    fn merge(
+        &self,
+        _base: Option<&serde_json::Value>,
+        local: &serde_json::Value,
+        _remote: &serde_json::Value,
+    ) -> serde_json::Value {
+        local.clone()
+    }
+    fn keep_modified(&self, deleted_side: Side) -> bool {
+        deleted_side == Side::Remote
+    }
+}
+
+/// Three-way merge per field: fields changed on only one side take that side's value,
+/// fields changed on both take the value from `prefer`. Modified entities are never deleted.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldMerge {
+    pub prefer: Side,
+}
+
+impl Default for FieldMerge {
+    fn default() -> Self {
+        Self {
+            prefer: Side::Remote,
+        }
+    }
+}
+
+impl ConflictResolver for FieldMerge {
+    fn merge(
+        &self,
+        base: Option<&serde_json::Value>,
+        local: &serde_json::Value,
+        remote: &serde_json::Value,
+    ) -> serde_json::Value {
+        let preferred = match self.prefer {
+            Side::Local => local,
+            Side::Remote => remote,
+        };
+        let (Some(local_fields), Some(remote_fields)) = (local.as_object(), remote.as_object())
+        else {
+            return preferred.clone();
+        };
+        let base_fields = base.and_then(|base| base.as_object());
+        let mut merged = serde_json::Map::new();
+        for key in local_fields.keys().chain(remote_fields.keys()) {
+            if merged.contains_key(key) {
+                continue;
+            }
+            let base_value = base_fields.and_then(|fields| fields.get(key));
+            let local_value = local_fields.get(key);
+            let remote_value = remote_fields.get(key);
+            let value = if local_value == base_value {
+                remote_value
+            } else if remote_value == base_value || local_value == remote_value {
+                local_value
+            } else {
+                preferred.get(key)
+            };
+            if let Some(value) = value {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        serde_json::Value::Object(merged)
+    }
+    fn keep_modified(&self, _deleted_side: Side) -> bool {
+        true
+    }
+}
+
+/// Entity modified on both sides, and how it was resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub id: String,
+    pub local: Option<serde_json::Value>,
+    pub remote: Option<serde_json::Value>,
+    pub resolved: Option<serde_json::Value>,
+}
+
+/// Result of merging local and remote state.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SyncPlan {
+    /// Changes to apply to the local state to reach `merged`.
+    pub to_local: ChangeSet,
+    /// Changes to push to TickTick to reach `merged`, see `SyncEngine::apply`.
+    pub to_remote: ChangeSet,
+    pub conflicts: Vec<Conflict>,
+    /// State after syncing, keep this as the base for the next sync.
+    /// Tasks created by `to_remote` get their IDs from TickTick, refetch to pick them up.
+    pub merged: Snapshot,
+}
+
+/// Failure to push a single change to TickTick.
+#[derive(Debug)]
+pub struct ApplyFailure {
+    /// ID of the project or task, empty for newly created ones.
+    pub id: String,
+    pub error: TickTickError,
+}
+
+/// Merges local and remote state, resolving conflicts with `R`.
+#[derive(Debug, Clone, Default)]
+pub struct SyncEngine<R: ConflictResolver = RemoteWins> {
+    resolver: R,
+}
+
+impl<R: ConflictResolver> SyncEngine<R> {
+    pub fn new(resolver: R) -> Self {
+        Self { resolver }
+    }
+
+    /// Three-way merge of `local` and `remote`, both derived from `base`.
+    pub fn plan(&self, base: &Snapshot, local: &Snapshot, remote: &Snapshot) -> SyncPlan {
+        let mut conflicts = Vec::new();
+        let merged = Snapshot {
+            projects: self.merge_entities(
+                &base.projects,
+                &local.projects,
+                &remote.projects,
+                &mut conflicts,
+            ),
+            tasks: self.merge_entities(&base.tasks, &local.tasks, &remote.tasks, &mut conflicts),
+        };
+        SyncPlan {
+            to_local: ChangeSet::between(local, &merged),
+            to_remote: ChangeSet::between(remote, &merged),
+            conflicts,
+            merged,
+        }
+    }
+
+    /// Fetch remote state and plan a sync against it.
+    pub async fn fetch_and_plan(
+        &self,
+        ticktick: &TickTick,
+        base: &Snapshot,
+        local: &Snapshot,
+    ) -> Result<SyncPlan, TickTickError> {
+        let remote = Snapshot::fetch_since(ticktick, base).await?;
+        Ok(self.plan(base, local, &remote))
+    }
+
+    /// Push `changes` to TickTick through the regular create/update/complete/delete calls.
+    /// Every change is attempted; failures are collected rather than stopping the sync.
+    pub async fn apply(&self, ticktick: &TickTick, changes: &ChangeSet) -> Vec<ApplyFailure> {
+        let mut failures = Vec::new();
+        let mut record = |id: &str, result: Result<(), TickTickError>| {
+            if let Err(error) = result {
+                failures.push(ApplyFailure {
+                    id: id.into(),
+                    error,
+                });
+            }
+        };
+        for change in &changes.projects {
+            let result = match change {
+                ProjectChange::Created(project) => {
+                    let mut builder = ticktick
+                        .projects()
+                        .builder(&project.name)
+                        .view_mode(project.view_mode)
+                        .kind(project.kind);
+                    if !project.color.is_empty() {
+                        builder = builder.color(&project.color);
+                    }
+                    builder.build_and_publish().await.map(drop)
+                }
+                ProjectChange::Updated { after, .. } => {
+                    ticktick.projects().update(after).await.map(drop)
+                }
+                ProjectChange::Deleted(_) => continue,
+            };
+            record(change_project_id(change), result);
+        }
+        for change in &changes.tasks {
+            let (id, result) = match change {
+                TaskChange::Created(task) => {
+                    (task.get_id(), ticktick.tasks().create(task).await.map(drop))
+                }
+                TaskChange::Updated { after, .. } => (
+                    after.get_id(),
+                    ticktick.tasks().update(after).await.map(drop),
+                ),
+                TaskChange::Completed { before, after } => {
+                    let mut result = Ok(());
+                    if changed_fields(before, after) != ["completedTime", "status"] {
+                        let mut open = after.clone();
+                        open.status = before.status;
+                        result = ticktick.tasks().update(&open).await.map(drop);
+                    }
+                    if result.is_ok() {
+                        result = ticktick.tasks().complete(after).await;
+                    }
+                    (after.get_id(), result)
+                }
+                TaskChange::Deleted(task) => (task.get_id(), ticktick.tasks().delete(task).await),
+            };
+            record(&id.0, result);
+        }
+        // Delete projects last, so tasks are moved out of them first.
+        for change in &changes.projects {
+            if let ProjectChange::Deleted(project) = change {
+                record(
+                    &project.get_id().0,
+                    ticktick.projects().delete(project.get_id()).await,
+                );
+            }
+        }
+        failures
+    }
+
+    fn merge_entities<T: SyncEntity>(
+        &self,
+        base: &[T],
+        local: &[T],
+        remote: &[T],
+        conflicts: &mut Vec<Conflict>,
+    ) -> Vec<T> {
+        let by_key = |entities: &[T]| -> HashMap<String, T> {
+            entities
+                .iter()
+                .filter(|entity| !entity.key().is_empty())
+                .map(|entity| (entity.key().to_string(), entity.clone()))
+                .collect()
+        };
+        let (base_by_key, local_by_key, remote_by_key) =
+            (by_key(base), by_key(local), by_key(remote));
+        // Keep remote order, then entities only known locally.
+        let mut seen = HashSet::new();
+        let keys: Vec<String> = remote
+            .iter()
+            .chain(local)
+            .chain(base)
+            .map(|entity| entity.key().to_string())
+            .filter(|key| !key.is_empty() && seen.insert(key.clone()))
+            .collect();
+        let mut merged = Vec::new();
+        for key in keys {
+            let base = base_by_key.get(&key);
+            let local = local_by_key.get(&key);
+            let remote = remote_by_key.get(&key);
+            let local_changed = local != base;
+            let remote_changed = remote != base;
+            let resolved = if !local_changed {
+                remote.cloned()
+            } else if !remote_changed || local == remote {
+                local.cloned()
+            } else {
+                let resolved = match (local, remote) {
+                    (Some(local), Some(remote)) => {
+                        let base = base.and_then(|base| serde_json::to_value(base).ok());
+                        let merged = self.resolver.merge(
+                            base.as_ref(),
+                            &serde_json::to_value(local).unwrap_or_default(),
+                            &serde_json::to_value(remote).unwrap_or_default(),
+                        );
+                        Some(serde_json::from_value(merged).unwrap_or_else(|_| remote.clone()))
+                    }
+                    (None, Some(remote)) => self
+                        .resolver
+                        .keep_modified(Side::Local)
+                        .then(|| remote.clone()),
+                    (Some(local), None) => self
+                        .resolver
+                        .keep_modified(Side::Remote)
+                        .then(|| local.clone()),
+                    (None, None) => None,
+                };
+                conflicts.push(Conflict {
+                    id: key,
+                    local: local.and_then(|local| serde_json::to_value(local).ok()),
+                    remote: remote.and_then(|remote| serde_json::to_value(remote).ok()),
+                    resolved: resolved
+                        .as_ref()
+                        .and_then(|resolved| serde_json::to_value(resolved).ok()),
+                });
+                resolved
+            };
+            merged.extend(resolved);
+        }
+        // Entities created locally without an ID are always kept.
+        merged.extend(
+            local
+                .iter()
+                .filter(|entity| entity.key().is_empty())
+                .cloned(),
+        );
+        merged
+    }
+}
+
+fn change_project_id(change: &ProjectChange) -> &str {
+    match change {
+        ProjectChange::Created(project) | ProjectChange::Deleted(project) => &project.get_id().0,
+        ProjectChange::Updated { after, .. } => &after.get_id().0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, name: &str) -> Project {
+        serde_json::from_value(serde_json::json!({ "id": id, "name": name })).unwrap()
+    }
+
+    fn task(id: &str, title: &str, status: TaskStatus) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "projectId": "p1",
+            "title": title,
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    fn snapshot(tasks: Vec<Task>) -> Snapshot {
+        Snapshot {
+            projects: vec![project("p1", "Work")],
+            tasks,
+        }
+    }
+
+    fn with_content(mut task: Task, content: &str) -> Task {
+        task.content = content.into();
+        task
+    }
+
+    #[test]
+    fn between_classifies_changes() {
+        let before = snapshot(vec![
+            task("t1", "Write", TaskStatus::Normal),
+            task("t2", "Read", TaskStatus::Normal),
+            task("t3", "Call", TaskStatus::Normal),
+        ]);
+        let after = snapshot(vec![
+            task("t1", "Write more", TaskStatus::Normal),
+            task("t2", "Read", TaskStatus::Completed),
+            task("t4", "Plan", TaskStatus::Normal),
+        ]);
+        let changes = ChangeSet::between(&before, &after);
+        assert!(changes.projects.is_empty());
+        assert!(matches!(
+            &changes.tasks[..],
+            [
+                TaskChange::Updated { changed_fields, .. },
+                TaskChange::Completed { .. },
+                TaskChange::Created(created),
+                TaskChange::Deleted(deleted),
+            ] if changed_fields == &["title"] && created.title == "Plan" && deleted.title == "Call"
+        ));
+    }
+
+    #[test]
+    fn plan_applies_one_sided_changes() {
+        let base = snapshot(vec![
+            task("t1", "Write", TaskStatus::Normal),
+            task("t2", "Read", TaskStatus::Normal),
+        ]);
+        let local = snapshot(vec![
+            task("t1", "Write more", TaskStatus::Normal),
+            task("t2", "Read", TaskStatus::Normal),
+            task("", "Plan", TaskStatus::Normal),
+        ]);
+        let remote = snapshot(vec![
+            task("t1", "Write", TaskStatus::Normal),
+            task("t2", "Read", TaskStatus::Completed),
+        ]);
+        let plan = SyncEngine::<RemoteWins>::default().plan(&base, &local, &remote);
+        assert!(plan.conflicts.is_empty());
+        assert!(matches!(
+            &plan.to_local.tasks[..],
+            [TaskChange::Completed { after, .. }] if after.get_id().0 == "t2"
+        ));
+        assert!(matches!(
+            &plan.to_remote.tasks[..],
+            [TaskChange::Updated { after, .. }, TaskChange::Created(created)]
+                if after.title == "Write more" && created.title == "Plan"
+        ));
+        assert_eq!(plan.merged.tasks.len(), 3);
+    }
+
+    #[test]
+    fn plan_resolves_conflicts() {
+        let base = snapshot(vec![task("t1", "Write", TaskStatus::Normal)]);
+        let local = snapshot(vec![with_content(
+            task("t1", "Write locally", TaskStatus::Normal),
+            "Notes",
+        )]);
+        let remote = snapshot(vec![task("t1", "Write remotely", TaskStatus::Normal)]);
+
+        let plan = SyncEngine::new(RemoteWins).plan(&base, &local, &remote);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.merged.tasks[0].title, "Write remotely");
+        assert_eq!(plan.merged.tasks[0].content, "");
+
+        let plan = SyncEngine::new(LocalWins).plan(&base, &local, &remote);
+        assert_eq!(plan.merged.tasks[0].title, "Write locally");
+
+        let plan = SyncEngine::new(FieldMerge::default()).plan(&base, &local, &remote);
+        assert_eq!(plan.merged.tasks[0].title, "Write remotely");
+        assert_eq!(plan.merged.tasks[0].content, "Notes");
+    }
+
+    #[test]
+    fn plan_resolves_delete_conflicts() {
+        let base = snapshot(vec![task("t1", "Write", TaskStatus::Normal)]);
+        let local = snapshot(vec![task("t1", "Write more", TaskStatus::Normal)]);
+        let remote = snapshot(Vec::new());
+
+        let plan = SyncEngine::new(RemoteWins).plan(&base, &local, &remote);
+        assert!(plan.merged.tasks.is_empty());
+        assert!(matches!(&plan.to_local.tasks[..], [TaskChange::Deleted(_)]));
+
+        let plan = SyncEngine::new(FieldMerge::default()).plan(&base, &local, &remote);
+        assert_eq!(plan.merged.tasks[0].title, "Write more");
+        assert!(matches!(
+            &plan.to_remote.tasks[..],
+            [TaskChange::Created(_)]
+        ));
+    }
+
+    #[cfg(feature = "testing")]
+    mod fetch {
+        use std::sync::Arc;
+
+        use super::*;
+        use crate::{testing::ScriptedTransport, AccessToken};
+
+        /// Client whose remote state is project `p1` with no open tasks, then answers the lookup of a task
+        /// missing from it with `status`.
+        fn ticktick(status: u16, body: serde_json::Value) -> TickTick {
+            let transport = ScriptedTransport::new();
+            transport
+                .push_json(200, &serde_json::json!([{ "id": "p1", "name": "Work" }]))
+                .push_json(200, &serde_json::json!({ "tasks": [], "columns": [] }))
+                .push_json(status, &body);
+            TickTick::with_transport(
+                AccessToken {
+                    value: "token".into(),
+                    token_type: "bearer".into(),
+                    expires_in: 3600,
+                    scope: "tasks:read tasks:write".into(),
+                },
+                Arc::new(transport),
+            )
+        }
+
+        #[tokio::test]
+        async fn missing_task_is_completed() {
+            let base = snapshot(vec![task("t1", "Write", TaskStatus::Normal)]);
+            let completed =
+                serde_json::to_value(task("t1", "Write", TaskStatus::Completed)).unwrap();
+            let remote = Snapshot::fetch_since(&ticktick(200, completed), &base)
+                .await
+                .unwrap();
+            assert!(matches!(
+                &ChangeSet::between(&base, &remote).tasks[..],
+                [TaskChange::Completed { .. }]
+            ));
+        }
+
+        #[tokio::test]
+        async fn missing_task_is_deleted_on_404() {
+            let base = snapshot(vec![task("t1", "Write", TaskStatus::Normal)]);
+            let remote = Snapshot::fetch_since(&ticktick(404, serde_json::json!({})), &base)
+                .await
+                .unwrap();
+            assert!(matches!(
+                &ChangeSet::between(&base, &remote).tasks[..],
+                [TaskChange::Deleted(_)]
+            ));
+        }
+
+        #[tokio::test]
+        async fn server_error_is_not_a_deletion() {
+            let base = snapshot(vec![task("t1", "Write", TaskStatus::Normal)]);
+            let ticktick = ticktick(500, serde_json::json!({ "errorMessage": "Try again" }));
+            // No plan, so no `TaskChange::Deleted` for a task that may still exist.
+            let result = SyncEngine::<RemoteWins>::default()
+                .fetch_and_plan(&ticktick, &base, &base)
+                .await;
+            assert!(matches!(
+                result,
+                Err(TickTickError::StatusError { status: 500, .. })
+            ));
+        }
+    }
+}
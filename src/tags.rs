@@ -0,0 +1,360 @@
+//! Tag management across every task: list tags with their use counts, and rename, merge or remove tags.
+//!
+//! TickTick has no endpoint for tags, so they're found by scanning the open tasks of every project and the Inbox,
+//! and edited by updating each affected task with a `Batch`. Tags are matched case-insensitively, like in TickTick.
+//! ```ignore
+//! for tag in ticktick.tags().list().await? {
+//!     println!("{} ({})", tag.name, tag.count);
+//! }
+//! let preview = ticktick.tags().merge(["todo", "to-do"], "next").dry_run(true).apply().await?;
+//! for task in &preview.tasks {
+//!     println!("Would retag {}: {:?}", task.title, task.tags);
+//! }
+//! let report = ticktick.tags().remove("someday").apply().await?;
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    batch::{BatchOperation, BatchReport},
+    tasks::Task,
+    TickTick, TickTickError,
+};
+
+/// A tag and the number of tasks using it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    /// The tag as first seen on a task.
+    pub name: String,
+    pub count: usize,
+}
+
+/// Count the tags used by `tasks`, most used first.
+pub fn count_tags(tasks: &[Task]) -> Vec<TagCount> {
+    let mut counts: HashMap<String, TagCount> = HashMap::new();
+    for task in tasks {
+        let mut seen = Vec::new();
+        for tag in &task.tags {
+            let key = tag.to_lowercase();
+            if seen.contains(&key) {
+                continue;
+            }
+            counts
+                .entry(key.clone())
+                .or_insert_with(|| TagCount {
+                    name: tag.clone(),
+                    count: 0,
+                })
+                .count += 1;
+            seen.push(key);
+        }
+    }
+    let mut counts: Vec<TagCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    counts
+}
+
+/// A change to the tags of every task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagChange {
+    Rename {
+        from: String,
+        to: String,
+    },
+    /// Replace each of `sources` with `into`.
+    Merge {
+        sources: Vec<String>,
+        into: String,
+    },
+    Remove(String),
+}
+
+impl TagChange {
+    /// `tags` after the change. The replacement tag takes the place of the first replaced tag, and isn't added
+    /// twice if a task already has it.
+    pub fn apply(&self, tags: &[String]) -> Vec<String> {
+        let (sources, into) = match self {
+            Self::Rename { from, to } => (std::slice::from_ref(from), Some(to)),
+            Self::Merge { sources, into } => (sources.as_slice(), Some(into)),
+            Self::Remove(tag) => (std::slice::from_ref(tag), None),
+        };
+        let is_source = |tag: &str| sources.iter().any(|source| same_tag(source, tag));
+        let mut result: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = match into {
+                Some(into) if is_source(tag) => into,
+                None if is_source(tag) => continue,
+                _ => tag,
+            };
+            if !result.iter().any(|existing| same_tag(existing, tag)) {
+                result.push(tag.clone());
+            }
+        }
+        result
+    }
+
+    /// Copies of the tasks in `tasks` whose tags are changed, with the change applied.
+    pub fn plan(&self, tasks: &[Task]) -> Vec<Task> {
+        tasks
+            .iter()
+            .filter_map(|task| {
+                let tags = self.apply(&task.tags);
+                (tags != task.tags).then(|| Task {
+                    tags,
+                    ..task.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+fn same_tag(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+/// Tag operations, borrowed from a `TickTick` handle using `TickTick::tags`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tags<'a> {
+    ticktick: &'a TickTick,
+}
+
+impl TickTick {
+    /// Tag operations: list, rename, merge, remove.
+    pub fn tags(&self) -> Tags<'_> {
+        Tags { ticktick: self }
+    }
+}
+
+impl Tags<'_> {
+    /// Fetch every open task and count the tags they use, most used first.
+    pub async fn list(&self) -> Result<Vec<TagCount>, TickTickError> {
+        Ok(count_tags(
+            &self.ticktick.tasks().get_all_with_inbox().await?,
+        ))
+    }
+    /// Rename `from` to `to` on every task. Call `apply` on the result to make the change.
+    pub fn rename(&self, from: &str, to: &str) -> TagEdit {
+        TagEdit::new(
+            self.ticktick,
+            TagChange::Rename {
+                from: from.into(),
+                to: to.into(),
+            },
+        )
+    }
+    /// Replace each of `sources` with `into` on every task. Call `apply` on the result to make the change.
+    pub fn merge<S: Into<String>>(
+        &self,
+        sources: impl IntoIterator<Item = S>,
+        into: &str,
+    ) -> TagEdit {
+        TagEdit::new(
+            self.ticktick,
+            TagChange::Merge {
+                sources: sources.into_iter().map(Into::into).collect(),
+                into: into.into(),
+            },
+        )
+    }
+    /// Remove `tag` from every task. Call `apply` on the result to make the change.
+    pub fn remove(&self, tag: &str) -> TagEdit {
+        TagEdit::new(self.ticktick, TagChange::Remove(tag.into()))
+    }
+}
+
+/// Result of `TagEdit::apply`.
+#[derive(Debug)]
+pub struct TagEditReport {
+    /// The affected tasks, with their tags after the change.
+    pub tasks: Vec<Task>,
+    /// Results of the task updates, `None` for dry runs.
+    pub batch: Option<BatchReport>,
+}
+
+impl TagEditReport {
+    pub fn is_success(&self) -> bool {
+        self.batch.as_ref().is_none_or(BatchReport::is_success)
+    }
+}
+
+/// A pending change to the tags of every task. Call `apply` to make it.
+#[derive(Debug, Clone)]
+pub struct TagEdit {
+    ticktick: TickTick,
+    change: TagChange,
+    dry_run: bool,
+}
+
+impl TagEdit {
+    pub fn new(ticktick: &TickTick, change: TagChange) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            change,
+            dry_run: false,
+        }
+    }
+    /// Only report the tasks that would change, without updating them.
+    pub fn dry_run(mut self, value: bool) -> Self {
+        self.dry_run = value;
+        self
+    }
+
+    pub fn change(&self) -> &TagChange {
+        &self.change
+    }
+
+    /// Fetch every open task and update the ones whose tags change. Failed updates are reported in the
+    /// `TagEditReport`'s batch rather than as an error.
+    pub async fn apply(self) -> Result<TagEditReport, TickTickError> {
+        let tasks = self
+            .change
+            .plan(&self.ticktick.tasks().get_all_with_inbox().await?);
+        if self.dry_run {
+            return Ok(TagEditReport { tasks, batch: None });
+        }
+        let batch = self
+            .ticktick
+            .batch()
+            .operations(tasks.iter().cloned().map(BatchOperation::Update))
+            .execute()
+            .await;
+        Ok(TagEditReport {
+            tasks,
+            batch: Some(batch),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn task(id: &str, values: &[&str]) -> Task {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "projectId": "p1",
+            "title": id,
+            "tags": values,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_tags_case_insensitively() {
+        let counts = count_tags(&[
+            task("t1", &["Work", "urgent"]),
+            task("t2", &["work", "WORK"]),
+            task("t3", &["home", "urgent"]),
+        ]);
+        let counts: Vec<(&str, usize)> = counts
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.count))
+            .collect();
+        assert_eq!(counts, [("Work", 2), ("urgent", 2), ("home", 1)]);
+    }
+
+    #[test]
+    fn applies_changes() {
+        let cases = [
+            (
+                TagChange::Rename {
+                    from: "todo".into(),
+                    to: "next".into(),
+                },
+                &["home", "TODO", "work"][..],
+                &["home", "next", "work"][..],
+            ),
+            (
+                TagChange::Rename {
+                    from: "todo".into(),
+                    to: "next".into(),
+                },
+                &["next", "todo"],
+                &["next"],
+            ),
+            (
+                TagChange::Merge {
+                    sources: tags(&["todo", "to-do"]),
+                    into: "next".into(),
+                },
+                &["work", "to-do", "home", "todo"],
+                &["work", "next", "home"],
+            ),
+            (
+                TagChange::Remove("Someday".into()),
+                &["someday", "home"],
+                &["home"],
+            ),
+            (TagChange::Remove("someday".into()), &["home"], &["home"]),
+        ];
+        for (change, before, after) in cases {
+            assert_eq!(
+                change.apply(&tags(before)),
+                after,
+                "{:?} on {:?}",
+                change,
+                before
+            );
+        }
+    }
+
+    #[test]
+    fn plans_only_changed_tasks() {
+        let tasks = [
+            task("t1", &["todo"]),
+            task("t2", &["home"]),
+            task("t3", &["Todo", "home"]),
+        ];
+        let planned = TagChange::Remove("todo".into()).plan(&tasks);
+        let planned: Vec<(&str, &[String])> = planned
+            .iter()
+            .map(|task| (task.get_id().0.as_str(), task.tags.as_slice()))
+            .collect();
+        assert_eq!(planned, [("t1", &[][..]), ("t3", &tags(&["home"])[..])]);
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn edit_updates_inbox_tasks() {
+        use std::sync::Arc;
+
+        use crate::{testing::ScriptedTransport, transport::HttpMethod, AccessToken};
+
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .push_json(200, &serde_json::json!([{ "id": "p1", "name": "Work" }]))
+            .push_json(
+                200,
+                &serde_json::json!({ "tasks": [task("t1", &["work"])], "columns": [] }),
+            )
+            .push_json(
+                200,
+                &serde_json::json!({ "tasks": [task("t2", &["todo", "home"])], "columns": [] }),
+            )
+            .push_json(200, &task("t2", &["next", "home"]));
+        let ticktick = TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        );
+        let report = ticktick
+            .tags()
+            .rename("todo", "next")
+            .apply()
+            .await
+            .unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.tasks.len(), 1);
+        assert_eq!(report.tasks[0].tags, ["next", "home"]);
+        let update = &transport.requests()[3];
+        assert_eq!(update.method, HttpMethod::Post);
+        assert!(update.url.ends_with("/task/t2"));
+    }
+}
@@ -2,12 +2,17 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::{ticktick_datetime_format, TickTick, TickTickError};
+use std::sync::Arc;
 
-use super::{builders::TaskBuilder, projects::ProjectID};
+use crate::{ticktick_datetime_format, Bound, TickTick, TickTickError};
+
+use super::{
+    builders::TaskBuilder,
+    projects::{ColumnID, ProjectID},
+};
 
 /// ID used to identify Tasks from TickTick.
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct TaskID(pub String);
 
@@ -18,7 +23,7 @@ impl TaskID {
 }
 
 /// ID used to identify Subtasks from TickTick.
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct SubtaskID(pub String);
 
@@ -30,29 +35,40 @@ impl SubtaskID {
 
 /// TickTick Subtask. In the API Reference, this is defined as a "ChecklistItem", but has been renamed to Subtask here for clarity.
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=checklistitem)
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Subtask {
     #[serde(skip_serializing_if = "SubtaskID::is_empty")]
-    id: SubtaskID,
-    title: String,
-    status: SubtaskStatus,
+    pub(crate) id: SubtaskID,
+    pub title: String,
+    pub status: SubtaskStatus,
     #[serde(with = "ticktick_datetime_format")]
-    completed_time: DateTime<Utc>,
-    is_all_day: bool,
-    sort_order: i64,
+    pub completed_time: DateTime<Utc>,
+    pub is_all_day: bool,
+    pub sort_order: i64,
     #[serde(with = "ticktick_datetime_format")]
-    start_date: DateTime<Utc>,
-    time_zone: String,
+    pub start_date: DateTime<Utc>,
+    pub time_zone: String,
+}
+
+impl Subtask {
+    /// Create a new, uncompleted Subtask. It is assigned an ID once its parent Task is published.
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+    pub fn get_id(&self) -> &SubtaskID {
+        &self.id
+    }
 }
 
 /// TickTick task
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=task-1)
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Task {
-    #[serde(skip)]
-    pub(crate) http_client: reqwest::Client,
     #[serde(skip_serializing_if = "TaskID::is_empty")]
     pub(crate) id: TaskID,
     #[serde(skip_serializing_if = "ProjectID::is_empty")]
@@ -77,76 +93,209 @@ pub struct Task {
     pub status: TaskStatus,
     pub time_zone: String,
     pub tags: Vec<String>,
+    /// Fields returned by the API that aren't modeled above (e.g. `modifiedTime`, `etag`, `columnId`).
+    /// These are kept so they survive being sent back through `publish_changes`.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Task {
     pub fn builder(ticktick: &TickTick, title: &str) -> TaskBuilder {
         TaskBuilder::new(ticktick, title.into())
     }
-    /// Get task using ProjectID & TaskID
+    /// Get task using ProjectID & TaskID, bound to `ticktick`.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-task-by-project-id-and-task-id)
     pub async fn get(
         ticktick: &TickTick,
         project_id: &ProjectID,
         task_id: &TaskID,
-    ) -> Result<Self, TickTickError> {
+    ) -> Result<Bound<Self>, TickTickError> {
         ticktick.get_task(project_id, task_id).await
     }
 
-    /// Get all tasks associated with projects.
-    pub async fn get_all_in_projects(ticktick: &TickTick) -> Result<Vec<Task>, TickTickError> {
+    /// Get all tasks associated with projects, bound to `ticktick`.
+    pub async fn get_all_in_projects(
+        ticktick: &TickTick,
+    ) -> Result<Vec<Bound<Task>>, TickTickError> {
         ticktick.get_all_tasks_in_projects().await
     }
     pub fn get_id(&self) -> &TaskID {
         &self.id
     }
-    /// Delete task
+    /// Time this task was last modified, if returned by the API.
+    pub fn modified_time(&self) -> Option<DateTime<Utc>> {
+        self.extra_str("modifiedTime")
+            .and_then(|value| ticktick_datetime_format::parse(value).ok())
+    }
+    /// Time this task was created, if returned by the API.
+    pub fn created_time(&self) -> Option<DateTime<Utc>> {
+        self.extra_str("createdTime")
+            .and_then(|value| ticktick_datetime_format::parse(value).ok())
+    }
+    /// Entity tag of this task, changes whenever the task is modified.
+    pub fn etag(&self) -> Option<&str> {
+        self.extra_str("etag")
+    }
+    /// ID of the kanban Column this task is placed in.
+    pub fn column_id(&self) -> Option<ColumnID> {
+        self.extra_str("columnId")
+            .map(|value| ColumnID(value.to_string()))
+    }
+    /// Kind of task, e.g. "TEXT", "CHECKLIST" or "NOTE".
+    pub fn kind(&self) -> Option<&str> {
+        self.extra_str("kind")
+    }
+    /// ID of the user this task is assigned to, in shared projects.
+    pub fn assignee(&self) -> Option<String> {
+        match self.extra.get("assignee")? {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+    fn extra_str(&self, key: &str) -> Option<&str> {
+        self.extra.get(key).and_then(serde_json::Value::as_str)
+    }
+}
+
+impl Bound<Task> {
+    /// Delete task, same as `ticktick.tasks().delete(&task)`.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=delete-task)
     pub async fn delete(self) -> Result<(), TickTickError> {
-        self.http_client
-            .delete(format!(
-                "https://ticktick.com/open/v1/project/{}/task/{}",
-                self.project_id.0, self.id.0
-            ))
-            .send()
-            .await?
-            .error_for_status()?;
-        drop(self);
-        Ok(())
+        self.ticktick.tasks().delete(&self.value).await
     }
     /// Send changes made to this task to the TickTick API. Clients will require a refresh/sync for changes to take effect.
+    /// Same as `ticktick.tasks().update(&task)`, without the returned task.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=update-task)
-    pub async fn publish_changes(&self) -> Result<(), reqwest::Error> {
-        self.http_client
-            .post(format!("https://ticktick.com/open/v1/task/{}", self.id.0))
-            .json(self)
-            .send()
-            .await?
-            .text()
-            .await?;
+    pub async fn publish_changes(&self) -> Result<(), TickTickError> {
+        self.ticktick.tasks().update(&self.value).await?;
         Ok(())
     }
 
-    /// Change task status to TaskStatus::Completed
+    /// Change task status to TaskStatus::Completed, through `ticktick.tasks().complete(&task)`.
     /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=complete-task)
-    pub async fn complete(&mut self) -> Result<(), reqwest::Error> {
-        self.status = TaskStatus::Completed;
-        self.http_client
-            .post(format!(
-                "/open/v1/project/{}/task/{}/complete",
-                self.project_id.0, self.id.0
-            ))
-            .json(self)
-            .send()
-            .await?
-            .error_for_status()?;
+    pub async fn complete(&mut self) -> Result<(), TickTickError> {
+        self.ticktick.tasks().complete(&self.value).await?;
+        self.value.status = TaskStatus::Completed;
         Ok(())
     }
 }
 
+/// Task operations, borrowed from a `TickTick` handle using `TickTick::tasks`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tasks<'a> {
+    ticktick: &'a TickTick,
+}
+
+impl<'a> Tasks<'a> {
+    pub(crate) fn new(ticktick: &'a TickTick) -> Self {
+        Self { ticktick }
+    }
+    /// Start building a new task. Call `build_and_publish` on the builder to create it.
+    pub fn builder(&self, title: &str) -> TaskBuilder {
+        TaskBuilder::new(self.ticktick, title.into())
+    }
+    /// Bind `task` to this handle, so it can be updated with `task.publish_changes()`.
+    pub fn bind(&self, task: Task) -> Bound<Task> {
+        Bound::new(Arc::new(self.ticktick.clone()), task)
+    }
+    /// Get task using ProjectID & TaskID
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=get-task-by-project-id-and-task-id)
+    pub async fn get(
+        &self,
+        project_id: &ProjectID,
+        task_id: &TaskID,
+    ) -> Result<Task, TickTickError> {
+        self.ticktick
+            .get_json(&format!("/project/{}/task/{}", project_id.0, task_id.0))
+            .await
+    }
+    /// Get all tasks associated with projects.
+    pub async fn get_all_in_projects(&self) -> Result<Vec<Task>, TickTickError> {
+        let projects = self.ticktick.projects().get_all().await?;
+        let mut value: Vec<Task> = Vec::new();
+        for proj in projects {
+            value.append(
+                &mut self
+                    .ticktick
+                    .projects()
+                    .get_data(proj.get_id())
+                    .await?
+                    .tasks,
+            );
+        }
+        Ok(value)
+    }
+    /// Get all tasks associated with projects, and the tasks in the Inbox.
+    pub async fn get_all_with_inbox(&self) -> Result<Vec<Task>, TickTickError> {
+        let mut tasks = self.get_all_in_projects().await?;
+        match self
+            .ticktick
+            .projects()
+            .get_data(&ProjectID("inbox".into()))
+            .await
+        {
+            Ok(data) => tasks.extend(data.tasks),
+            // Some servers don't expose the Inbox as a project.
+            Err(TickTickError::StatusError { status: 404, .. }) => {}
+            Err(error) => return Err(error),
+        }
+        Ok(tasks)
+    }
+    /// Create a copy of `task` in TickTick, returning the new task. The task's ID is ignored.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=create-task)
+    pub async fn create(&self, task: &Task) -> Result<Task, TickTickError> {
+        let mut task = task.clone();
+        task.id = TaskID::default();
+        for subtask in &mut task.subtasks {
+            subtask.id = SubtaskID::default();
+        }
+        self.ticktick.post_json("/task", &task).await
+    }
+    /// Send changes made to a task to the TickTick API, returning the task as stored by TickTick.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=update-task)
+    pub async fn update(&self, task: &Task) -> Result<Task, TickTickError> {
+        self.ticktick
+            .post_json(&format!("/task/{}", task.id.0), task)
+            .await
+    }
+    /// Mark a task as completed.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=complete-task)
+    pub async fn complete(&self, task: &Task) -> Result<(), TickTickError> {
+        self.ticktick
+            .post_empty(&format!(
+                "/project/{}/task/{}/complete",
+                task.project_id.0, task.id.0
+            ))
+            .await
+    }
+    /// Delete a task.
+    /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=delete-task)
+    pub async fn delete(&self, task: &Task) -> Result<(), TickTickError> {
+        self.ticktick
+            .delete(&format!(
+                "/project/{}/task/{}",
+                task.project_id.0, task.id.0
+            ))
+            .await
+    }
+}
+
 /// Enum matching Task Priority values listed in the Task API Reference
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=task-1)
-#[derive(Serialize_repr, Deserialize_repr, Debug, Default)]
+#[derive(
+    Serialize_repr,
+    Deserialize_repr,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
 #[repr(u8)]
 pub enum TaskPriority {
     #[default]
@@ -156,9 +305,34 @@ pub enum TaskPriority {
     High = 5,
 }
 
+impl std::str::FromStr for TaskPriority {
+    type Err = String;
+    /// Parse priority from its name (`none`, `low`, `medium`, `high`) or API value (`0`, `1`, `3`, `5`).
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "none" | "0" => Ok(Self::None),
+            "low" | "1" => Ok(Self::Low),
+            "medium" | "med" | "3" => Ok(Self::Medium),
+            "high" | "5" => Ok(Self::High),
+            _ => Err(format!("Unknown task priority \"{}\"", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for TaskPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        })
+    }
+}
+
 /// Enum matching Task Status values listed in the Task API Reference
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=task-1)
-#[derive(Serialize_repr, Deserialize_repr, Debug, Default)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum TaskStatus {
     #[default]
@@ -168,7 +342,7 @@ pub enum TaskStatus {
 
 /// Enum matching Subtask Status values listed in the ChecklistItem API Reference
 /// [API Reference](https://developer.ticktick.com/docs/index.html#/openapi?id=checklistitem)
-#[derive(Serialize_repr, Deserialize_repr, Debug, Default)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum SubtaskStatus {
     #[default]
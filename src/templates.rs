@@ -0,0 +1,683 @@
+//! Task templates for checklists that are created again and again, like release checklists or onboarding.
+//!
+//! A template lists tasks with their content, tags, priority, subtasks, reminders and a due date relative to when
+//! it's published. Any text can use `{{variables}}`, with defaults in `[variables]`. Templates are loaded from JSON,
+//! or TOML with the `toml` feature:
+//! ```toml
+//! name = "Release {{version}}"
+//! view_mode = "kanban"
+//!
+//! [variables]
+//! owner = "release team"
+//!
+//! [[tasks]]
+//! title = "Release {{version}}"
+//! content = "Owned by {{owner}}, started {{today}}"
+//! priority = "high"
+//! tags = ["release"]
+//! due = "+1w"
+//! due_time = "17:00"
+//! reminders = ["-1d", "-15m"]
+//! subtasks = ["Bump version to {{version}}", "Update changelog", "Tag v{{version}}"]
+//! ```
+//! ```ignore
+//! let template = Template::from_toml(&std::fs::read_to_string("release.toml")?)?;
+//! let report = template
+//!     .instantiate(&ticktick)
+//!     .variable("version", "2.1.0")
+//!     .create_project(true)
+//!     .publish()
+//!     .await?;
+//! ```
+//! `due` is an offset like `+3d`, `-1w` or `2d 4h`, made of weeks (`w`), days (`d`), hours (`h`) and minutes (`m`).
+//! Offsets in days or weeks make all-day tasks, unless `due_time` (`HH:MM`) gives a time. Reminders are offsets from
+//! the due date, e.g. `-15m`, or TickTick triggers like `TRIGGER:-PT15M`. `{{today}}` is the date the template is
+//! published. `{{#name}}...{{/name}}` sections are only kept if `name` has a non-empty value.
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    builders::{ProjectBuilder, TaskBuilder},
+    markdown::render_with,
+    projects::{Project, ProjectID, ProjectViewMode},
+    tasks::{Subtask, Task, TaskPriority},
+    TickTick, TickTickError,
+};
+
+/// Error from loading or publishing a template.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// The template file couldn't be parsed.
+    Parse(String),
+    /// A `{{variable}}` has no value, and no default in the template.
+    MissingVariable(String),
+    /// A field has an invalid value after substituting variables.
+    Invalid(String),
+    /// Creating the template's project failed.
+    TickTick(TickTickError),
+}
+
+impl From<TickTickError> for TemplateError {
+    fn from(value: TickTickError) -> Self {
+        Self::TickTick(value)
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(message) => write!(f, "Invalid template: {}", message),
+            Self::MissingVariable(name) => write!(f, "No value for template variable \"{}\"", name),
+            Self::Invalid(message) => f.write_str(message),
+            Self::TickTick(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// A set of tasks to create together, optionally in a project of their own.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Template {
+    /// Name of the project created with `TemplateInstance::create_project`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_mode: Option<ProjectViewMode>,
+    /// Default values of variables.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+    pub tasks: Vec<TemplateTask>,
+}
+
+/// A task in a `Template`. Every text field can use variables.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TemplateTask {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// `none`, `low`, `medium` or `high`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Offset from when the template is published, e.g. `+3d`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Time of day on the due date, `HH:MM`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<String>,
+    /// Offsets from the due date, e.g. `-15m`, or TickTick triggers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<String>,
+    /// Subtask titles.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<String>,
+}
+
+impl Template {
+    pub fn from_json(json: &str) -> Result<Self, TemplateError> {
+        serde_json::from_str(json).map_err(|err| TemplateError::Parse(err.to_string()))
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, TemplateError> {
+        toml::from_str(toml).map_err(|err| TemplateError::Parse(err.to_string()))
+    }
+
+    /// Names of the variables used by the template, other than `today`, sorted.
+    pub fn variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .texts()
+            .flat_map(placeholders)
+            .filter(|name| *name != "today")
+            .map(String::from)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Start publishing the template's tasks. Call `publish` on the result to create them.
+    pub fn instantiate(&self, ticktick: &TickTick) -> TemplateInstance {
+        TemplateInstance::new(ticktick, self.clone())
+    }
+
+    fn texts(&self) -> impl Iterator<Item = &str> {
+        self.name
+            .iter()
+            .map(String::as_str)
+            .chain(self.tasks.iter().flat_map(|task| {
+                [
+                    &task.content,
+                    &task.desc,
+                    &task.priority,
+                    &task.due,
+                    &task.due_time,
+                ]
+                .into_iter()
+                .flatten()
+                .chain([&task.title])
+                .chain(&task.tags)
+                .chain(&task.reminders)
+                .chain(&task.subtasks)
+                .map(String::as_str)
+            }))
+    }
+}
+
+/// Task that failed to be created by `TemplateInstance::publish`.
+#[derive(Debug)]
+pub struct TemplateFailure {
+    pub title: String,
+    pub error: TickTickError,
+}
+
+/// Result of `TemplateInstance::publish`.
+#[derive(Debug, Default)]
+pub struct TemplateReport {
+    /// The project created with `create_project`.
+    pub project: Option<Project>,
+    pub created: Vec<Task>,
+    pub failures: Vec<TemplateFailure>,
+}
+
+/// A template with values for its variables, ready to publish.
+#[derive(Debug, Clone)]
+pub struct TemplateInstance {
+    ticktick: TickTick,
+    template: Template,
+    variables: HashMap<String, String>,
+    project_id: Option<ProjectID>,
+    create_project: bool,
+}
+
+impl TemplateInstance {
+    pub fn new(ticktick: &TickTick, template: Template) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            template,
+            variables: HashMap::new(),
+            project_id: None,
+            create_project: false,
+        }
+    }
+    /// Set a variable, overriding the template's default.
+    pub fn variable(mut self, name: &str, value: &str) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+    pub fn variables<K: Into<String>, V: Into<String>>(
+        mut self,
+        values: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.variables
+            .extend(values.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+    /// Project to create the tasks in. Defaults to the Inbox.
+    pub fn project_id(mut self, value: ProjectID) -> Self {
+        self.project_id = Some(value);
+        self
+    }
+    /// Create a project from the template's `name`, `color` and `view_mode`, and put the tasks in it.
+    pub fn create_project(mut self, value: bool) -> Self {
+        self.create_project = value;
+        self
+    }
+
+    /// The template with variables substituted, as if published today in the local time zone.
+    pub fn render(&self) -> Result<Template, TemplateError> {
+        self.render_at(&Local::now())
+    }
+
+    /// The template with variables substituted, as if published at `now`.
+    pub fn render_at<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Result<Template, TemplateError> {
+        let today = now.date_naive().to_string();
+        let mut values: HashMap<&str, &str> = HashMap::from([("today", today.as_str())]);
+        for (name, value) in self.template.variables.iter().chain(&self.variables) {
+            values.insert(name, value);
+        }
+        let render = |text: &String| -> Result<String, TemplateError> {
+            match missing_variable(text, &values) {
+                Some(name) => Err(TemplateError::MissingVariable(name.into())),
+                None => Ok(render_with(text, &values)),
+            }
+        };
+        let render_all = |texts: &[String]| texts.iter().map(render).collect::<Result<_, _>>();
+        let render_option = |text: &Option<String>| text.as_ref().map(render).transpose();
+        let mut tasks = Vec::new();
+        for task in &self.template.tasks {
+            tasks.push(TemplateTask {
+                title: render(&task.title)?,
+                content: render_option(&task.content)?,
+                desc: render_option(&task.desc)?,
+                priority: render_option(&task.priority)?,
+                tags: render_all(&task.tags)?,
+                due: render_option(&task.due)?,
+                due_time: render_option(&task.due_time)?,
+                reminders: render_all(&task.reminders)?,
+                subtasks: render_all(&task.subtasks)?,
+            });
+        }
+        Ok(Template {
+            name: render_option(&self.template.name)?,
+            color: self.template.color.clone(),
+            view_mode: self.template.view_mode,
+            variables: self.template.variables.clone(),
+            tasks,
+        })
+    }
+
+    /// Create the template's tasks, with due dates counted from now in the local time zone.
+    pub async fn publish(self) -> Result<TemplateReport, TemplateError> {
+        self.publish_at(&Local::now()).await
+    }
+
+    /// Create the template's tasks, with due dates counted from `now`. The whole template is checked before anything
+    /// is created, so a missing variable or invalid field creates nothing. Tasks that fail to be created are reported
+    /// in the `TemplateReport`, but creating the project failing is an error.
+    pub async fn publish_at<Tz: TimeZone>(
+        self,
+        now: &DateTime<Tz>,
+    ) -> Result<TemplateReport, TemplateError> {
+        let template = self.render_at(now)?;
+        let builders = template
+            .tasks
+            .iter()
+            .map(|task| self.builder(task, now))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut report = TemplateReport::default();
+        let mut project_id = self.project_id.clone();
+        if self.create_project {
+            let name = template
+                .name
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| {
+                    TemplateError::Invalid("Template has no name to create a project with".into())
+                })?;
+            let mut project = ProjectBuilder::new(&self.ticktick, name);
+            if let Some(color) = &template.color {
+                project = project.color(color);
+            }
+            if let Some(view_mode) = template.view_mode {
+                project = project.view_mode(view_mode);
+            }
+            let project = project.build_and_publish().await?;
+            project_id = Some(project.get_id().clone());
+            report.project = Some(project.into_inner());
+        }
+        for (task, mut builder) in template.tasks.iter().zip(builders) {
+            if let Some(project_id) = &project_id {
+                builder = builder.project_id(project_id.clone());
+            }
+            match builder.build_and_publish().await {
+                Ok(created) => report.created.push(created.into_inner()),
+                Err(error) => report.failures.push(TemplateFailure {
+                    title: task.title.clone(),
+                    error,
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    fn builder<Tz: TimeZone>(
+        &self,
+        task: &TemplateTask,
+        now: &DateTime<Tz>,
+    ) -> Result<TaskBuilder, TemplateError> {
+        let invalid = |field: &str, value: &str, message: &str| {
+            TemplateError::Invalid(format!(
+                "Invalid {} \"{}\" in task \"{}\": {}",
+                field, value, task.title, message
+            ))
+        };
+        let mut builder = TaskBuilder::new(&self.ticktick, task.title.clone());
+        if let Some(content) = &task.content {
+            builder = builder.content(content);
+        }
+        if let Some(desc) = &task.desc {
+            builder = builder.desc(desc);
+        }
+        if let Some(priority) = &task.priority {
+            builder = builder.priority(
+                priority
+                    .parse::<TaskPriority>()
+                    .map_err(|message| invalid("priority", priority, &message))?,
+            );
+        }
+        if !task.tags.is_empty() {
+            builder = builder.tags(task.tags.clone());
+        }
+        if !task.subtasks.is_empty() {
+            builder = builder.subtasks(
+                task.subtasks
+                    .iter()
+                    .map(|title| Subtask::new(title))
+                    .collect(),
+            );
+        }
+        if let Some(due) = &task.due {
+            let offset = Offset::parse(due)
+                .ok_or_else(|| invalid("due", due, "expected an offset like +3d"))?;
+            let time = task
+                .due_time
+                .as_ref()
+                .map(|time| {
+                    NaiveTime::parse_from_str(time, "%H:%M")
+                        .map_err(|_| invalid("due_time", time, "expected HH:MM"))
+                })
+                .transpose()?;
+            let (due_date, is_all_day) = offset
+                .due_date(now, time)
+                .ok_or_else(|| invalid("due", due, "can't be combined with due_time"))?;
+            builder = builder.due_date(due_date).is_all_day(is_all_day);
+        } else if let Some(time) = &task.due_time {
+            return Err(invalid("due_time", time, "requires a due offset"));
+        }
+        if !task.reminders.is_empty() {
+            let reminders = task
+                .reminders
+                .iter()
+                .map(|reminder| {
+                    if reminder.starts_with("TRIGGER:") {
+                        Ok(reminder.clone())
+                    } else {
+                        Offset::parse(reminder)
+                            .map(|offset| offset.trigger())
+                            .ok_or_else(|| {
+                                invalid("reminder", reminder, "expected an offset like -15m")
+                            })
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            builder = builder.reminders(reminders);
+        }
+        Ok(builder)
+    }
+}
+
+/// Names used by `{{name}}` placeholders and `{{#name}}` sections in `text`.
+fn placeholders(text: &str) -> impl Iterator<Item = &str> {
+    text.split("{{").skip(1).filter_map(|part| {
+        let name = part.split_once("}}")?.0.trim();
+        (!name.starts_with('/')).then(|| name.trim_start_matches('#'))
+    })
+}
+
+/// First `{{name}}` placeholder in `text` without a value, skipping sections that won't be rendered.
+fn missing_variable<'a>(text: &'a str, values: &HashMap<&str, &str>) -> Option<&'a str> {
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..].find("}}")?;
+        let tag = rest[start + 2..start + end].trim();
+        rest = &rest[start + end + 2..];
+        match tag.strip_prefix('#') {
+            Some(section) => {
+                let closing = format!("{{{{/{}}}}}", section);
+                let (inner, after) = rest.split_once(&closing).unwrap_or((rest, ""));
+                if values.get(section).is_some_and(|value| !value.is_empty()) {
+                    if let Some(name) = missing_variable(inner, values) {
+                        return Some(name);
+                    }
+                }
+                rest = after;
+            }
+            None if tag.starts_with('/') || values.contains_key(tag) => {}
+            None => return Some(tag),
+        }
+    }
+    None
+}
+
+/// An offset like `-1w 2d` or `+4h30m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Offset {
+    negative: bool,
+    days: i64,
+    hours: i64,
+    minutes: i64,
+}
+
+impl Offset {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (negative, rest) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let mut offset = Self {
+            negative,
+            days: 0,
+            hours: 0,
+            minutes: 0,
+        };
+        if rest == "0" {
+            return Some(offset);
+        }
+        let mut number = String::new();
+        let mut components = 0;
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => number.push(c),
+                ' ' if number.is_empty() => {}
+                'w' | 'd' | 'h' | 'm' if !number.is_empty() => {
+                    let amount: i64 = number.parse().ok()?;
+                    match c {
+                        'w' => offset.days += amount * 7,
+                        'd' => offset.days += amount,
+                        'h' => offset.hours += amount,
+                        _ => offset.minutes += amount,
+                    }
+                    number.clear();
+                    components += 1;
+                }
+                _ => return None,
+            }
+        }
+        (number.is_empty() && components > 0).then_some(offset)
+    }
+
+    fn has_time(&self) -> bool {
+        self.hours != 0 || self.minutes != 0
+    }
+
+    /// Due date `self` after `now`, and whether it's all-day. `None` if a time is given for an offset with hours or
+    /// minutes.
+    fn due_date<Tz: TimeZone>(
+        &self,
+        now: &DateTime<Tz>,
+        time: Option<NaiveTime>,
+    ) -> Option<(DateTime<Utc>, bool)> {
+        if self.has_time() {
+            if time.is_some() {
+                return None;
+            }
+            let offset = Duration::days(self.days)
+                + Duration::hours(self.hours)
+                + Duration::minutes(self.minutes);
+            let offset = if self.negative { -offset } else { offset };
+            return Some(((now.clone() + offset).with_timezone(&Utc), false));
+        }
+        let days = if self.negative { -self.days } else { self.days };
+        let date = now.date_naive() + Duration::days(days);
+        let local = now
+            .timezone()
+            .from_local_datetime(&date.and_time(time.unwrap_or(NaiveTime::MIN)))
+            .earliest()?;
+        Some((local.with_timezone(&Utc), time.is_none()))
+    }
+
+    /// The offset as a TickTick reminder trigger, e.g. `TRIGGER:-PT15M`.
+    fn trigger(&self) -> String {
+        if self.days == 0 && !self.has_time() {
+            return "TRIGGER:PT0S".into();
+        }
+        let mut trigger = String::from(if self.negative {
+            "TRIGGER:-P"
+        } else {
+            "TRIGGER:P"
+        });
+        if self.days != 0 {
+            trigger.push_str(&format!("{}D", self.days));
+        }
+        if self.has_time() {
+            trigger.push('T');
+            if self.hours != 0 {
+                trigger.push_str(&format!("{}H", self.hours));
+            }
+            if self.minutes != 0 {
+                trigger.push_str(&format!("{}M", self.minutes));
+            }
+        }
+        trigger
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(negative: bool, days: i64, hours: i64, minutes: i64) -> Offset {
+        Offset {
+            negative,
+            days,
+            hours,
+            minutes,
+        }
+    }
+
+    #[test]
+    fn parses_offsets() {
+        let cases = [
+            ("+3d", Some(offset(false, 3, 0, 0))),
+            ("3d", Some(offset(false, 3, 0, 0))),
+            ("-1w 2d", Some(offset(true, 9, 0, 0))),
+            ("+4h30m", Some(offset(false, 0, 4, 30))),
+            (" -15m ", Some(offset(true, 0, 0, 15))),
+            ("0", Some(offset(false, 0, 0, 0))),
+            ("", None),
+            ("+", None),
+            ("3", None),
+            ("d", None),
+            ("1d2", None),
+            ("3x", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(Offset::parse(value), expected, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn formats_triggers() {
+        let cases = [
+            ("-15m", "TRIGGER:-PT15M"),
+            ("-1d", "TRIGGER:-P1D"),
+            ("+1d 2h", "TRIGGER:P1DT2H"),
+            ("-1h30m", "TRIGGER:-PT1H30M"),
+            ("0", "TRIGGER:PT0S"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                Offset::parse(value).unwrap().trigger(),
+                expected,
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn computes_due_dates() {
+        let now = DateTime::parse_from_rfc3339("2026-10-14T10:00:00+02:00").unwrap();
+        let at = |value: &str| value.parse::<DateTime<Utc>>().unwrap();
+        let five_pm = NaiveTime::from_hms_opt(17, 0, 0);
+        let cases = [
+            ("+1w", None, Some((at("2026-10-20T22:00:00Z"), true))),
+            ("-1d", None, Some((at("2026-10-12T22:00:00Z"), true))),
+            ("+1d", five_pm, Some((at("2026-10-15T15:00:00Z"), false))),
+            ("+2h", None, Some((at("2026-10-14T10:00:00Z"), false))),
+            ("-1d 30m", None, Some((at("2026-10-13T07:30:00Z"), false))),
+            ("+2h", five_pm, None),
+        ];
+        for (value, time, expected) in cases {
+            let offset = Offset::parse(value).unwrap();
+            assert_eq!(
+                offset.due_date(&now, time),
+                expected,
+                "{:?} {:?}",
+                value,
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn finds_missing_variables() {
+        let values = HashMap::from([("version", "2.1.0"), ("owner", ""), ("today", "2026-10-14")]);
+        let cases = [
+            ("Release {{version}} on {{today}}", None),
+            ("Release {{ version }}", None),
+            ("Owned by {{team}}", Some("team")),
+            ("{{version}} {{team}} {{other}}", Some("team")),
+            ("{{#owner}}Owned by {{team}}{{/owner}}", None),
+            ("{{#version}}Tag {{tag}}{{/version}}", Some("tag")),
+            ("{{#notes}}{{notes}}{{/notes}} {{team}}", Some("team")),
+            ("Unclosed {{team", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(missing_variable(text, &values), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn lists_variable_names() {
+        let template = Template::from_json(
+            r#"{
+                "name": "Release {{version}}",
+                "tasks": [{
+                    "title": "Release {{version}}",
+                    "content": "{{#owner}}Owned by {{owner}}{{/owner}}, started {{today}}",
+                    "subtasks": ["Tag v{{version}}"]
+                }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(template.variable_names(), ["owner", "version"]);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parses_toml() {
+        let template = Template::from_toml(
+            r#"
+            name = "Release {{version}}"
+            view_mode = "kanban"
+
+            [variables]
+            owner = "release team"
+
+            [[tasks]]
+            title = "Release {{version}}"
+            priority = "high"
+            due = "+1w"
+            due_time = "17:00"
+            reminders = ["-1d", "-15m"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(template.view_mode, Some(ProjectViewMode::Kanban));
+        assert_eq!(template.variables["owner"], "release team");
+        assert_eq!(template.tasks[0].due.as_deref(), Some("+1w"));
+        assert_eq!(template.tasks[0].reminders, ["-1d", "-15m"]);
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::transport::{
+    HttpRequest, HttpResponse, Transport, TransportError, TransportFuture,
+    SENSITIVE_REQUEST_HEADERS,
+};
+
+const CASSETTE_VERSION: u32 = 1;
+const REDACTED: &str = "[REDACTED]";
+/// Response headers stripped from recorded interactions.
+const SENSITIVE_RESPONSE_HEADERS: [&str; 3] = ["set-cookie", "set-cookie2", "www-authenticate"];
+/// Set this environment variable to record with `CassetteTransport::from_env`.
+pub const RECORD_ENV_VAR: &str = "TICKS_RECORD";
+
+/// Recorded request and the response it received.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    pub request: HttpRequest,
+    pub response: HttpResponse,
+}
+
+/// On-disk cassette file format.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Cassette {
+    pub version: u32,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let cassette: Self = serde_json::from_slice(&fs::read(path)?)?;
+        if cassette.version != CASSETTE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Unsupported cassette version {}", cassette.version),
+            ));
+        }
+        Ok(cassette)
+    }
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Debug)]
+enum Mode {
+    Record(Arc<dyn Transport>),
+    Replay,
+}
+
+/// `Transport` that records real responses to a JSON cassette file, or replays them from one.
+/// Recorded cassettes are redacted: the Authorization and cookie headers are stripped from requests, `Set-Cookie` and
+/// `WWW-Authenticate` from responses, and OAuth secrets and tokens are masked.
+#[derive(Debug)]
+pub struct CassetteTransport {
+    mode: Mode,
+    path: PathBuf,
+    /// Interactions recorded so far, or not yet replayed.
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl CassetteTransport {
+    /// Forward requests to `inner`, saving every interaction to `path`. Any existing cassette is overwritten.
+    pub fn record(path: impl Into<PathBuf>, inner: Arc<dyn Transport>) -> Self {
+        Self {
+            mode: Mode::Record(inner),
+            path: path.into(),
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+    /// Serve responses from the cassette at `path`. Requests are matched by method and URL, in recorded order.
+    pub fn replay(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let cassette = Cassette::load(&path)?;
+        Ok(Self {
+            mode: Mode::Replay,
+            path,
+            interactions: Mutex::new(cassette.interactions),
+        })
+    }
+    /// Record through `inner` if the `TICKS_RECORD` environment variable is set, otherwise replay.
+    pub fn from_env(path: impl Into<PathBuf>, inner: Arc<dyn Transport>) -> std::io::Result<Self> {
+        if std::env::var_os(RECORD_ENV_VAR).is_some() {
+            Ok(Self::record(path, inner))
+        } else {
+            Self::replay(path)
+        }
+    }
+    pub fn is_recording(&self) -> bool {
+        matches!(self.mode, Mode::Record(_))
+    }
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn replay_request(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        let mut interactions = self.interactions.lock().unwrap();
+        let position = interactions
+            .iter()
+            .position(|interaction| {
+                interaction.request.method == request.method
+                    && interaction.request.url == request.url
+            })
+            .ok_or_else(|| {
+                TransportError::Other(format!(
+                    "No recorded interaction for {} {} in {}",
+                    request.method.as_str(),
+                    request.url,
+                    self.path.display()
+                ))
+            })?;
+        Ok(interactions.remove(position).response)
+    }
+
+    fn save_interaction(&self, interaction: Interaction) -> Result<(), TransportError> {
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(redact(interaction));
+        Cassette {
+            version: CASSETTE_VERSION,
+            interactions: interactions.clone(),
+        }
+        .save(&self.path)
+        .map_err(|err| TransportError::Other(format!("Failed to save cassette: {}", err)))
+    }
+}
+
+impl Transport for CassetteTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            match &self.mode {
+                Mode::Replay => self.replay_request(&request),
+                Mode::Record(inner) => {
+                    let response = inner.send(request.clone()).await?;
+                    self.save_interaction(Interaction {
+                        request,
+                        response: response.clone(),
+                    })?;
+                    Ok(response)
+                }
+            }
+        })
+    }
+}
+
+fn redact(mut interaction: Interaction) -> Interaction {
+    let is_sensitive = |sensitive: &[&str], name: &str| {
+        sensitive
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case(name))
+    };
+    interaction
+        .request
+        .headers
+        .retain(|(name, _)| !is_sensitive(&SENSITIVE_REQUEST_HEADERS, name));
+    interaction
+        .response
+        .headers
+        .retain(|(name, _)| !is_sensitive(&SENSITIVE_RESPONSE_HEADERS, name));
+    if interaction.request.url.ends_with("/oauth/token") {
+        interaction.request.body = interaction.request.body.map(|body| {
+            oauth2::url::form_urlencoded::parse(body.as_bytes())
+                .map(|(key, value)| match key.as_ref() {
+                    "client_secret" | "code" => (key.into_owned(), REDACTED.to_string()),
+                    _ => (key.into_owned(), value.into_owned()),
+                })
+                .fold(
+                    oauth2::url::form_urlencoded::Serializer::new(String::new()),
+                    |mut serializer, (key, value)| {
+                        serializer.append_pair(&key, &value);
+                        serializer
+                    },
+                )
+                .finish()
+        });
+        if let Ok(serde_json::Value::Object(mut body)) =
+            serde_json::from_str::<serde_json::Value>(&interaction.response.body)
+        {
+            for key in ["access_token", "refresh_token"] {
+                if body.contains_key(key) {
+                    body.insert(key.into(), REDACTED.into());
+                }
+            }
+            interaction.response.body = serde_json::Value::Object(body).to_string();
+        }
+    }
+    interaction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::HttpMethod;
+
+    #[test]
+    fn redact_strips_sensitive_headers() {
+        let mut request = HttpRequest::new(HttpMethod::Get, "https://ticktick.com/open/v1/project");
+        request.headers = vec![
+            ("Authorization".into(), "Bearer secret".into()),
+            ("Cookie".into(), "t=secret".into()),
+            ("Accept".into(), "application/json".into()),
+        ];
+        let mut response = HttpResponse::new(200, "[]");
+        response.headers = vec![
+            ("Set-Cookie".into(), "t=secret; Path=/".into()),
+            ("content-type".into(), "application/json".into()),
+        ];
+
+        let interaction = redact(Interaction { request, response });
+
+        assert_eq!(
+            interaction.request.headers,
+            [("Accept".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(
+            interaction.response.headers,
+            [("content-type".to_string(), "application/json".to_string())]
+        );
+    }
+
+    #[test]
+    fn redact_masks_oauth_secrets() {
+        let mut request = HttpRequest::new(HttpMethod::Post, "https://ticktick.com/oauth/token");
+        request.body = Some("grant_type=authorization_code&code=abc&client_secret=shh".into());
+        let response = HttpResponse::new(
+            200,
+            r#"{"access_token":"token","token_type":"bearer","expires_in":1,"scope":"tasks:read"}"#,
+        );
+
+        let interaction = redact(Interaction { request, response });
+
+        let body = interaction.request.body.unwrap();
+        assert!(!body.contains("abc") && !body.contains("shh"), "{}", body);
+        assert!(body.contains("grant_type=authorization_code"));
+        assert!(!interaction.response.body.contains("\"token\""));
+        assert!(interaction.response.body.contains("\"bearer\""));
+    }
+}
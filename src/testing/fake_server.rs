@@ -0,0 +1,370 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Utc;
+use serde::Serialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+use crate::{
+    projects::{Project, ProjectData, ProjectID},
+    tasks::{Task, TaskID, TaskStatus},
+    ticktick_datetime_format, AccessToken, TickTick,
+};
+
+const FAKE_ACCESS_TOKEN: &str = "fake-access-token";
+const INBOX_ID: &str = "inbox";
+
+/// In-process fake of the TickTick Open API, serving projects and tasks stored in memory on a loopback port.
+/// Tasks created without a project go to the Inbox, whose ID is `inbox`.
+/// Point a `TickTick` at it with `client`, or use `base_url` with `TickTick::with_base_url` and `AwaitingAuthCode::with_base_url`.
+/// The server stops when this is dropped.
+#[derive(Debug)]
+pub struct FakeTickTick {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    server: JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    projects: Vec<Project>,
+    tasks: Vec<Task>,
+    next_id: u64,
+}
+
+impl FakeState {
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{:08}", prefix, self.next_id)
+    }
+}
+
+impl FakeTickTick {
+    /// Bind to a random loopback port and start serving. Must be called within a Tokio runtime.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(FakeState::default()));
+        let server_state = state.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, state).await;
+                });
+            }
+        });
+        Ok(Self {
+            addr,
+            state,
+            server,
+        })
+    }
+    /// Base URL of the fake server, e.g. `http://127.0.0.1:41234`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+    /// Access token accepted by the fake server. Requests using any other token are rejected with 401.
+    pub fn access_token(&self) -> AccessToken {
+        AccessToken {
+            value: FAKE_ACCESS_TOKEN.into(),
+            token_type: "bearer".into(),
+            expires_in: 15551999,
+            scope: "tasks:read tasks:write".into(),
+        }
+    }
+    /// `TickTick` instance authorized against, and pointed at, this fake server.
+    pub fn client(&self) -> TickTick {
+        TickTick::new(self.access_token())
+            .expect("Failed to create HTTP client.")
+            .with_base_url(&self.base_url())
+    }
+    /// Store a project directly, bypassing the API. An ID is assigned if it has none.
+    pub fn insert_project(&self, mut project: Project) -> ProjectID {
+        let mut state = self.state.lock().unwrap();
+        if project.id.is_empty() {
+            project.id = ProjectID(state.new_id("project"));
+        }
+        let id = project.id.clone();
+        state.projects.push(project);
+        id
+    }
+    /// Store a task directly, bypassing the API. An ID is assigned if it has none.
+    pub fn insert_task(&self, mut task: Task) -> TaskID {
+        let mut state = self.state.lock().unwrap();
+        if task.id.is_empty() {
+            task.id = TaskID(state.new_id("task"));
+        }
+        let id = task.id.clone();
+        state.tasks.push(task);
+        id
+    }
+    /// All projects currently stored.
+    pub fn projects(&self) -> Vec<Project> {
+        self.state.lock().unwrap().projects.clone()
+    }
+    /// All tasks currently stored, including completed ones.
+    pub fn tasks(&self) -> Vec<Task> {
+        self.state.lock().unwrap().tasks.clone()
+    }
+}
+
+impl Drop for FakeTickTick {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+struct FakeRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+struct FakeResponse {
+    status: u16,
+    body: String,
+}
+
+impl FakeResponse {
+    fn json<T: Serialize>(value: &T) -> Self {
+        Self {
+            status: 200,
+            body: serde_json::to_string(value).expect("Fake response must serialize."),
+        }
+    }
+    fn empty() -> Self {
+        Self {
+            status: 200,
+            body: String::new(),
+        }
+    }
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "errorMessage": message }).to_string(),
+        }
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: Arc<Mutex<FakeState>>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    let response = route(&request, &mut state.lock().unwrap());
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<FakeRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut content_length = 0;
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.to_string());
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(FakeRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn route(request: &FakeRequest, state: &mut FakeState) -> FakeResponse {
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if segments == ["oauth", "token"] && request.method == "POST" {
+        return FakeResponse::json(&serde_json::json!({
+            "access_token": FAKE_ACCESS_TOKEN,
+            "token_type": "bearer",
+            "expires_in": 15551999,
+            "scope": "tasks:read tasks:write",
+        }));
+    }
+    if request.authorization.as_deref() != Some(&format!("Bearer {}", FAKE_ACCESS_TOKEN)) {
+        return FakeResponse::error(401, "unauthorized");
+    }
+    let Some(route) = segments.strip_prefix(&["open", "v1"]) else {
+        return FakeResponse::error(404, "not found");
+    };
+    match (request.method.as_str(), route) {
+        ("GET", ["project"]) => FakeResponse::json(&state.projects),
+        ("POST", ["project"]) => create_project(request, state),
+        ("GET", ["project", id]) => match find_project(state, id) {
+            Some(project) => FakeResponse::json(project),
+            None => FakeResponse::error(404, "project not found"),
+        },
+        ("POST", ["project", id]) => update_project(request, state, id),
+        ("DELETE", ["project", id]) => {
+            let before = state.projects.len();
+            state.projects.retain(|project| project.id.0 != *id);
+            if state.projects.len() == before {
+                return FakeResponse::error(404, "project not found");
+            }
+            state.tasks.retain(|task| task.project_id.0 != *id);
+            FakeResponse::empty()
+        }
+        ("GET", ["project", id, "data"]) => {
+            // Like TickTick, the Inbox isn't listed as a project but its data can be fetched.
+            if *id != INBOX_ID && find_project(state, id).is_none() {
+                return FakeResponse::error(404, "project not found");
+            }
+            FakeResponse::json(&ProjectData {
+                tasks: state
+                    .tasks
+                    .iter()
+                    .filter(|task| task.project_id.0 == *id && task.status != TaskStatus::Completed)
+                    .cloned()
+                    .collect(),
+                columns: Vec::new(),
+            })
+        }
+        ("GET", ["project", project_id, "task", task_id]) => {
+            match find_task(state, project_id, task_id) {
+                Some(index) => FakeResponse::json(&state.tasks[index]),
+                None => FakeResponse::error(404, "task not found"),
+            }
+        }
+        ("POST", ["project", project_id, "task", task_id, "complete"]) => {
+            match find_task(state, project_id, task_id) {
+                Some(index) => {
+                    let task = &mut state.tasks[index];
+                    task.status = TaskStatus::Completed;
+                    task.completed_time = Utc::now();
+                    FakeResponse::empty()
+                }
+                None => FakeResponse::error(404, "task not found"),
+            }
+        }
+        ("DELETE", ["project", project_id, "task", task_id]) => {
+            match find_task(state, project_id, task_id) {
+                Some(index) => {
+                    state.tasks.remove(index);
+                    FakeResponse::empty()
+                }
+                None => FakeResponse::error(404, "task not found"),
+            }
+        }
+        ("POST", ["task"]) => create_task(request, state),
+        ("POST", ["task", id]) => update_task(request, state, id),
+        _ => FakeResponse::error(404, "not found"),
+    }
+}
+
+fn find_project<'a>(state: &'a FakeState, id: &str) -> Option<&'a Project> {
+    state.projects.iter().find(|project| project.id.0 == id)
+}
+
+fn find_task(state: &FakeState, project_id: &str, task_id: &str) -> Option<usize> {
+    state
+        .tasks
+        .iter()
+        .position(|task| task.project_id.0 == project_id && task.id.0 == task_id)
+}
+
+fn create_project(request: &FakeRequest, state: &mut FakeState) -> FakeResponse {
+    let Ok(mut project) = serde_json::from_slice::<Project>(&request.body) else {
+        return FakeResponse::error(400, "invalid project");
+    };
+    project.id = ProjectID(state.new_id("project"));
+    state.projects.push(project.clone());
+    FakeResponse::json(&project)
+}
+
+fn update_project(request: &FakeRequest, state: &mut FakeState, id: &str) -> FakeResponse {
+    let Ok(mut changes) = serde_json::from_slice::<Project>(&request.body) else {
+        return FakeResponse::error(400, "invalid project");
+    };
+    let Some(project) = state.projects.iter_mut().find(|project| project.id.0 == id) else {
+        return FakeResponse::error(404, "project not found");
+    };
+    changes.id = project.id.clone();
+    *project = changes;
+    FakeResponse::json(project)
+}
+
+fn create_task(request: &FakeRequest, state: &mut FakeState) -> FakeResponse {
+    let Ok(mut task) = serde_json::from_slice::<Task>(&request.body) else {
+        return FakeResponse::error(400, "invalid task");
+    };
+    task.id = TaskID(state.new_id("task"));
+    if task.project_id.is_empty() {
+        task.project_id = ProjectID(INBOX_ID.into());
+    }
+    stamp_modified(&mut task);
+    state.tasks.push(task.clone());
+    FakeResponse::json(&task)
+}
+
+fn update_task(request: &FakeRequest, state: &mut FakeState, id: &str) -> FakeResponse {
+    let Ok(mut changes) = serde_json::from_slice::<Task>(&request.body) else {
+        return FakeResponse::error(400, "invalid task");
+    };
+    let Some(task) = state.tasks.iter_mut().find(|task| task.id.0 == id) else {
+        return FakeResponse::error(404, "task not found");
+    };
+    changes.id = task.id.clone();
+    if changes.project_id.is_empty() {
+        changes.project_id = task.project_id.clone();
+    }
+    stamp_modified(&mut changes);
+    *task = changes;
+    FakeResponse::json(task)
+}
+
+/// Update `modifiedTime` and `etag` the way TickTick does on every write.
+fn stamp_modified(task: &mut Task) {
+    let now = Utc::now();
+    task.extra.insert(
+        "modifiedTime".into(),
+        serde_json::Value::String(ticktick_datetime_format::format(&now)),
+    );
+    task.extra.insert(
+        "etag".into(),
+        serde_json::Value::String(format!(
+            "{:x}",
+            now.timestamp_nanos_opt().unwrap_or_default()
+        )),
+    );
+}
@@ -0,0 +1,94 @@
+//! Helpers for testing code built on Ticks without network access. Enabled with the `testing` feature.
+mod cassette;
+mod fake_server;
+use std::{collections::VecDeque, sync::Mutex};
+
+use serde::Serialize;
+
+pub use cassette::{Cassette, CassetteTransport, Interaction, RECORD_ENV_VAR};
+pub use fake_server::FakeTickTick;
+
+use crate::transport::{
+    HttpMethod, HttpRequest, HttpResponse, Transport, TransportError, TransportFuture,
+};
+
+/// In-memory `Transport` that answers requests with scripted responses, in the order they were pushed.
+/// Every request sent is recorded, and can be inspected with `requests`.
+#[derive(Debug, Default)]
+pub struct ScriptedTransport {
+    responses: Mutex<VecDeque<ScriptedResponse>>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[derive(Debug)]
+struct ScriptedResponse {
+    expected: Option<(HttpMethod, String)>,
+    response: HttpResponse,
+}
+
+impl ScriptedTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Queue a response for the next request, whatever it is.
+    pub fn push(&self, response: HttpResponse) -> &Self {
+        self.push_scripted(None, response)
+    }
+    /// Queue a JSON response for the next request, whatever it is.
+    pub fn push_json<B: Serialize + ?Sized>(&self, status: u16, body: &B) -> &Self {
+        self.push(HttpResponse::json(status, body).expect("Scripted body must serialize."))
+    }
+    /// Queue a response for the next request, which must use `method` and have a URL ending in `path`.
+    /// If it doesn't, the request fails with `TransportError::Other`.
+    pub fn expect(&self, method: HttpMethod, path: &str, response: HttpResponse) -> &Self {
+        self.push_scripted(Some((method, path.into())), response)
+    }
+    /// All requests sent through this transport so far.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+    /// Number of queued responses not yet consumed.
+    pub fn remaining(&self) -> usize {
+        self.responses.lock().unwrap().len()
+    }
+    fn push_scripted(
+        &self,
+        expected: Option<(HttpMethod, String)>,
+        response: HttpResponse,
+    ) -> &Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back(ScriptedResponse { expected, response });
+        self
+    }
+    fn respond(&self, request: &HttpRequest) -> Result<HttpResponse, TransportError> {
+        let scripted = self.responses.lock().unwrap().pop_front().ok_or_else(|| {
+            TransportError::Other(format!(
+                "No scripted response left for {} {}",
+                request.method.as_str(),
+                request.url
+            ))
+        })?;
+        if let Some((method, path)) = &scripted.expected {
+            if *method != request.method || !request.url.ends_with(path.as_str()) {
+                return Err(TransportError::Other(format!(
+                    "Expected {} {}, got {} {}",
+                    method.as_str(),
+                    path,
+                    request.method.as_str(),
+                    request.url
+                )));
+            }
+        }
+        Ok(scripted.response)
+    }
+}
+
+impl Transport for ScriptedTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        let result = self.respond(&request);
+        self.requests.lock().unwrap().push(request);
+        Box::pin(async move { result })
+    }
+}
@@ -0,0 +1,554 @@
+//! Reader and writer for the CSV backups made by TickTick's apps (Settings → Account → Generate Backup). Enabled with
+//! the `csv` feature.
+//!
+//! Backups are read into a `Backup`, so they can be restored through the Open API with `Backup::restore`, and any
+//! `Backup` can be written in the same format.
+//! ```ignore
+//! let backup = ticktick_csv::read(std::fs::File::open("TickTick-backup.csv")?)?;
+//! let report = backup.restore(&ticktick).await;
+//!
+//! let backup = Backup::fetch(&ticktick).await?;
+//! ticktick_csv::write(&backup, std::fs::File::create("export.csv")?)?;
+//! ```
+//! Besides TickTick's own columns, written backups have a `listId` column with each project's ID. Read projects use it
+//! as their ID when it is present, and their list name otherwise, as TickTick's backups identify lists by name only.
+//! Columns always use their name as ID.
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+use chrono::{DateTime, SubsecRound, Utc};
+use csv::{QuoteStyle, ReaderBuilder, StringRecord, WriterBuilder};
+
+use crate::{
+    backup::{Backup, ProjectBackup, SCHEMA_VERSION},
+    projects::{Column, ColumnID, Project, ProjectID, ProjectKind, ProjectViewMode},
+    tasks::{Subtask, SubtaskStatus, Task, TaskID, TaskPriority, TaskStatus},
+};
+
+/// Version of TickTick's backup format this module reads and writes.
+const FORMAT_VERSION: &str = "7.1";
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+/// Checklist items are stored in a task's content, one per line, prefixed with one of these.
+const OPEN_ITEM: char = '▫';
+const COMPLETED_ITEM: char = '▪';
+/// `Project::extra` key the folder name is kept in, as the Open API only exposes folder IDs.
+const FOLDER_NAME_KEY: &str = "folderName";
+
+const HEADER: [&str; 25] = [
+    "Folder Name",
+    "List Name",
+    "Title",
+    "Kind",
+    "Tags",
+    "Content",
+    "Is Check list",
+    "Start Date",
+    "Due Date",
+    "Reminder",
+    "Repeat",
+    "Priority",
+    "Status",
+    "Created Time",
+    "Completed Time",
+    "Order",
+    "Timezone",
+    "Is All Day",
+    "Is Floating",
+    "Column Name",
+    "Column Order",
+    "View Mode",
+    "taskId",
+    "parentId",
+    "listId",
+];
+
+/// Errors that can occur while reading or writing a CSV backup.
+#[derive(Debug)]
+pub enum CsvError {
+    CsvError(csv::Error),
+    /// No `Folder Name, List Name, ...` header row was found.
+    MissingHeader,
+    /// A cell couldn't be parsed. `row` is the 1-based line number in the file.
+    InvalidValue {
+        row: u64,
+        column: String,
+        value: String,
+    },
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(value: csv::Error) -> Self {
+        Self::CsvError(value)
+    }
+}
+
+impl From<std::io::Error> for CsvError {
+    fn from(value: std::io::Error) -> Self {
+        Self::CsvError(value.into())
+    }
+}
+
+impl std::fmt::Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CsvError(err) => write!(f, "{}", err),
+            Self::MissingHeader => write!(f, "Not a TickTick backup, no header row found"),
+            Self::InvalidValue { row, column, value } => {
+                write!(f, "Invalid {} \"{}\" on line {}", column, value, row)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Read a TickTick CSV backup. Tasks are grouped into projects by list ID, or by list name in backups without a
+/// `listId` column, in the order they first appear.
+pub fn read(reader: impl Read) -> Result<Backup, CsvError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+    let mut records = reader.records();
+    // Skip the "Date: ...", "Version: ..." and "Status: ..." preamble.
+    let header = loop {
+        match records.next() {
+            Some(record) => {
+                let record = record?;
+                if record.get(0).map(str::trim) == Some(HEADER[0]) {
+                    break record;
+                }
+            }
+            None => return Err(CsvError::MissingHeader),
+        }
+    };
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.trim().to_string(), index))
+        .collect();
+    let mut projects: Vec<ProjectBackup> = Vec::new();
+    for record in records {
+        let row = Row {
+            record: record?,
+            columns: &columns,
+        };
+        let list_id = list_id(&row);
+        let index = match projects
+            .iter()
+            .position(|backup| backup.project.get_id().0 == list_id)
+        {
+            Some(index) => index,
+            None => {
+                projects.push(ProjectBackup {
+                    project: read_project(&row),
+                    columns: Vec::new(),
+                    tasks: Vec::new(),
+                });
+                projects.len() - 1
+            }
+        };
+        let backup = &mut projects[index];
+        let column_name = row.get("Column Name");
+        if !column_name.is_empty()
+            && !backup
+                .columns
+                .iter()
+                .any(|column| column.get_name() == column_name)
+        {
+            backup.columns.push(Column {
+                id: ColumnID(column_name.into()),
+                project_id: backup.project.get_id().clone(),
+                name: column_name.into(),
+                sort_order: row.parse("Column Order")?.unwrap_or_default(),
+            });
+        }
+        let mut task = read_task(&row)?;
+        task.project_id = backup.project.get_id().clone();
+        if !column_name.is_empty() {
+            task.extra
+                .insert("columnId".into(), column_name.to_string().into());
+        }
+        backup.tasks.push(task);
+    }
+    Ok(Backup {
+        schema_version: SCHEMA_VERSION,
+        created_time: Utc::now().trunc_subsecs(3),
+        projects,
+    })
+}
+
+/// Write `backup` in TickTick's CSV backup format.
+pub fn write(backup: &Backup, writer: impl Write) -> Result<(), CsvError> {
+    let mut writer = WriterBuilder::new()
+        .flexible(true)
+        .quote_style(QuoteStyle::Always)
+        .from_writer(writer);
+    writer.write_record([format!("Date: {}", Utc::now().format("%Y-%m-%d%z"))])?;
+    writer.write_record([format!("Version: {}", FORMAT_VERSION)])?;
+    writer.write_record(["Status: \n0 Normal\n1 Completed\n2 Archived"])?;
+    writer.write_record(HEADER)?;
+    for project in &backup.projects {
+        for task in &project.tasks {
+            writer.write_record(task_record(project, task))?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+struct Row<'a> {
+    record: StringRecord,
+    columns: &'a HashMap<String, usize>,
+}
+
+impl Row<'_> {
+    /// Value of `column`, empty if the column is missing.
+    fn get(&self, column: &str) -> &str {
+        self.columns
+            .get(column)
+            .and_then(|index| self.record.get(*index))
+            .unwrap_or_default()
+    }
+    fn parse<T: std::str::FromStr>(&self, column: &str) -> Result<Option<T>, CsvError> {
+        let value = self.get(column).trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        value.parse().map(Some).map_err(|_| self.invalid(column))
+    }
+    fn date(&self, column: &str) -> Result<DateTime<Utc>, CsvError> {
+        let value = self.get(column).trim();
+        if value.is_empty() {
+            return Ok(DateTime::default());
+        }
+        DateTime::parse_from_str(value, DATE_FORMAT)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|_| self.invalid(column))
+    }
+    fn flag(&self, column: &str) -> bool {
+        matches!(
+            self.get(column).trim().to_ascii_lowercase().as_str(),
+            "y" | "yes" | "true"
+        )
+    }
+    fn invalid(&self, column: &str) -> CsvError {
+        CsvError::InvalidValue {
+            row: self
+                .record
+                .position()
+                .map(|position| position.line())
+                .unwrap_or_default(),
+            column: column.into(),
+            value: self.get(column).into(),
+        }
+    }
+}
+
+/// ID of the row's project, which is its list name if the backup has no list IDs.
+fn list_id<'a>(row: &'a Row) -> &'a str {
+    match row.get("listId") {
+        "" => row.get("List Name"),
+        id => id,
+    }
+}
+
+fn read_project(row: &Row) -> Project {
+    let mut project = Project {
+        id: ProjectID(list_id(row).into()),
+        name: row.get("List Name").into(),
+        view_mode: ProjectViewMode::from(row.get("View Mode").to_string()),
+        ..Default::default()
+    };
+    if row.get("Kind") == "NOTE" {
+        project.kind = ProjectKind::Note;
+    }
+    let folder = row.get("Folder Name");
+    if !folder.is_empty() {
+        project
+            .extra
+            .insert(FOLDER_NAME_KEY.into(), folder.to_string().into());
+    }
+    project
+}
+
+fn read_task(row: &Row) -> Result<Task, CsvError> {
+    let mut task = Task {
+        id: TaskID(row.get("taskId").into()),
+        title: row.get("Title").into(),
+        start_date: row.date("Start Date")?,
+        due_date: row.date("Due Date")?,
+        completed_time: row.date("Completed Time")?,
+        repeat_flag: row.get("Repeat").into(),
+        time_zone: row.get("Timezone").into(),
+        is_all_day: row.flag("Is All Day"),
+        sort_order: row.parse("Order")?.unwrap_or_default(),
+        ..Default::default()
+    };
+    task.priority = match row.parse::<u8>("Priority")?.unwrap_or_default() {
+        0 => TaskPriority::None,
+        1 => TaskPriority::Low,
+        3 => TaskPriority::Medium,
+        5 => TaskPriority::High,
+        _ => return Err(row.invalid("Priority")),
+    };
+    // 1 is completed and 2 is archived (completed, then hidden), neither exist separately in the Open API.
+    task.status = match row.parse::<u8>("Status")?.unwrap_or_default() {
+        0 => TaskStatus::Normal,
+        1 | 2 => TaskStatus::Completed,
+        _ => return Err(row.invalid("Status")),
+    };
+    task.tags = split_list(row.get("Tags"));
+    task.reminders = split_list(row.get("Reminder"));
+    let content = row.get("Content");
+    if row.flag("Is Check list") {
+        let mut description = Vec::new();
+        for line in content.lines() {
+            if let Some(title) = line.strip_prefix(OPEN_ITEM) {
+                task.subtasks.push(Subtask::new(title));
+            } else if let Some(title) = line.strip_prefix(COMPLETED_ITEM) {
+                let mut subtask = Subtask::new(title);
+                subtask.status = SubtaskStatus::Completed;
+                task.subtasks.push(subtask);
+            } else {
+                description.push(line);
+            }
+        }
+        task.desc = description.join("\n");
+    } else {
+        task.content = content.into();
+    }
+    for (column, key) in [
+        ("Kind", "kind"),
+        ("Created Time", "createdTime"),
+        ("parentId", "parentId"),
+    ] {
+        let value = row.get(column);
+        if !value.is_empty() {
+            let value = if key == "createdTime" {
+                crate::ticktick_datetime_format::format(&row.date(column)?)
+            } else {
+                value.into()
+            };
+            task.extra.insert(key.into(), value.into());
+        }
+    }
+    Ok(task)
+}
+
+fn task_record(backup: &ProjectBackup, task: &Task) -> Vec<String> {
+    let project = &backup.project;
+    let is_checklist = !task.subtasks.is_empty() || task.kind() == Some("CHECKLIST");
+    let content = if is_checklist {
+        let items = task.subtasks.iter().map(|subtask| {
+            let marker = if subtask.status == SubtaskStatus::Completed {
+                COMPLETED_ITEM
+            } else {
+                OPEN_ITEM
+            };
+            format!("{}{}", marker, subtask.title)
+        });
+        let description = (!task.desc.is_empty()).then(|| task.desc.clone());
+        description
+            .into_iter()
+            .chain(items)
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        task.content.clone()
+    };
+    let column = task
+        .column_id()
+        .and_then(|id| backup.columns.iter().find(|column| *column.get_id() == id));
+    let kind = task
+        .kind()
+        .unwrap_or(if is_checklist { "CHECKLIST" } else { "TEXT" });
+    let status = match task.status {
+        TaskStatus::Normal => "0",
+        TaskStatus::Completed => "1",
+    };
+    let view_mode = match project.view_mode {
+        ProjectViewMode::List => "list",
+        ProjectViewMode::Kanban => "kanban",
+        ProjectViewMode::Timeline => "timeline",
+    };
+    vec![
+        project
+            .extra
+            .get(FOLDER_NAME_KEY)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .into(),
+        project.name.clone(),
+        task.title.clone(),
+        kind.into(),
+        task.tags.join(","),
+        content,
+        if is_checklist { "Y" } else { "N" }.into(),
+        format_date(&task.start_date),
+        format_date(&task.due_date),
+        task.reminders.join(","),
+        task.repeat_flag.clone(),
+        (task.priority as u8).to_string(),
+        status.into(),
+        task.created_time()
+            .map(|date| format_date(&date))
+            .unwrap_or_default(),
+        format_date(&task.completed_time),
+        task.sort_order.to_string(),
+        task.time_zone.clone(),
+        task.is_all_day.to_string(),
+        "false".into(),
+        column
+            .map(|column| column.get_name().to_string())
+            .unwrap_or_default(),
+        column
+            .map(|column| column.get_sort_order().to_string())
+            .unwrap_or_default(),
+        view_mode.into(),
+        task.get_id().0.clone(),
+        task.extra
+            .get("parentId")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .into(),
+        project.get_id().0.clone(),
+    ]
+}
+
+/// Unset dates are deserialized as the Unix epoch, and written as empty cells.
+fn format_date(date: &DateTime<Utc>) -> String {
+    if date.timestamp() == 0 {
+        return String::new();
+    }
+    date.format(DATE_FORMAT).to_string()
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKUP: &str = "\"Date: 2024-03-01+0000\"\n\
+\"Version: 7.1\"\n\
+\"Status: \n0 Normal\n1 Completed\n2 Archived\"\n\
+\"Folder Name\",\"List Name\",\"Title\",\"Kind\",\"Tags\",\"Content\",\"Is Check list\",\"Start Date\",\"Due Date\",\"Reminder\",\"Repeat\",\"Priority\",\"Status\",\"Created Time\",\"Completed Time\",\"Order\",\"Timezone\",\"Is All Day\",\"Is Floating\",\"Column Name\",\"Column Order\",\"View Mode\",\"taskId\",\"parentId\"\n\
+\"Home\",\"Chores\",\"Pay rent\",\"TEXT\",\"bills,home\",\"By transfer\",\"N\",\"\",\"2024-03-01T09:00:00+0000\",\"TRIGGER:-PT15M\",\"\",\"5\",\"0\",\"2024-02-20T08:00:00+0000\",\"\",\"-1099511627776\",\"Europe/London\",\"false\",\"false\",\"To do\",\"0\",\"kanban\",\"t1\",\"\"\n\
+\"\",\"Reading\",\"Novels\",\"CHECKLIST\",\"\",\"Next up\n▫Dune\n▪Emma\",\"Y\",\"\",\"\",\"\",\"\",\"0\",\"2\",\"\",\"2024-02-28T10:00:00+0000\",\"0\",\"\",\"false\",\"false\",\"\",\"\",\"list\",\"t2\",\"\"\n\
+\"Home\",\"Chores\",\"Vacuum\",\"TEXT\",\"\",\"\",\"N\",\"\",\"\",\"\",\"\",\"1\",\"0\",\"\",\"\",\"0\",\"\",\"false\",\"false\",\"To do\",\"0\",\"kanban\",\"t3\",\"\"\n";
+
+    #[test]
+    fn reads_backup() {
+        let backup = read(BACKUP.as_bytes()).unwrap();
+        let [chores, reading] = &backup.projects[..] else {
+            panic!("expected two projects, got {:?}", backup.projects);
+        };
+        assert_eq!(chores.project.name, "Chores");
+        assert_eq!(chores.project.view_mode, ProjectViewMode::Kanban);
+        assert_eq!(chores.project.extra[FOLDER_NAME_KEY], "Home");
+        assert_eq!(chores.columns.len(), 1);
+        assert_eq!(chores.tasks.len(), 2);
+
+        let rent = &chores.tasks[0];
+        assert_eq!(rent.get_id().0, "t1");
+        assert_eq!(rent.project_id.0, "Chores");
+        assert_eq!(rent.tags, ["bills", "home"]);
+        assert_eq!(rent.reminders, ["TRIGGER:-PT15M"]);
+        assert_eq!(rent.priority, TaskPriority::High);
+        assert_eq!(rent.due_date.to_rfc3339(), "2024-03-01T09:00:00+00:00");
+        assert_eq!(rent.start_date.timestamp(), 0);
+        assert_eq!(rent.column_id().unwrap().0, "To do");
+        assert!(rent.created_time().is_some());
+
+        let novels = &reading.tasks[0];
+        assert_eq!(novels.status, TaskStatus::Completed);
+        assert_eq!(novels.desc, "Next up");
+        let subtasks: Vec<_> = novels
+            .subtasks
+            .iter()
+            .map(|subtask| (subtask.title.as_str(), subtask.status))
+            .collect();
+        assert_eq!(
+            subtasks,
+            [
+                ("Dune", SubtaskStatus::Normal),
+                ("Emma", SubtaskStatus::Completed)
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_backup() {
+        let backup = read(BACKUP.as_bytes()).unwrap();
+        let mut written = Vec::new();
+        write(&backup, &mut written).unwrap();
+        let reread = read(written.as_slice()).unwrap();
+        assert_eq!(reread.projects, backup.projects);
+    }
+
+    #[test]
+    fn keys_projects_by_list_id() {
+        let backup = Backup {
+            schema_version: SCHEMA_VERSION,
+            created_time: Utc::now(),
+            projects: ["p1", "p2"]
+                .into_iter()
+                .map(|id| ProjectBackup {
+                    project: serde_json::from_value(
+                        serde_json::json!({"id": id, "name": "Chores"}),
+                    )
+                    .unwrap(),
+                    columns: Vec::new(),
+                    tasks: vec![serde_json::from_value(
+                        serde_json::json!({"id": format!("{}-task", id), "projectId": id, "title": id}),
+                    )
+                    .unwrap()],
+                })
+                .collect(),
+        };
+        let mut written = Vec::new();
+        write(&backup, &mut written).unwrap();
+        let reread = read(written.as_slice()).unwrap();
+        let projects: Vec<_> = reread
+            .projects
+            .iter()
+            .map(|backup| {
+                (
+                    backup.project.get_id().0.as_str(),
+                    backup.tasks[0].project_id.0.as_str(),
+                    backup.tasks.len(),
+                )
+            })
+            .collect();
+        assert_eq!(projects, [("p1", "p1", 1), ("p2", "p2", 1)]);
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        assert!(matches!(
+            read("\"Date: 2024-03-01+0000\"\n\"Title\"\n".as_bytes()),
+            Err(CsvError::MissingHeader)
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_values_with_line() {
+        let backup = BACKUP.replacen("\"5\",\"0\"", "\"4\",\"0\"", 1);
+        match read(backup.as_bytes()) {
+            Err(CsvError::InvalidValue { row, column, value }) => {
+                assert_eq!((row, column.as_str(), value.as_str()), (8, "Priority", "4"));
+            }
+            result => panic!("expected an invalid priority, got {:?}", result),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
 use serde::{self, Deserialize, Deserializer, Serializer};
 
 const TICKTICK_DATETIME_FORMAT_STR: &str = "%Y-%m-%dT%H:%M:%S%.3f%z"; // "yyyy-MM-dd'T'HH:mm:ssZ"
@@ -6,8 +6,7 @@ pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Err
 where
     S: Serializer,
 {
-    let s = format!("{}", date.format(TICKTICK_DATETIME_FORMAT_STR));
-    serializer.serialize_str(&s)
+    serializer.serialize_str(&format(date))
 }
 
 pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
@@ -15,9 +14,18 @@ where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    let dt = NaiveDateTime::parse_from_str(&s, TICKTICK_DATETIME_FORMAT_STR)
-        .map_err(serde::de::Error::custom)?;
-    Ok(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
+    parse(&s).map_err(serde::de::Error::custom)
+}
+
+/// Format a datetime in TickTick's format.
+pub(crate) fn format(date: &DateTime<Utc>) -> String {
+    format!("{}", date.format(TICKTICK_DATETIME_FORMAT_STR))
+}
+
+/// Parse a datetime string in TickTick's format, converting it to UTC. The API usually sends `+0000`, but some
+/// fields (e.g. `modifiedTime` and subtask times) carry the user's offset, like `+0800`.
+pub(crate) fn parse(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+    DateTime::parse_from_str(s, TICKTICK_DATETIME_FORMAT_STR).map(|date| date.with_timezone(&Utc))
 }
 
 pub mod optional_datetime {
@@ -37,3 +45,54 @@ pub mod optional_datetime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse_converts_offsets_to_utc() {
+        let cases = [
+            ("2026-10-18T20:30:00.000+0000", (2026, 10, 18, 20, 30)),
+            ("2026-10-18T20:30:00.000+0800", (2026, 10, 18, 12, 30)),
+            ("2026-10-18T09:15:00.000-0500", (2026, 10, 18, 14, 15)),
+            ("2026-10-18T05:00:00.000+0530", (2026, 10, 17, 23, 30)),
+            ("2026-10-18T23:45:00.000-0930", (2026, 10, 19, 9, 15)),
+        ];
+        for (value, (year, month, day, hour, minute)) in cases {
+            assert_eq!(
+                parse(value).unwrap(),
+                Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+                    .unwrap(),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn deserializes_offsets() {
+        let task: crate::tasks::Task = serde_json::from_value(serde_json::json!({
+            "dueDate": "2026-10-19T00:00:00.000+0800",
+            "items": [{ "completedTime": "2026-10-18T10:15:00.000+0800" }],
+        }))
+        .unwrap();
+        assert_eq!(
+            task.due_date,
+            Utc.with_ymd_and_hms(2026, 10, 18, 16, 0, 0).unwrap()
+        );
+        assert_eq!(
+            task.subtasks[0].completed_time,
+            Utc.with_ymd_and_hms(2026, 10, 18, 2, 15, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn format_round_trips() {
+        let date = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(format(&date), "2026-01-02T03:04:05.000+0000");
+        assert_eq!(parse(&format(&date)).unwrap(), date);
+    }
+}
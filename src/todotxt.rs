@@ -0,0 +1,310 @@
+//! Conversion between tasks and [todo.txt](https://github.com/todotxt/todo.txt) lines.
+//!
+//! ```text
+//! (A) 2024-05-01 Pay rent +Home @finance due:2024-05-31 id:6630a1e2
+//! x 2024-05-02 2024-05-01 Buy milk +Home pri:B id:6630a1f7
+//! ```
+//! Priorities map to letters (`High` is `A`, `Medium` is `B`, `Low` is `C`), tags to `@contexts` and the project name
+//! to a `+project`. The task's ID is kept in an `id:` tag so edited lines can be matched back to their task.
+//! Dates are in the local time zone.
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+
+use crate::tasks::{Task, TaskID, TaskPriority, TaskStatus};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A parsed todo.txt line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TodoTxtItem {
+    /// The task described by the line. Its ID is set from the `id:` tag, if any.
+    pub task: Task,
+    /// Name of the first `+project`.
+    pub project: Option<String>,
+}
+
+/// Format `task` as a todo.txt line. `project_name` is written as a `+project`. Spaces in it and in tags are replaced
+/// by `_`.
+pub fn format_line(task: &Task, project_name: Option<&str>) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let completed = task.status == TaskStatus::Completed;
+    if completed {
+        parts.push("x".into());
+        if let Some(date) = local_date(&task.completed_time) {
+            parts.push(date.format(DATE_FORMAT).to_string());
+        }
+    } else if let Some(letter) = priority_letter(task.priority) {
+        parts.push(format!("({})", letter));
+    }
+    // A creation date is only valid after a completion date on completed lines.
+    if let Some(created) = task.created_time() {
+        if !completed || local_date(&task.completed_time).is_some() {
+            parts.push(
+                created
+                    .with_timezone(&Local)
+                    .format(DATE_FORMAT)
+                    .to_string(),
+            );
+        }
+    }
+    parts.push(task.title.trim().into());
+    if let Some(project) = project_name {
+        parts.push(format!("+{}", project.replace(' ', "_")));
+    }
+    for tag in &task.tags {
+        parts.push(format!("@{}", tag.replace(' ', "_")));
+    }
+    if let Some(due) = local_date(&task.due_date) {
+        parts.push(format!("due:{}", due.format(DATE_FORMAT)));
+    }
+    // Completed lines drop their priority, keep it as a tag as most todo.txt clients do.
+    if completed {
+        if let Some(letter) = priority_letter(task.priority) {
+            parts.push(format!("pri:{}", letter));
+        }
+    }
+    if !task.get_id().is_empty() {
+        parts.push(format!("id:{}", task.get_id().0));
+    }
+    parts.join(" ")
+}
+
+/// Parse a todo.txt line. Returns `None` for blank lines.
+///
+/// `due:` dates become all-day due dates. `+project`s other than the first, and unknown `key:value` tags, are kept in
+/// the title.
+pub fn parse_line(line: &str) -> Option<TodoTxtItem> {
+    let mut tokens = line.split_whitespace().peekable();
+    tokens.peek()?;
+    let mut task = Task::default();
+    if tokens.peek() == Some(&"x") {
+        tokens.next();
+        task.status = TaskStatus::Completed;
+        if let Some(date) = tokens.peek().and_then(|token| parse_date(token)) {
+            tokens.next();
+            task.completed_time = local_midnight(date);
+        }
+    } else if let Some(priority) = tokens.peek().and_then(|token| parse_priority(token)) {
+        tokens.next();
+        task.priority = priority;
+    }
+    if let Some(date) = tokens.peek().and_then(|token| parse_date(token)) {
+        tokens.next();
+        task.extra.insert(
+            "createdTime".into(),
+            crate::ticktick_datetime_format::format(&local_midnight(date)).into(),
+        );
+    }
+    let mut project = None;
+    let mut title = Vec::new();
+    for token in tokens {
+        if let Some(name) = token.strip_prefix('+').filter(|name| !name.is_empty()) {
+            if project.is_none() {
+                project = Some(name.replace('_', " "));
+                continue;
+            }
+        } else if let Some(tag) = token.strip_prefix('@').filter(|tag| !tag.is_empty()) {
+            task.tags.push(tag.into());
+            continue;
+        } else if let Some((key, value)) = token.split_once(':') {
+            match key {
+                "due" => {
+                    if let Some(date) = parse_date(value) {
+                        task.due_date = local_midnight(date);
+                        task.is_all_day = true;
+                        continue;
+                    }
+                }
+                "id" if !value.is_empty() => {
+                    task.id = TaskID(value.into());
+                    continue;
+                }
+                "pri" => {
+                    if let Some(priority) = parse_priority(&format!("({})", value)) {
+                        task.priority = priority;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+        }
+        title.push(token);
+    }
+    task.title = title.join(" ");
+    Some(TodoTxtItem { task, project })
+}
+
+/// Parse every non-blank line of a todo.txt file.
+pub fn parse(contents: &str) -> Vec<TodoTxtItem> {
+    contents.lines().filter_map(parse_line).collect()
+}
+
+impl TodoTxtItem {
+    /// Copy the fields a todo.txt line can express onto `task`, returning whether anything changed.
+    ///
+    /// The due time is kept if the due date is the same, and the completed time if the task was already completed.
+    /// Tags that match one of the task's tags with its spaces replaced by `_` keep the task's spelling.
+    pub fn apply_to(&self, task: &mut Task) -> bool {
+        let before = task.clone();
+        task.title = self.task.title.clone();
+        task.priority = self.task.priority;
+        task.tags = self
+            .task
+            .tags
+            .iter()
+            .map(|tag| {
+                before
+                    .tags
+                    .iter()
+                    .find(|existing| existing.replace(' ', "_") == *tag)
+                    .unwrap_or(tag)
+                    .clone()
+            })
+            .collect();
+        if local_date(&task.due_date) != local_date(&self.task.due_date) {
+            task.due_date = self.task.due_date;
+            task.is_all_day = self.task.is_all_day;
+        }
+        if task.status != self.task.status {
+            task.status = self.task.status;
+            task.completed_time = self.task.completed_time;
+        }
+        *task != before
+    }
+}
+
+fn priority_letter(priority: TaskPriority) -> Option<char> {
+    match priority {
+        TaskPriority::None => None,
+        TaskPriority::High => Some('A'),
+        TaskPriority::Medium => Some('B'),
+        TaskPriority::Low => Some('C'),
+    }
+}
+
+/// Parse `(A)` style priorities. Letters after `C` are treated as low priority.
+fn parse_priority(token: &str) -> Option<TaskPriority> {
+    let letter = token.strip_prefix('(')?.strip_suffix(')')?;
+    match letter {
+        "A" => Some(TaskPriority::High),
+        "B" => Some(TaskPriority::Medium),
+        _ if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) => {
+            Some(TaskPriority::Low)
+        }
+        _ => None,
+    }
+}
+
+fn parse_date(token: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(token, DATE_FORMAT).ok()
+}
+
+/// Local date of `date`, `None` if it's unset (deserialized as the Unix epoch).
+fn local_date(date: &DateTime<Utc>) -> Option<NaiveDate> {
+    (date.timestamp() != 0).then(|| date.with_timezone(&Local).date_naive())
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> DateTime<Utc> {
+        local_midnight(parse_date(value).unwrap())
+    }
+
+    #[test]
+    fn parses_line() {
+        let item = parse_line("(A) 2024-05-01 Pay rent +Home @finance due:2024-05-31 +Other id:t1")
+            .unwrap();
+        assert_eq!(item.project.as_deref(), Some("Home"));
+        let task = item.task;
+        assert_eq!(task.title, "Pay rent +Other");
+        assert_eq!(task.priority, TaskPriority::High);
+        assert_eq!(task.tags, ["finance"]);
+        assert_eq!(task.due_date, date("2024-05-31"));
+        assert!(task.is_all_day);
+        assert_eq!(task.created_time(), Some(date("2024-05-01")));
+        assert_eq!(task.get_id().0, "t1");
+    }
+
+    #[test]
+    fn parses_completed_line() {
+        let task = parse_line("x 2024-05-02 2024-05-01 Buy milk pri:B note:keep")
+            .unwrap()
+            .task;
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.completed_time, date("2024-05-02"));
+        assert_eq!(task.created_time(), Some(date("2024-05-01")));
+        assert_eq!(task.priority, TaskPriority::Medium);
+        assert_eq!(task.title, "Buy milk note:keep");
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        assert_eq!(parse_line("   "), None);
+        assert_eq!(parse("Call Bob\n\n(D) Call Alice\n").len(), 2);
+        assert_eq!(parse_priority("(D)"), Some(TaskPriority::Low));
+        assert_eq!(parse_priority("(a)"), None);
+    }
+
+    #[test]
+    fn formats_line() {
+        let line = "(A) 2024-05-01 Pay rent +Home_Office @finance due:2024-05-31 id:t1";
+        let item = parse_line(line).unwrap();
+        assert_eq!(item.project.as_deref(), Some("Home Office"));
+        assert_eq!(format_line(&item.task, item.project.as_deref()), line);
+
+        let line = "x 2024-05-02 2024-05-01 Buy milk pri:B";
+        assert_eq!(format_line(&parse_line(line).unwrap().task, None), line);
+    }
+
+    #[test]
+    fn round_trips_tags_with_spaces() {
+        let mut task = Task {
+            title: "Plan trip".into(),
+            tags: vec!["home office".into(), "travel_plans".into()],
+            ..Default::default()
+        };
+        let line = format_line(&task, None);
+        assert_eq!(line, "Plan trip @home_office @travel_plans");
+        let item = parse_line(&line).unwrap();
+        assert!(!item.apply_to(&mut task));
+        assert_eq!(task.tags, ["home office", "travel_plans"]);
+
+        let item = parse_line("Plan trip @home_office @new_tag").unwrap();
+        assert!(item.apply_to(&mut task));
+        assert_eq!(task.tags, ["home office", "new_tag"]);
+    }
+
+    #[test]
+    fn apply_keeps_due_time_on_same_day() {
+        let mut task = Task {
+            title: "Pay rent".into(),
+            due_date: date("2024-05-31") + chrono::Duration::hours(9),
+            ..Default::default()
+        };
+        let item = parse_line("Pay the rent due:2024-05-31").unwrap();
+        assert!(item.apply_to(&mut task));
+        assert_eq!(task.title, "Pay the rent");
+        assert_eq!(
+            task.due_date,
+            date("2024-05-31") + chrono::Duration::hours(9)
+        );
+        assert!(!task.is_all_day);
+        assert!(!item.apply_to(&mut task));
+
+        let item = parse_line("x 2024-06-01 Pay the rent due:2024-06-30").unwrap();
+        assert!(item.apply_to(&mut task));
+        assert_eq!(task.due_date, date("2024-06-30"));
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.completed_time, date("2024-06-01"));
+    }
+}
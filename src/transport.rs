@@ -0,0 +1,240 @@
+use std::{fmt::Debug, future::Future, pin::Pin};
+
+use serde::{Deserialize, Serialize};
+
+/// HTTP methods used by the TickTick API.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Delete,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+/// Request handed to a `Transport`. Its `Debug` output redacts the `Authorization` and cookie headers.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: HttpMethod, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+    /// Set body to serialized JSON, along with the matching Content-Type header.
+    pub fn json<B: Serialize + ?Sized>(self, body: &B) -> Result<Self, serde_json::Error> {
+        let mut request = self.header("Content-Type", "application/json");
+        request.body = Some(serde_json::to_string(body)?);
+        Ok(request)
+    }
+    /// Set body to a URL encoded form, along with the matching Content-Type header.
+    pub fn form(self, fields: &[(&str, &str)]) -> Self {
+        let mut request = self.header("Content-Type", "application/x-www-form-urlencoded");
+        request.body = Some(
+            oauth2::url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(fields)
+                .finish(),
+        );
+        request
+    }
+    /// Get the value of a header, matched case-insensitively.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+impl Debug for HttpRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                if is_sensitive_header(name) {
+                    (name.as_str(), "[REDACTED]")
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+        f.debug_struct("HttpRequest")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &headers)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// Request headers carrying credentials, which are kept out of logs and recordings.
+pub(crate) const SENSITIVE_REQUEST_HEADERS: [&str; 3] =
+    ["authorization", "cookie", "proxy-authorization"];
+
+fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_REQUEST_HEADERS
+        .iter()
+        .any(|header| header.eq_ignore_ascii_case(name))
+}
+
+/// Response returned by a `Transport`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+    /// Create response with a serialized JSON body.
+    pub fn json<B: Serialize + ?Sized>(status: u16, body: &B) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            status,
+            headers: vec![("Content-Type".into(), "application/json".into())],
+            body: serde_json::to_string(body)?,
+        })
+    }
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+    /// Get the value of a header, matched case-insensitively.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Errors that can occur while sending a request through a `Transport`.
+#[derive(Debug)]
+pub enum TransportError {
+    ReqwestError(reqwest::Error),
+    /// Error raised by a non-reqwest transport.
+    Other(String),
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::ReqwestError(value)
+    }
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReqwestError(err) => write!(f, "{}", err),
+            Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, TransportError>> + Send + 'a>>;
+
+/// Sends HTTP requests on behalf of `TickTick`, the builders and `AwaitingAuthCode::finish_auth`.
+/// `ReqwestTransport` is used by default, implement this to route requests elsewhere (e.g. in tests).
+pub trait Transport: Send + Sync + Debug {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+/// Default `Transport`, sends requests over the network using reqwest.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http_client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self { http_client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let method = match request.method {
+                HttpMethod::Get => reqwest::Method::GET,
+                HttpMethod::Post => reqwest::Method::POST,
+                HttpMethod::Delete => reqwest::Method::DELETE,
+            };
+            let mut builder = self.http_client.request(method, &request.url);
+            for (name, value) in &request.headers {
+                let mut value = reqwest::header::HeaderValue::from_str(value)
+                    .map_err(|err| TransportError::Other(err.to_string()))?;
+                value.set_sensitive(is_sensitive_header(name));
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            let resp = builder.send().await?;
+            let status = resp.status().as_u16();
+            let headers = resp
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: resp.text().await?,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let request = HttpRequest::new(HttpMethod::Get, "https://ticktick.com/open/v1/project")
+            .header("Authorization", "Bearer secret-token")
+            .header("Accept", "application/json");
+        let debug = format!("{:?}", request);
+        assert!(!debug.contains("secret-token"), "{}", debug);
+        assert!(
+            debug.contains("(\"Authorization\", \"[REDACTED]\")"),
+            "{}",
+            debug
+        );
+        assert!(debug.contains("application/json"), "{}", debug);
+    }
+}
@@ -0,0 +1,568 @@
+//! TickTick's built-in smart lists (Today, Tomorrow, Next 7 Days and Overdue), computed from fetched tasks. Enabled
+//! with the `views` feature.
+//! ```ignore
+//! for day in ticktick.smart_view(SmartView::Next7Days).await? {
+//!     println!("{}", day.date);
+//!     for task in &day.tasks {
+//!         println!("  {}", task.title);
+//!     }
+//! }
+//! ```
+//! Days are in the local time zone, or the one passed to `smart_view_in`. All-day tasks keep the date they have in
+//! their own `time_zone`, so a task due on the 20th is on the 20th wherever you are. Tasks with a start date are
+//! listed on every day from their start to their due date.
+//!
+//! Repeating tasks are listed on each of their occurrences in the view, as copies of the task with `due_date` and
+//! `start_date` moved to the occurrence. `repeat_flag` RRULEs with `FREQ`, `INTERVAL`, `BYDAY`, `BYMONTHDAY`,
+//! `BYMONTH`, `COUNT` and `UNTIL` are supported. Only open tasks are included.
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, TimeZone, Utc, Weekday};
+
+use crate::{
+    tasks::{Task, TaskStatus},
+    TickTick, TickTickError,
+};
+
+/// One of TickTick's built-in smart lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartView {
+    Today,
+    Tomorrow,
+    /// Today and the 6 days after it.
+    Next7Days,
+    /// Open tasks whose due date has passed, grouped by their due date.
+    Overdue,
+}
+
+impl std::str::FromStr for SmartView {
+    type Err = String;
+    /// Parse a view from its name: `today`, `tomorrow`, `next7days` (or `week`) or `overdue`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value
+            .to_ascii_lowercase()
+            .replace(['-', '_', ' '], "")
+            .as_str()
+        {
+            "today" => Ok(Self::Today),
+            "tomorrow" => Ok(Self::Tomorrow),
+            "next7days" | "week" => Ok(Self::Next7Days),
+            "overdue" => Ok(Self::Overdue),
+            _ => Err(format!("Unknown smart view \"{}\"", value)),
+        }
+    }
+}
+
+impl std::fmt::Display for SmartView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Today => "today",
+            Self::Tomorrow => "tomorrow",
+            Self::Next7Days => "next7days",
+            Self::Overdue => "overdue",
+        })
+    }
+}
+
+/// Tasks listed on a day of a smart view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayGroup {
+    pub date: NaiveDate,
+    pub tasks: Vec<Task>,
+}
+
+impl TickTick {
+    /// Fetch every open task, including the Inbox, and group it into `view` in the local time zone.
+    pub async fn smart_view(&self, view: SmartView) -> Result<Vec<DayGroup>, TickTickError> {
+        self.smart_view_in(view, &Local).await
+    }
+
+    /// Fetch every open task, including the Inbox, and group it into `view` in `time_zone`.
+    pub async fn smart_view_in<Tz: TimeZone>(
+        &self,
+        view: SmartView,
+        time_zone: &Tz,
+    ) -> Result<Vec<DayGroup>, TickTickError> {
+        let tasks = self.tasks().get_all_with_inbox().await?;
+        Ok(group_tasks(
+            &tasks,
+            view,
+            &Utc::now().with_timezone(time_zone),
+        ))
+    }
+}
+
+/// Group `tasks` into `view` as of `now`, with days in `now`'s time zone. Days without tasks are left out.
+pub fn group_tasks<Tz: TimeZone>(
+    tasks: &[Task],
+    view: SmartView,
+    now: &DateTime<Tz>,
+) -> Vec<DayGroup> {
+    let zone = now.timezone();
+    let today = now.date_naive();
+    let (first, last) = match view {
+        SmartView::Today => (today, today),
+        SmartView::Tomorrow => (today + Days::new(1), today + Days::new(1)),
+        SmartView::Next7Days => (today, today + Days::new(6)),
+        SmartView::Overdue => (NaiveDate::MIN, today),
+    };
+    let mut days: BTreeMap<NaiveDate, Vec<Task>> = BTreeMap::new();
+    for task in tasks
+        .iter()
+        .filter(|task| task.status == TaskStatus::Normal)
+    {
+        if view == SmartView::Overdue {
+            let Some(due) = date_set(&task.due_date) else {
+                continue;
+            };
+            let due_day = day_of(task, &due, &zone);
+            let is_overdue = if task.is_all_day {
+                due_day < today
+            } else {
+                due < now.with_timezone(&Utc)
+            };
+            if is_overdue {
+                days.entry(due_day).or_default().push(task.clone());
+            }
+            continue;
+        }
+        for occurrence in occurrences(task, &zone, last) {
+            let Some((start, end)) = span(&occurrence, &zone) else {
+                continue;
+            };
+            let mut day = start.max(first);
+            while day <= end.min(last) {
+                days.entry(day).or_default().push(occurrence.clone());
+                day = day + Days::new(1);
+            }
+        }
+    }
+    days.into_iter()
+        .map(|(date, tasks)| DayGroup { date, tasks })
+        .collect()
+}
+
+/// `date`, or `None` if it's unset (deserialized as the Unix epoch).
+fn date_set(date: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+    (date.timestamp() != 0).then_some(*date)
+}
+
+fn task_time_zone(task: &Task) -> Option<chrono_tz::Tz> {
+    task.time_zone.parse().ok()
+}
+
+/// Day `datetime` falls on. All-day dates are taken in the task's time zone, if it has one.
+fn day_of<Tz: TimeZone>(task: &Task, datetime: &DateTime<Utc>, zone: &Tz) -> NaiveDate {
+    match task_time_zone(task).filter(|_| task.is_all_day) {
+        Some(task_zone) => datetime.with_timezone(&task_zone).date_naive(),
+        None => datetime.with_timezone(zone).date_naive(),
+    }
+}
+
+/// First and last day of the task, from its start date (if any) to its due date.
+fn span<Tz: TimeZone>(task: &Task, zone: &Tz) -> Option<(NaiveDate, NaiveDate)> {
+    let start = date_set(&task.start_date).map(|start| day_of(task, &start, zone));
+    let due = date_set(&task.due_date).map(|due| day_of(task, &due, zone));
+    let start = start.or(due)?;
+    Some((start, due.unwrap_or(start).max(start)))
+}
+
+/// The task itself, followed by copies for its later occurrences up to the day after `last`.
+fn occurrences<Tz: TimeZone>(task: &Task, zone: &Tz, last: NaiveDate) -> Vec<Task> {
+    let mut occurrences = vec![task.clone()];
+    let Some(rule) = Rule::parse(&task.repeat_flag) else {
+        return occurrences;
+    };
+    let Some(anchor) = date_set(&task.due_date).or(date_set(&task.start_date)) else {
+        return occurrences;
+    };
+    // Occurrences keep their wall-clock time in the task's time zone, across daylight saving changes.
+    match task_time_zone(task) {
+        Some(task_zone) => expand(task, &rule, &anchor, &task_zone, last, &mut occurrences),
+        None => expand(task, &rule, &anchor, zone, last, &mut occurrences),
+    }
+    occurrences
+}
+
+fn expand<Tz: TimeZone>(
+    task: &Task,
+    rule: &Rule,
+    anchor: &DateTime<Utc>,
+    zone: &Tz,
+    last: NaiveDate,
+    occurrences: &mut Vec<Task>,
+) {
+    let base = anchor.with_timezone(zone).date_naive();
+    for day in rule.dates_after(base, last + Days::new(1)) {
+        let days = (day - base).num_days();
+        let mut occurrence = task.clone();
+        if let Some(due) = date_set(&task.due_date) {
+            occurrence.due_date = shift_days(&due, days, zone);
+        }
+        if let Some(start) = date_set(&task.start_date) {
+            occurrence.start_date = shift_days(&start, days, zone);
+        }
+        occurrences.push(occurrence);
+    }
+}
+
+/// Move `datetime` by `days`, keeping its wall-clock time in `zone`.
+fn shift_days<Tz: TimeZone>(datetime: &DateTime<Utc>, days: i64, zone: &Tz) -> DateTime<Utc> {
+    let local = datetime.with_timezone(zone).naive_local();
+    let shifted = if days < 0 {
+        local.checked_sub_days(Days::new(days.unsigned_abs()))
+    } else {
+        local.checked_add_days(Days::new(days as u64))
+    };
+    shifted
+        .and_then(|shifted| zone.from_local_datetime(&shifted).earliest())
+        .map_or(*datetime, |shifted| shifted.with_timezone(&Utc))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The subset of an RRULE needed to list occurrences by day.
+#[derive(Debug, Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    /// Weekdays, with an optional ordinal within the month, e.g. `-1FR` for the last Friday.
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+}
+
+impl Rule {
+    /// Parse a `repeat_flag` like `RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO`.
+    fn parse(repeat_flag: &str) -> Option<Self> {
+        let mut rule = Self {
+            frequency: Frequency::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            count: None,
+            until: None,
+        };
+        let mut frequency = None;
+        let value = repeat_flag.trim();
+        for part in value.strip_prefix("RRULE:").unwrap_or(value).split(';') {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            let list = || value.split(',').map(str::trim);
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.trim().to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value.trim().parse().ok().filter(|interval| *interval > 0)?
+                }
+                "BYDAY" => rule.by_day = list().map(parse_by_day).collect::<Option<_>>()?,
+                "BYMONTHDAY" => {
+                    rule.by_month_day = list().map(|day| day.parse().ok()).collect::<Option<_>>()?
+                }
+                "BYMONTH" => {
+                    rule.by_month = list()
+                        .map(|month| month.parse().ok())
+                        .collect::<Option<_>>()?
+                }
+                "COUNT" => rule.count = Some(value.trim().parse().ok()?),
+                "UNTIL" => {
+                    rule.until =
+                        Some(NaiveDate::parse_from_str(value.trim().get(..8)?, "%Y%m%d").ok()?)
+                }
+                _ => {}
+            }
+        }
+        rule.frequency = frequency?;
+        Some(rule)
+    }
+
+    /// Occurrence days after `base`, which is the first occurrence, up to and including `last`.
+    fn dates_after(&self, base: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        let mut count = 1;
+        let mut day = base;
+        while let Some(next) = day.succ_opt() {
+            day = next;
+            if day > last || self.until.is_some_and(|until| day > until) {
+                break;
+            }
+            if !self.matches(day, base) {
+                continue;
+            }
+            count += 1;
+            if self.count.is_some_and(|limit| count > limit) {
+                break;
+            }
+            dates.push(day);
+        }
+        dates
+    }
+
+    fn matches(&self, day: NaiveDate, base: NaiveDate) -> bool {
+        let interval = i64::from(self.interval);
+        let in_period = match self.frequency {
+            Frequency::Daily => (day - base).num_days() % interval == 0,
+            Frequency::Weekly => (week_start(day) - week_start(base)).num_weeks() % interval == 0,
+            Frequency::Monthly => (month_index(day) - month_index(base)) % interval == 0,
+            Frequency::Yearly => i64::from(day.year() - base.year()) % interval == 0,
+        };
+        if !in_period || (!self.by_month.is_empty() && !self.by_month.contains(&day.month())) {
+            return false;
+        }
+        let weekday_matches = || {
+            self.by_day.iter().any(|(ordinal, weekday)| {
+                *weekday == day.weekday() && ordinal.is_none_or(|n| nth_in_month(day, n))
+            })
+        };
+        let month_day_matches = || {
+            let days_in_month = i32::try_from(days_in_month(day)).unwrap_or(31);
+            self.by_month_day.iter().any(|month_day| {
+                let month_day = if *month_day < 0 {
+                    days_in_month + 1 + month_day
+                } else {
+                    *month_day
+                };
+                month_day == day.day() as i32
+            })
+        };
+        // BYMONTHDAY limits every frequency, so `FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13` is every Friday the 13th.
+        if !self.by_month_day.is_empty() && !month_day_matches() {
+            return false;
+        }
+        match self.frequency {
+            Frequency::Daily => self.by_day.is_empty() || weekday_matches(),
+            Frequency::Weekly if self.by_day.is_empty() => day.weekday() == base.weekday(),
+            Frequency::Weekly => weekday_matches(),
+            _ if !self.by_day.is_empty() => weekday_matches(),
+            _ if !self.by_month_day.is_empty() => true,
+            Frequency::Monthly => day.day() == base.day(),
+            Frequency::Yearly if !self.by_month.is_empty() => day.day() == base.day(),
+            Frequency::Yearly => day.month() == base.month() && day.day() == base.day(),
+        }
+    }
+}
+
+/// Parse a `BYDAY` entry like `MO`, `2TU` or `-1FR`.
+fn parse_by_day(value: &str) -> Option<(Option<i32>, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let weekday = match value.get(split..)?.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+    let ordinal = &value[..split];
+    if ordinal.is_empty() {
+        Some((None, weekday))
+    } else {
+        Some((Some(ordinal.trim_start_matches('+').parse().ok()?), weekday))
+    }
+}
+
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(u64::from(day.weekday().num_days_from_monday()))
+}
+
+fn month_index(day: NaiveDate) -> i64 {
+    i64::from(day.year()) * 12 + i64::from(day.month0())
+}
+
+fn days_in_month(day: NaiveDate) -> u32 {
+    let first = day.with_day(1).unwrap_or(day);
+    (first + Months::new(1))
+        .pred_opt()
+        .map_or(31, |last| last.day())
+}
+
+/// Whether `day` is the `n`th of its weekday in its month, counting from the end if `n` is negative.
+fn nth_in_month(day: NaiveDate, n: i32) -> bool {
+    let from_start = (day.day() as i32 - 1) / 7 + 1;
+    let from_end = -((days_in_month(day) as i32 - day.day() as i32) / 7 + 1);
+    n == from_start || n == from_end
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Berlin;
+    use serde_json::json;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Titles of the tasks on each day of `view`, as of 2026-10-14 10:00 in Berlin (08:00 UTC).
+    fn view(view: SmartView) -> Vec<(u32, Vec<String>)> {
+        let tasks: Vec<Task> = [
+            json!({ "id": "late", "title": "late", "dueDate": "2026-10-14T07:00:00.000+0000" }),
+            // Past midnight in Berlin, so tomorrow even though it's still the 14th in UTC.
+            json!({ "id": "tonight", "title": "tonight", "dueDate": "2026-10-14T22:30:00.000+0000" }),
+            json!({ "id": "friday", "title": "friday", "dueDate": "2026-10-16T08:00:00.000+0000" }),
+            json!({
+                "id": "all-day",
+                "title": "all-day",
+                "dueDate": "2026-10-13T22:00:00.000+0000",
+                "isAllDay": true,
+                "timeZone": "Europe/Berlin"
+            }),
+            json!({
+                "id": "yesterday",
+                "title": "yesterday",
+                "dueDate": "2026-10-12T22:00:00.000+0000",
+                "isAllDay": true,
+                "timeZone": "Europe/Berlin"
+            }),
+            json!({ "id": "done", "title": "done", "dueDate": "2026-10-14T07:00:00.000+0000", "status": 2 }),
+            json!({
+                "id": "daily",
+                "title": "daily",
+                "dueDate": "2026-10-14T16:00:00.000+0000",
+                "repeatFlag": "RRULE:FREQ=DAILY;INTERVAL=2"
+            }),
+            json!({
+                "id": "span",
+                "title": "span",
+                "startDate": "2026-10-17T08:00:00.000+0000",
+                "dueDate": "2026-10-19T08:00:00.000+0000"
+            }),
+        ]
+        .into_iter()
+        .map(|task| serde_json::from_value(task).unwrap())
+        .collect();
+        let now = Berlin.with_ymd_and_hms(2026, 10, 14, 10, 0, 0).unwrap();
+        group_tasks(&tasks, view, &now)
+            .into_iter()
+            .map(|group| {
+                let titles = group.tasks.into_iter().map(|task| task.title).collect();
+                (group.date.day(), titles)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_today_and_tomorrow() {
+        assert_eq!(
+            view(SmartView::Today),
+            [(14, vec!["late".into(), "all-day".into(), "daily".into()])]
+        );
+        assert_eq!(view(SmartView::Tomorrow), [(15, vec!["tonight".into()])]);
+    }
+
+    #[test]
+    fn groups_next_7_days() {
+        let days: Vec<(u32, Vec<&str>)> = vec![
+            (14, vec!["late", "all-day", "daily"]),
+            (15, vec!["tonight"]),
+            (16, vec!["friday", "daily"]),
+            (17, vec!["span"]),
+            (18, vec!["daily", "span"]),
+            (19, vec!["span"]),
+            (20, vec!["daily"]),
+        ];
+        let expected: Vec<(u32, Vec<String>)> = days
+            .into_iter()
+            .map(|(day, titles)| (day, titles.into_iter().map(String::from).collect()))
+            .collect();
+        assert_eq!(view(SmartView::Next7Days), expected);
+    }
+
+    #[test]
+    fn groups_overdue() {
+        assert_eq!(
+            view(SmartView::Overdue),
+            [(13, vec!["yesterday".into()]), (14, vec!["late".into()])]
+        );
+    }
+
+    #[test]
+    fn parses_rule() {
+        let rule = Rule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,-1FR;UNTIL=20261231T000000Z")
+            .unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(
+            rule.by_day,
+            [(None, Weekday::Mon), (Some(-1), Weekday::Fri)]
+        );
+        assert_eq!(rule.until, Some(date(2026, 12, 31)));
+        assert!(Rule::parse("FREQ=HOURLY").is_none());
+        assert!(Rule::parse("FREQ=DAILY;INTERVAL=0").is_none());
+        assert!(Rule::parse("INTERVAL=2").is_none());
+        assert!(Rule::parse("").is_none());
+    }
+
+    #[test]
+    fn lists_rule_dates() {
+        let dates = |rule: &str, base: NaiveDate, last: NaiveDate| {
+            Rule::parse(rule).unwrap().dates_after(base, last)
+        };
+        assert_eq!(
+            dates(
+                "FREQ=MONTHLY;BYDAY=-1FR",
+                date(2026, 10, 30),
+                date(2027, 1, 31)
+            ),
+            [date(2026, 11, 27), date(2026, 12, 25), date(2027, 1, 29)]
+        );
+        assert_eq!(
+            dates(
+                "FREQ=DAILY;BYMONTHDAY=1,-1",
+                date(2026, 10, 14),
+                date(2026, 12, 1)
+            ),
+            [
+                date(2026, 10, 31),
+                date(2026, 11, 1),
+                date(2026, 11, 30),
+                date(2026, 12, 1)
+            ]
+        );
+        assert_eq!(
+            dates(
+                "FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13",
+                date(2026, 2, 13),
+                date(2026, 12, 31)
+            ),
+            [date(2026, 3, 13), date(2026, 11, 13)]
+        );
+        assert_eq!(
+            dates("FREQ=DAILY;COUNT=3", date(2026, 10, 14), date(2026, 12, 31)),
+            [date(2026, 10, 15), date(2026, 10, 16)]
+        );
+        assert_eq!(
+            dates("FREQ=YEARLY", date(2024, 2, 29), date(2028, 12, 31)),
+            [date(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn matches_interval() {
+        let rule = Rule::parse("FREQ=WEEKLY;INTERVAL=2").unwrap();
+        let base = date(2026, 10, 12);
+        assert!(!rule.matches(date(2026, 10, 19), base));
+        assert!(rule.matches(date(2026, 10, 26), base));
+        assert!(!rule.matches(date(2026, 10, 27), base));
+    }
+}
@@ -0,0 +1,384 @@
+//! Polling change-watcher. The TickTick Open API has no webhooks, so `Watcher` periodically snapshots
+//! projects and tasks and emits the differences as a `Stream` of `WatchEvent`s. Enabled with the `watch` feature.
+//! ```ignore
+//! let events = Watcher::new(&ticktick)
+//!     .interval(Duration::from_secs(60))
+//!     .state_file("watch-state.json")
+//!     .into_stream();
+//! futures_util::pin_mut!(events);
+//! while let Some(event) = events.next().await {
+//!     println!("{:?}", event?);
+//! }
+//! ```
+use std::{
+    collections::{HashSet, VecDeque},
+    path::PathBuf,
+    time::Duration,
+};
+
+use futures_util::{stream, Stream};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    projects::{Project, ProjectID},
+    sync::{ChangeSet, ProjectChange, Snapshot, TaskChange},
+    tasks::{Task, TaskStatus},
+    TickTick, TickTickError,
+};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Change observed between two polls.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum WatchEvent {
+    TaskCreated(Task),
+    TaskUpdated {
+        before: Task,
+        after: Task,
+        /// API names of the fields that changed, e.g. `dueDate`.
+        changed_fields: Vec<String>,
+    },
+    TaskCompleted(Task),
+    TaskDeleted(Task),
+    ProjectCreated(Project),
+    ProjectUpdated {
+        before: Project,
+        after: Project,
+        changed_fields: Vec<String>,
+    },
+    ProjectDeleted(Project),
+}
+
+/// Errors that can occur while watching.
+#[derive(Debug)]
+pub enum WatchError {
+    ApiError(TickTickError),
+    /// Failed to load or save the state file.
+    StateError(std::io::Error),
+}
+
+impl From<TickTickError> for WatchError {
+    fn from(value: TickTickError) -> Self {
+        Self::ApiError(value)
+    }
+}
+
+impl From<std::io::Error> for WatchError {
+    fn from(value: std::io::Error) -> Self {
+        Self::StateError(value)
+    }
+}
+
+impl std::fmt::Display for WatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ApiError(err) => write!(f, "{}", err),
+            Self::StateError(err) => write!(f, "Watcher state error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for WatchError {}
+
+/// Polls TickTick for changes. Configure with the builder methods, then call `into_stream` or `poll`.
+#[derive(Debug)]
+pub struct Watcher {
+    ticktick: TickTick,
+    interval: Duration,
+    projects: Option<HashSet<ProjectID>>,
+    state_file: Option<PathBuf>,
+    /// Last seen state, `None` until the first poll (or loading the state file).
+    last_seen: Option<State>,
+}
+
+/// Last seen state, as saved to the state file.
+#[derive(Serialize, Deserialize, Debug)]
+struct State {
+    /// Projects watched when the state was seen, `None` for all of them.
+    #[serde(default)]
+    watched: Option<HashSet<ProjectID>>,
+    #[serde(flatten)]
+    snapshot: Snapshot,
+}
+
+impl Watcher {
+    pub fn new(ticktick: &TickTick) -> Self {
+        Self {
+            ticktick: ticktick.clone(),
+            interval: DEFAULT_INTERVAL,
+            projects: None,
+            state_file: None,
+            last_seen: None,
+        }
+    }
+    /// Time between polls. Defaults to 60 seconds.
+    pub fn interval(mut self, value: Duration) -> Self {
+        self.interval = value;
+        self
+    }
+    /// Only watch these projects, and their tasks.
+    pub fn projects(mut self, value: Vec<ProjectID>) -> Self {
+        self.projects = Some(value.into_iter().collect());
+        self
+    }
+    /// Persist last seen state to `path`, so a restarted watcher only reports changes made since it last polled.
+    /// If the watched projects changed since, projects that were only watched by one of the runs aren't reported.
+    pub fn state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_file = Some(path.into());
+        self
+    }
+
+    /// Poll once, returning changes since the previous poll.
+    /// The first poll only records state, unless state was loaded from the state file.
+    pub async fn poll(&mut self) -> Result<Vec<WatchEvent>, WatchError> {
+        if self.last_seen.is_none() {
+            self.last_seen = self.load_state()?;
+        }
+        let mut current = Snapshot::fetch_projects(&self.ticktick, |project| {
+            is_watched(self.projects.as_ref(), project.get_id())
+        })
+        .await?;
+        let events = match &self.last_seen {
+            Some(last_seen) => {
+                // The watched projects may have changed since the state file was saved,
+                // only compare projects watched both then and now.
+                let before = watched_only(&last_seen.snapshot, self.projects.as_ref());
+                current
+                    .include_completed_since(&self.ticktick, &before)
+                    .await?;
+                events_between(&before, &watched_only(&current, last_seen.watched.as_ref()))
+            }
+            None => Vec::new(),
+        };
+        // Completed tasks have been reported, stop tracking them.
+        current
+            .tasks
+            .retain(|task| task.status != TaskStatus::Completed);
+        let state = State {
+            watched: self.projects.clone(),
+            snapshot: current,
+        };
+        self.save_state(&state)?;
+        self.last_seen = Some(state);
+        Ok(events)
+    }
+
+    /// Poll forever, yielding each event as it's found. Errors are yielded without ending the stream.
+    pub fn into_stream(self) -> impl Stream<Item = Result<WatchEvent, WatchError>> {
+        let state = (self, VecDeque::new(), true);
+        stream::unfold(state, |(mut watcher, mut pending, mut first)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((Ok(event), (watcher, pending, first)));
+                }
+                if !first {
+                    tokio::time::sleep(watcher.interval).await;
+                }
+                first = false;
+                match watcher.poll().await {
+                    Ok(events) => pending.extend(events),
+                    Err(err) => return Some((Err(err), (watcher, pending, first))),
+                }
+            }
+        })
+    }
+
+    fn load_state(&self) -> Result<Option<State>, WatchError> {
+        let Some(path) = &self.state_file else {
+            return Ok(None);
+        };
+        match std::fs::read(path) {
+            Ok(contents) => Ok(Some(
+                serde_json::from_slice(&contents).map_err(std::io::Error::from)?,
+            )),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save_state(&self, state: &State) -> Result<(), WatchError> {
+        if let Some(path) = &self.state_file {
+            std::fs::write(
+                path,
+                serde_json::to_vec(state).map_err(std::io::Error::from)?,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn is_watched(watched: Option<&HashSet<ProjectID>>, id: &ProjectID) -> bool {
+    watched.is_none_or(|watched| watched.contains(id))
+}
+
+/// The projects of `snapshot` in `watched`, and their tasks.
+fn watched_only(snapshot: &Snapshot, watched: Option<&HashSet<ProjectID>>) -> Snapshot {
+    Snapshot {
+        projects: snapshot
+            .projects
+            .iter()
+            .filter(|project| is_watched(watched, project.get_id()))
+            .cloned()
+            .collect(),
+        tasks: snapshot
+            .tasks
+            .iter()
+            .filter(|task| is_watched(watched, &task.project_id))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn events_between(before: &Snapshot, after: &Snapshot) -> Vec<WatchEvent> {
+    let changes = ChangeSet::between(before, after);
+    let projects = changes.projects.into_iter().map(|change| match change {
+        ProjectChange::Created(project) => WatchEvent::ProjectCreated(project),
+        ProjectChange::Updated {
+            before,
+            after,
+            changed_fields,
+        } => WatchEvent::ProjectUpdated {
+            before,
+            after,
+            changed_fields,
+        },
+        ProjectChange::Deleted(project) => WatchEvent::ProjectDeleted(project),
+    });
+    let tasks = changes.tasks.into_iter().map(|change| match change {
+        TaskChange::Created(task) => WatchEvent::TaskCreated(task),
+        TaskChange::Updated {
+            before,
+            after,
+            changed_fields,
+        } => WatchEvent::TaskUpdated {
+            before,
+            after,
+            changed_fields,
+        },
+        TaskChange::Completed { after, .. } => WatchEvent::TaskCompleted(after),
+        TaskChange::Deleted(task) => WatchEvent::TaskDeleted(task),
+    });
+    projects.chain(tasks).collect()
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use std::{path::Path, sync::Arc};
+
+    use super::*;
+    use crate::{testing::ScriptedTransport, AccessToken};
+
+    fn ticktick(transport: &Arc<ScriptedTransport>) -> TickTick {
+        TickTick::with_transport(
+            AccessToken {
+                value: "token".into(),
+                token_type: "bearer".into(),
+                expires_in: 3600,
+                scope: "tasks:read tasks:write".into(),
+            },
+            transport.clone(),
+        )
+    }
+
+    fn state_file(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("ticks-watch-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn saved_task_ids(path: &Path) -> Vec<String> {
+        let state: State = serde_json::from_slice(&std::fs::read(path).unwrap()).unwrap();
+        state
+            .snapshot
+            .tasks
+            .iter()
+            .map(|task| task.get_id().0.clone())
+            .collect()
+    }
+
+    fn projects(ids: &[&str]) -> serde_json::Value {
+        ids.iter()
+            .map(|id| serde_json::json!({ "id": id, "name": id }))
+            .collect()
+    }
+
+    fn data(project: &str, tasks: &[&str]) -> serde_json::Value {
+        let tasks: Vec<_> = tasks
+            .iter()
+            .map(|id| serde_json::json!({ "id": id, "projectId": project, "title": id }))
+            .collect();
+        serde_json::json!({ "tasks": tasks, "columns": [] })
+    }
+
+    #[tokio::test]
+    async fn server_error_keeps_missing_task() {
+        let path = state_file("server-error");
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .push_json(200, &projects(&["p1"]))
+            .push_json(200, &data("p1", &["t1"]))
+            .push_json(200, &projects(&["p1"]))
+            .push_json(200, &data("p1", &[]))
+            .push_json(503, &serde_json::json!({ "errorMessage": "Unavailable" }))
+            .push_json(200, &projects(&["p1"]))
+            .push_json(200, &data("p1", &["t1"]));
+        let mut watcher = Watcher::new(&ticktick(&transport)).state_file(&path);
+
+        assert_eq!(watcher.poll().await.unwrap(), Vec::new());
+        let result = watcher.poll().await;
+        assert!(matches!(
+            result,
+            Err(WatchError::ApiError(TickTickError::StatusError {
+                status: 503,
+                ..
+            }))
+        ));
+        assert_eq!(saved_task_ids(&path), ["t1"]);
+        assert_eq!(watcher.poll().await.unwrap(), Vec::new());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn changed_project_filter_is_not_reported() {
+        let path = state_file("filter");
+        let transport = Arc::new(ScriptedTransport::new());
+        transport
+            .push_json(200, &projects(&["p1", "p2"]))
+            .push_json(200, &data("p1", &["t1"]))
+            .push_json(200, &data("p2", &["t2"]));
+        let mut watcher = Watcher::new(&ticktick(&transport)).state_file(&path);
+        watcher.poll().await.unwrap();
+
+        // Narrowed: p2 and its task are no longer watched, not deleted.
+        transport
+            .push_json(200, &projects(&["p1", "p2"]))
+            .push_json(200, &data("p1", &["t1"]));
+        let mut watcher = Watcher::new(&ticktick(&transport))
+            .projects(vec![ProjectID("p1".into())])
+            .state_file(&path);
+        assert_eq!(watcher.poll().await.unwrap(), Vec::new());
+        assert_eq!(saved_task_ids(&path), ["t1"]);
+
+        // Widened: p2 and its task were already there, not created.
+        transport
+            .push_json(200, &projects(&["p1", "p2"]))
+            .push_json(200, &data("p1", &["t1"]))
+            .push_json(200, &data("p2", &["t2"]));
+        let mut watcher = Watcher::new(&ticktick(&transport)).state_file(&path);
+        assert_eq!(watcher.poll().await.unwrap(), Vec::new());
+
+        // Changes within watched projects are still reported.
+        transport
+            .push_json(200, &projects(&["p1", "p2"]))
+            .push_json(200, &data("p1", &["t1"]))
+            .push_json(200, &data("p2", &[]))
+            .push_json(404, &serde_json::json!({}));
+        assert!(matches!(
+            &watcher.poll().await.unwrap()[..],
+            [WatchEvent::TaskDeleted(task)] if task.get_id().0 == "t2"
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! Backup and restore tests against `FakeTickTick`.
+use ticks::{backup::Backup, projects::ProjectID, testing::FakeTickTick, TickTick};
+
+/// Add a "Groceries" project with one task, and one Inbox task.
+async fn populate(ticktick: &TickTick) -> ProjectID {
+    let project = ticktick
+        .projects()
+        .builder("Groceries")
+        .build_and_publish()
+        .await
+        .unwrap();
+    ticktick
+        .tasks()
+        .builder("Buy milk")
+        .project_id(project.get_id().clone())
+        .build_and_publish()
+        .await
+        .unwrap();
+    ticktick
+        .tasks()
+        .builder("Call Bob")
+        .build_and_publish()
+        .await
+        .unwrap();
+    project.get_id().clone()
+}
+
+#[tokio::test]
+async fn fetch_includes_inbox() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project_id = populate(&ticktick).await;
+
+    let backup = Backup::fetch(&ticktick).await.unwrap();
+    let projects: Vec<_> = backup
+        .projects
+        .iter()
+        .map(|backup| {
+            (
+                backup.project.get_id().0.as_str(),
+                backup.tasks[0].title.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        projects,
+        [(project_id.0.as_str(), "Buy milk"), ("inbox", "Call Bob")]
+    );
+}
+
+#[tokio::test]
+async fn restore_remaps_ids_in_another_account() {
+    // Fixed IDs, so they can't match the ones the target assigns by chance.
+    let source = FakeTickTick::start().await.unwrap();
+    let project_id = source.insert_project(
+        serde_json::from_value(serde_json::json!({ "id": "groceries", "name": "Groceries" }))
+            .unwrap(),
+    );
+    for (id, title, project_id) in [
+        ("milk", "Buy milk", "groceries"),
+        ("bob", "Call Bob", "inbox"),
+    ] {
+        source.insert_task(
+            serde_json::from_value(
+                serde_json::json!({ "id": id, "title": title, "projectId": project_id }),
+            )
+            .unwrap(),
+        );
+    }
+    let backup = Backup::fetch(&source.client()).await.unwrap();
+
+    let target = FakeTickTick::start().await.unwrap();
+    let report = backup.restore(&target.client()).await;
+    assert!(report.failures.is_empty(), "{:?}", report.failures);
+
+    let projects = target.projects();
+    let groceries = projects
+        .iter()
+        .find(|project| project.name == "Groceries")
+        .unwrap();
+    assert_ne!(*groceries.get_id(), project_id);
+    assert_eq!(report.project_ids[&project_id], *groceries.get_id());
+    assert_eq!(
+        report.project_ids[&ProjectID("inbox".into())],
+        ProjectID("inbox".into())
+    );
+
+    let tasks = target.tasks();
+    assert_eq!(tasks.len(), 2);
+    for backup in &backup.projects {
+        let old = &backup.tasks[0];
+        let restored = tasks
+            .iter()
+            .find(|task| *task.get_id() == report.task_ids[old.get_id()])
+            .unwrap();
+        assert_eq!(restored.title, old.title);
+        assert_eq!(
+            restored.project_id,
+            report.project_ids[backup.project.get_id()]
+        );
+    }
+}
+
+#[tokio::test]
+async fn restore_reuses_projects_and_skips_existing_tasks() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project_id = populate(&ticktick).await;
+    let backup = Backup::fetch(&ticktick).await.unwrap();
+    let milk = backup.projects[0].tasks[0].clone();
+    ticktick.tasks().delete(&milk).await.unwrap();
+
+    let report = backup.restore(&ticktick).await;
+    assert!(report.failures.is_empty(), "{:?}", report.failures);
+
+    assert_eq!(fake.projects().len(), 1);
+    assert_eq!(report.project_ids[&project_id], project_id);
+    let tasks = fake.tasks();
+    assert_eq!(tasks.len(), 2);
+    let inbox_task = &backup.projects[1].tasks[0];
+    assert_eq!(report.task_ids[inbox_task.get_id()], *inbox_task.get_id());
+    let restored = &report.task_ids[milk.get_id()];
+    assert_ne!(restored, milk.get_id());
+    let restored = tasks.iter().find(|task| task.get_id() == restored).unwrap();
+    assert_eq!(restored.title, "Buy milk");
+    assert_eq!(restored.project_id, project_id);
+}
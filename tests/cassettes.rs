@@ -0,0 +1,85 @@
+//! Replays recorded TickTick payloads to catch regressions in response parsing.
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use ticks::{
+    projects::{ProjectID, ProjectKind, ProjectViewMode},
+    tasks::{SubtaskStatus, TaskPriority},
+    testing::CassetteTransport,
+    AccessToken, TickTick,
+};
+
+fn replay(name: &str) -> TickTick {
+    let path = format!(
+        "{}/tests/cassettes/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let transport = CassetteTransport::replay(path).unwrap();
+    let token = AccessToken {
+        value: "replayed".into(),
+        token_type: "bearer".into(),
+        expires_in: 0,
+        scope: String::new(),
+    };
+    TickTick::with_transport(token, Arc::new(transport))
+}
+
+#[tokio::test]
+async fn parses_projects() {
+    let ticktick = replay("project_data");
+
+    let projects = ticktick.get_all_projects().await.unwrap();
+
+    assert_eq!(projects.len(), 2);
+    let work = &projects[0];
+    assert_eq!(work.name, "Work");
+    assert_eq!(work.view_mode, ProjectViewMode::Kanban);
+    assert_eq!(work.kind, ProjectKind::Task);
+    assert_eq!(work.etag(), Some("x1t2o9p3"));
+    assert_eq!(
+        work.modified_time(),
+        Some(Utc.with_ymd_and_hms(2026, 10, 17, 8, 12, 45).unwrap())
+    );
+    assert_eq!(projects[1].kind, ProjectKind::Note);
+}
+
+#[tokio::test]
+async fn parses_project_data() {
+    let ticktick = replay("project_data");
+
+    let data = ticktick
+        .get_project_data(&ProjectID("6226ff9877acee87727f6bca".into()))
+        .await
+        .unwrap();
+
+    assert_eq!(data.columns.len(), 2);
+    assert_eq!(data.tasks.len(), 2);
+    let report = &data.tasks[0];
+    assert_eq!(report.title, "Quarterly report");
+    assert!(report.is_all_day);
+    assert_eq!(report.priority, TaskPriority::High);
+    assert_eq!(report.tags, ["finance"]);
+    assert_eq!(report.repeat_flag, "RRULE:FREQ=MONTHLY;INTERVAL=3");
+    assert_eq!(
+        report.due_date,
+        Utc.with_ymd_and_hms(2026, 10, 19, 16, 0, 0).unwrap()
+    );
+    assert_eq!(report.column_id(), Some(data.columns[0].get_id().clone()));
+    assert_eq!(report.kind(), Some("CHECKLIST"));
+    // Timestamps with a non-UTC offset.
+    assert_eq!(
+        report.modified_time(),
+        Some(Utc.with_ymd_and_hms(2026, 10, 18, 3, 2, 33).unwrap())
+    );
+    assert_eq!(report.subtasks.len(), 2);
+    assert_eq!(report.subtasks[0].status, SubtaskStatus::Completed);
+    assert_eq!(
+        report.subtasks[0].completed_time,
+        Utc.with_ymd_and_hms(2026, 10, 18, 2, 15, 0).unwrap()
+    );
+    // Unmodeled fields survive a round trip.
+    let json = serde_json::to_value(report).unwrap();
+    assert_eq!(json["etag"], "q9w8e7r6");
+    assert_eq!(json["columnId"], "6226ff9877acee87727f6bd0");
+}
@@ -0,0 +1,168 @@
+//! End-to-end tests of the client against `FakeTickTick`.
+use ticks::{
+    projects::Project,
+    tasks::{Subtask, Task, TaskPriority, TaskStatus},
+    testing::FakeTickTick,
+    TickTickError,
+};
+
+#[tokio::test]
+async fn build_and_publish_creates_task() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project = ticktick
+        .projects()
+        .builder("Groceries")
+        .build_and_publish()
+        .await
+        .unwrap();
+
+    let task = ticktick
+        .tasks()
+        .builder("Buy milk")
+        .project_id(project.get_id().clone())
+        .priority(TaskPriority::High)
+        .tags(vec!["shopping".into()])
+        .subtasks(vec![Subtask::new("Oat"), Subtask::new("Soy")])
+        .build_and_publish()
+        .await
+        .unwrap();
+
+    assert!(!task.get_id().is_empty());
+    assert_eq!(task.project_id, *project.get_id());
+    let stored = fake.tasks();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].title, "Buy milk");
+    assert_eq!(stored[0].priority, TaskPriority::High);
+    assert_eq!(stored[0].tags, ["shopping"]);
+    assert_eq!(stored[0].subtasks.len(), 2);
+
+    let data = project.get_data().await.unwrap();
+    assert_eq!(data.tasks, vec![task.into_inner()]);
+}
+
+#[tokio::test]
+async fn publish_changes_and_complete() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let mut task = ticktick
+        .tasks()
+        .builder("Draft")
+        .build_and_publish()
+        .await
+        .unwrap();
+
+    task.title = "Final".into();
+    task.publish_changes().await.unwrap();
+    assert_eq!(fake.tasks()[0].title, "Final");
+
+    task.complete().await.unwrap();
+    assert_eq!(task.status, TaskStatus::Completed);
+    assert_eq!(fake.tasks()[0].status, TaskStatus::Completed);
+}
+
+#[tokio::test]
+async fn delete_removes_task() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project = ticktick
+        .projects()
+        .builder("Work")
+        .build_and_publish()
+        .await
+        .unwrap();
+    let task = ticktick
+        .tasks()
+        .builder("Old report")
+        .project_id(project.get_id().clone())
+        .build_and_publish()
+        .await
+        .unwrap();
+    let (project_id, task_id) = (task.project_id.clone(), task.get_id().clone());
+
+    task.delete().await.unwrap();
+
+    assert!(fake.tasks().is_empty());
+    let error = ticktick
+        .tasks()
+        .get(&project_id, &task_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        TickTickError::StatusError { status: 404, .. }
+    ));
+}
+
+#[tokio::test]
+async fn delete_removes_project() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project = ticktick
+        .projects()
+        .builder("Temporary")
+        .build_and_publish()
+        .await
+        .unwrap();
+    assert_eq!(ticktick.get_all_projects().await.unwrap().len(), 1);
+
+    project.delete().await.unwrap();
+
+    assert!(fake.projects().is_empty());
+}
+
+#[tokio::test]
+async fn fetched_models_are_bound() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let ticktick = fake.client();
+    let project = ticktick
+        .projects()
+        .builder("Chores")
+        .build_and_publish()
+        .await
+        .unwrap();
+    let task = ticktick
+        .tasks()
+        .builder("Dishes")
+        .project_id(project.get_id().clone())
+        .build_and_publish()
+        .await
+        .unwrap();
+
+    let mut fetched = Task::get(&ticktick, &task.project_id, task.get_id())
+        .await
+        .unwrap();
+    fetched.content = "After dinner".into();
+    fetched.publish_changes().await.unwrap();
+    assert_eq!(fake.tasks()[0].content, "After dinner");
+
+    let projects = Project::get_all(&ticktick).await.unwrap();
+    let mut tasks = projects[0].get_tasks().await.unwrap();
+    assert_eq!(tasks.len(), 1);
+    tasks[0].complete().await.unwrap();
+    assert_eq!(fake.tasks()[0].status, TaskStatus::Completed);
+
+    let unbound = ticktick
+        .tasks()
+        .get(&task.project_id, task.get_id())
+        .await
+        .unwrap();
+    ticktick.tasks().bind(unbound).delete().await.unwrap();
+    assert!(fake.tasks().is_empty());
+}
+
+#[tokio::test]
+async fn rejects_other_tokens() {
+    let fake = FakeTickTick::start().await.unwrap();
+    let mut token = fake.access_token();
+    token.value = "wrong".into();
+    let ticktick = ticks::TickTick::new(token)
+        .unwrap()
+        .with_base_url(&fake.base_url());
+
+    let error = ticktick.get_all_projects().await.unwrap_err();
+    assert!(matches!(
+        error,
+        TickTickError::StatusError { status: 401, .. }
+    ));
+}